@@ -0,0 +1,195 @@
+//! Delta queries for multiway joins of relations.
+//!
+//! A delta query maintains a multiway join by responding to the changes in each relation
+//! independently. For each relation there is a "delta" dataflow that starts from the
+//! changes to that relation, and extends each change by the other relations one variable
+//! at a time using `PrefixExtender` implementations. Relations that precede the changing
+//! relation are observed as of the time of the change, and relations that follow it are
+//! observed just before the time of the change, using the `AltNeu` timestamp. This ensures
+//! that simultaneous changes to multiple relations are each accounted for exactly once,
+//! and that the concatenation of the delta dataflows is the change in the join.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use timely::dataflow::Scope;
+
+use differential_dataflow::{ExchangeData, Collection};
+use differential_dataflow::difference::{Monoid, Multiply};
+use differential_dataflow::lattice::Lattice;
+
+use crate::{CollectionIndex, PrefixExtender, ProposeExtensionMethod};
+use crate::altneu::AltNeu;
+
+/// A multiway join of relations, rendered as a delta query.
+///
+/// Each relation is a collection of tuples, and names for each of its columns a variable.
+/// Columns of different relations that name the same variable must have equal values, and
+/// the join produces tuples of values for all variables, in the order of the variables.
+///
+/// # Examples
+///
+/// ```
+/// use differential_dataflow::input::Input;
+/// use differential_dogs3::delta_query::DeltaQuery;
+///
+/// ::timely::example(|scope| {
+///
+///     let edges = scope.new_collection_from(vec![vec![1, 2], vec![2, 3], vec![1, 3]]).1;
+///
+///     // triangles `(a, b, c)` with edges `(a, b)`, `(b, c)`, and `(a, c)`.
+///     let triangles =
+///     DeltaQuery::new()
+///         .relation(&edges, vec![0, 1])
+///         .relation(&edges, vec![1, 2])
+///         .relation(&edges, vec![0, 2])
+///         .render();
+///
+///     triangles.assert_eq(&scope.new_collection_from(vec![vec![1, 2, 3]]).1);
+/// });
+/// ```
+pub struct DeltaQuery<G: Scope, D: ExchangeData, R: Monoid> {
+    relations: Vec<(Collection<G, Vec<D>, R>, Vec<usize>)>,
+}
+
+impl<G, D, R> DeltaQuery<G, D, R>
+where
+    G: Scope,
+    G::Timestamp: Lattice+ExchangeData,
+    D: ExchangeData+Hash+Default,
+    R: Monoid+Multiply<Output = R>+ExchangeData,
+{
+    /// Creates a query without relations.
+    pub fn new() -> Self {
+        DeltaQuery { relations: Vec::new() }
+    }
+
+    /// Adds a relation to the join, naming the variable of each of its columns.
+    ///
+    /// The variables of a relation must be distinct.
+    pub fn relation(mut self, collection: &Collection<G, Vec<D>, R>, variables: Vec<usize>) -> Self {
+        for (index, variable) in variables.iter().enumerate() {
+            assert!(!variables[.. index].contains(variable), "DeltaQuery: variable {} repeated in relation {:?}", variable, variables);
+        }
+        self.relations.push((collection.clone(), variables));
+        self
+    }
+
+    /// Plans the extension of changes to relation `delta`.
+    ///
+    /// Each step binds a variable, using each unused relation whose other variables are bound.
+    /// The driver prefers variables that can be bound by the most relations, and then the least
+    /// variables. It panics if the unused relations cannot all be used in this way.
+    fn plan(&self, delta: usize) -> Vec<(usize, Vec<usize>)> {
+
+        let mut bound = self.relations[delta].1.clone();
+        let mut used = vec![false; self.relations.len()];
+        used[delta] = true;
+
+        let mut plan = Vec::new();
+        while used.iter().any(|u| !u) {
+
+            let mut best: Option<(usize, Vec<usize>)> = None;
+            for (_relation, variables) in self.relations.iter() {
+                for variable in variables.iter().filter(|v| !bound.contains(v)) {
+                    let extenders =
+                    (0 .. self.relations.len())
+                        .filter(|&other| !used[other])
+                        .filter(|&other| self.relations[other].1.contains(variable))
+                        .filter(|&other| self.relations[other].1.iter().all(|v| v == variable || bound.contains(v)))
+                        .collect::<Vec<_>>();
+
+                    let better = match &best {
+                        None => true,
+                        Some((best_var, best_ext)) => (extenders.len(), std::cmp::Reverse(*variable)) > (best_ext.len(), std::cmp::Reverse(*best_var)),
+                    };
+                    if !extenders.is_empty() && better {
+                        best = Some((*variable, extenders));
+                    }
+                }
+            }
+
+            let (variable, extenders) = best.unwrap_or_else(|| {
+                panic!("DeltaQuery: cannot extend bindings {:?} of changes to relation {}; each step must bind a variable with some relation whose other variables are bound", bound, delta)
+            });
+            for other in extenders.iter() {
+                used[*other] = true;
+            }
+            bound.push(variable);
+            plan.push((variable, extenders));
+        }
+
+        plan
+    }
+
+    /// Renders the join, producing tuples of values for all variables.
+    pub fn render(&self) -> Collection<G, Vec<D>, R> {
+
+        assert!(!self.relations.is_empty(), "DeltaQuery: no relations to join");
+        let arity = self.relations.iter().flat_map(|(_, variables)| variables.iter()).max().map(|v| v + 1).unwrap_or(0);
+
+        let plans = (0 .. self.relations.len()).map(|delta| self.plan(delta)).collect::<Vec<_>>();
+
+        let mut scope = self.relations[0].0.scope();
+        scope.scoped::<AltNeu<G::Timestamp>,_,_>("DeltaQuery", |inner| {
+
+            // Indices by relation, key columns, value column, and whether they are delayed to `neu`.
+            let mut indices = HashMap::new();
+
+            let mut results = Vec::new();
+            for (delta, plan) in plans.into_iter().enumerate() {
+
+                let mut bound = self.relations[delta].1.clone();
+                let mut prefixes = self.relations[delta].0.enter(inner);
+
+                for (variable, others) in plan {
+
+                    let mut extenders =
+                    others
+                        .into_iter()
+                        .map(|other| {
+                            let variables = &self.relations[other].1;
+                            let key_columns = (0 .. variables.len()).filter(|c| variables[*c] != variable).collect::<Vec<_>>();
+                            let val_column = variables.iter().position(|v| *v == variable).unwrap();
+                            // Relations after the delta relation do not yet reflect the changes at a time.
+                            let neu = other > delta;
+
+                            let index =
+                            indices
+                                .entry((other, key_columns.clone(), val_column, neu))
+                                .or_insert_with(|| {
+                                    let relation = self.relations[other].0.enter(inner);
+                                    let relation = if neu { relation.delay(|time| AltNeu::neu(time.time.clone())) } else { relation };
+                                    let key_columns = key_columns.clone();
+                                    CollectionIndex::index(&relation.map(move |tuple| {
+                                        (key_columns.iter().map(|c| tuple[*c].clone()).collect::<Vec<_>>(), tuple[val_column].clone())
+                                    }))
+                                });
+
+                            let positions = key_columns.iter().map(|c| bound.iter().position(|v| *v == variables[*c]).unwrap()).collect::<Vec<_>>();
+                            index.extend_using(move |prefix: &Vec<D>| positions.iter().map(|p| prefix[*p].clone()).collect::<Vec<_>>())
+                        })
+                        .collect::<Vec<_>>();
+
+                    let mut extenders =
+                    extenders
+                        .iter_mut()
+                        .map(|extender| extender as &mut dyn PrefixExtender<_, R, Prefix=Vec<D>, Extension=D>)
+                        .collect::<Vec<_>>();
+
+                    prefixes =
+                    prefixes
+                        .extend(&mut extenders[..])
+                        .map(|(mut prefix, value)| { prefix.push(value); prefix });
+
+                    bound.push(variable);
+                }
+
+                let positions = (0 .. arity).map(|v| bound.iter().position(|b| *b == v).expect("DeltaQuery: variable not bound by any relation")).collect::<Vec<_>>();
+                results.push(prefixes.map(move |prefix| positions.iter().map(|p| prefix[*p].clone()).collect()));
+            }
+
+            differential_dataflow::collection::concatenate(inner, results).leave()
+        })
+    }
+}
@@ -14,6 +14,7 @@ use differential_dataflow::operators::arrange::{ArrangeBySelf, ArrangeByKey};
 
 pub mod altneu;
 pub mod calculus;
+pub mod delta_query;
 pub mod operators;
 
 /// A type capable of extending a stream of prefixes.
@@ -105,10 +106,10 @@ where
     T: Lattice+ExchangeData+Timestamp,
     R: Monoid+Multiply<Output = R>+ExchangeData,
 {
-    /// A trace of type (K, ()), used to count extensions for each prefix.
+    /// A trace of type (K, ()), used to restrict prefixes to the keys present.
     count_trace: TraceKeyHandle<K, T, isize>,
 
-    /// A trace of type (K, V), used to propose extensions for each prefix.
+    /// A trace of type (K, V), used to count and propose extensions for each prefix.
     propose_trace: TraceValHandle<K, V, T, R>,
 
     /// A trace of type ((K, V), ()), used to validate proposed extensions.
@@ -140,7 +141,7 @@ where
 {
 
     pub fn index<G: Scope<Timestamp = T>>(collection: &Collection<G, (K, V), R>) -> Self {
-        // We need to count the number of (k, v) pairs and not rely on the given Monoid R and its binary addition operation.
+        // The distinct keys are arranged with `isize` differences rather than with the given Monoid R.
         // counts and validate can share the base arrangement
         let arranged = collection.arrange_by_self();
         let counts = arranged
@@ -166,6 +167,126 @@ where
     }
 }
 
+impl<K, V, T, R> CollectionIndex<K, V, T, R>
+where
+    K: ExchangeData+Hash+Default,
+    V: ExchangeData+Hash,
+    T: Lattice+ExchangeData+Timestamp,
+    R: Monoid+Multiply<Output = R>+ExchangeData,
+{
+    /// Indexes `collection` by the key columns projected from `D` by `columns`.
+    ///
+    /// The resulting index should be used through `extend_using_multi` and `validate_prefixes_using`,
+    /// which encode keys in the same way.
+    pub fn index_multi<G, D, C>(collection: &Collection<G, (D, V), R>, columns: C) -> Self
+    where
+        G: Scope<Timestamp = T>,
+        D: ExchangeData,
+        C: KeyColumns<D, Key = K>+'static,
+    {
+        CollectionIndex::index(&collection.map(move |(data, val)| (columns.key(&data), val)))
+    }
+    /// Extends prefixes using the key columns projected from `P` by `columns`.
+    ///
+    /// The functions should project the columns corresponding to those used to build the index, in the same order.
+    pub fn extend_using_multi<P, C>(&self, columns: C) -> CollectionExtender<K, V, T, R, P, impl Fn(&P)->K+Clone>
+    where
+        C: KeyColumns<P, Key = K>+Clone,
+    {
+        self.extend_using(move |prefix: &P| columns.key(prefix))
+    }
+    /// Restricts `prefixes` to those whose columns projected by `columns` match the leading key columns of some
+    /// indexed record.
+    ///
+    /// The functions should project the columns corresponding to the first few used to build the index, in the
+    /// same order; all key columns may be used, in which case prefixes are restricted to keys present in the index.
+    /// As with `validate`, prefixes are matched against the index as of their times, and later changes to the index
+    /// do not revisit them. Each call arranges the distinct leading key columns anew.
+    pub fn validate_prefixes_using<G, P, C>(&self, prefixes: &Collection<G, P, R>, columns: C) -> Collection<G, P, R>
+    where
+        G: Scope<Timestamp = T>,
+        P: ExchangeData,
+        C: KeyColumns<P>+Clone+'static,
+        C::Key: Default,
+        K: KeyPrefix<C::Key>,
+    {
+        let leading =
+        self.count_trace
+            .clone()
+            .import(&prefixes.scope())
+            .as_collection(|key, &()| key.prefix())
+            .distinct()
+            .arrange_by_self();
+
+        operators::lookup_map(
+            prefixes,
+            leading,
+            move |prefix: &P, key: &mut C::Key| { *key = columns.key(prefix); },
+            |prefix, r, _, _| (prefix.clone(), r.clone()),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+    }
+}
+
+/// Projections of several key columns from records of type `X`, and their encoding as one key.
+///
+/// Implemented for tuples of up to four functions, each projecting one column, whose types may differ.
+/// A key is the tuple of its projected columns. The construction of, extension using, and validation
+/// against indexes with composite keys all use this encoding, so that `count`, `propose`, and `validate`
+/// agree on keys.
+pub trait KeyColumns<X> {
+    /// The encoding of the key columns.
+    type Key: ExchangeData+Hash;
+    /// Projects the key columns from `item`.
+    fn key(&self, item: &X) -> Self::Key;
+}
+
+/// Keys whose leading columns form a key of type `Prefix`.
+///
+/// Implemented for tuples of up to four columns, and each of their non-empty prefixes.
+pub trait KeyPrefix<Prefix> {
+    /// The leading columns of the key.
+    fn prefix(&self) -> Prefix;
+}
+
+macro_rules! implement_key_columns {
+    ($($func:ident $col:ident $index:tt),+) => {
+        impl<X, $($func, $col),+> KeyColumns<X> for ($($func,)+)
+        where
+            $($func: Fn(&X)->$col, $col: ExchangeData+Hash,)+
+        {
+            type Key = ($($col,)+);
+            fn key(&self, item: &X) -> Self::Key { ($((self.$index)(item),)+) }
+        }
+    }
+}
+
+implement_key_columns!(F0 A0 0);
+implement_key_columns!(F0 A0 0, F1 A1 1);
+implement_key_columns!(F0 A0 0, F1 A1 1, F2 A2 2);
+implement_key_columns!(F0 A0 0, F1 A1 1, F2 A2 2, F3 A3 3);
+
+macro_rules! implement_key_prefix {
+    (($($col:ident),+) => ($($pre:ident $index:tt),+)) => {
+        impl<$($col: Clone),+> KeyPrefix<($($pre,)+)> for ($($col,)+) {
+            fn prefix(&self) -> ($($pre,)+) { ($(self.$index.clone(),)+) }
+        }
+    }
+}
+
+implement_key_prefix!((A0) => (A0 0));
+implement_key_prefix!((A0, A1) => (A0 0));
+implement_key_prefix!((A0, A1) => (A0 0, A1 1));
+implement_key_prefix!((A0, A1, A2) => (A0 0));
+implement_key_prefix!((A0, A1, A2) => (A0 0, A1 1));
+implement_key_prefix!((A0, A1, A2) => (A0 0, A1 1, A2 2));
+implement_key_prefix!((A0, A1, A2, A3) => (A0 0));
+implement_key_prefix!((A0, A1, A2, A3) => (A0 0, A1 1));
+implement_key_prefix!((A0, A1, A2, A3) => (A0 0, A1 1, A2 2));
+implement_key_prefix!((A0, A1, A2, A3) => (A0 0, A1 1, A2 2, A3 3));
+
 pub struct CollectionExtender<K, V, T, R, P, F>
 where
     K: ExchangeData,
@@ -179,6 +300,22 @@ where
     key_selector: F,
 }
 
+impl<K, V, T, R, P, F> CollectionExtender<K, V, T, R, P, F>
+where
+    K: ExchangeData+Hash+Default,
+    V: ExchangeData+Hash+Default,
+    P: ExchangeData,
+    T: Lattice+ExchangeData+Timestamp,
+    R: Monoid+Multiply<Output = R>+ExchangeData,
+    F: Fn(&P)->K+Clone+'static,
+{
+    /// As `propose`, but accompanying each extension with the number of extensions of its prefix.
+    pub fn propose_with_counts<G: Scope<Timestamp = T>>(&mut self, prefixes: &Collection<G, P, R>) -> Collection<G, (P, V, usize), R> {
+        let propose = self.indices.propose_trace.import(&prefixes.scope());
+        operators::propose::propose_with_counts(prefixes, propose, self.key_selector.clone())
+    }
+}
+
 impl<G, K, V, R, P, F> PrefixExtender<G, R> for CollectionExtender<K, V, G::Timestamp, R, P, F>
 where
     G: Scope,
@@ -193,7 +330,7 @@ where
     type Extension = V;
 
     fn count(&mut self, prefixes: &Collection<G, (P, usize, usize), R>, index: usize) -> Collection<G, (P, usize, usize), R> {
-        let counts = self.indices.count_trace.import(&prefixes.scope());
+        let counts = self.indices.propose_trace.import(&prefixes.scope());
         operators::count::count(prefixes, counts, self.key_selector.clone(), index)
     }
 
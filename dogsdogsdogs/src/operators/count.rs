@@ -1,17 +1,23 @@
 use timely::dataflow::Scope;
 
 use differential_dataflow::{ExchangeData, Collection, Hashable};
-use differential_dataflow::difference::{Semigroup, Monoid, Multiply};
+use differential_dataflow::difference::{Monoid, Multiply};
 use differential_dataflow::operators::arrange::Arranged;
-use differential_dataflow::trace::TraceReader;
+use differential_dataflow::trace::{Cursor, TraceReader};
 use differential_dataflow::trace::cursor::IntoOwned;
 
 /// Reports a number of extensions to a stream of prefixes.
 ///
 /// This method takes as input a stream of `(prefix, count, index)` triples.
-/// For each triple, it extracts a key using `key_selector`, and finds the
-/// associated count in `arrangement`. If the found count is less than `count`,
-/// the `count` and `index` fields are overwritten with their new values.
+/// For each triple, it extracts a key using `key_selector`, and counts the
+/// values associated with the key in `arrangement`. If the found count is
+/// less than `count`, the `count` and `index` fields are overwritten with
+/// their new values.
+///
+/// Values are counted with `Cursor::count_vals`, without visiting their updates, and so
+/// the count includes values whose updates are at later times or accumulate to zero, and
+/// may count a value once for each batch that contains it. The count is only used to choose
+/// among extenders, and the extensions subsequently proposed and validated are exact.
 pub fn count<G, Tr, K, R, F, P>(
     prefixes: &Collection<G, (P, usize, usize), R>,
     arrangement: Arranged<G, Tr>,
@@ -20,22 +26,22 @@ pub fn count<G, Tr, K, R, F, P>(
 ) -> Collection<G, (P, usize, usize), R>
 where
     G: Scope<Timestamp=Tr::Time>,
-    Tr: TraceReader<Diff=isize>+Clone+'static,
+    Tr: TraceReader+Clone+'static,
     for<'a> Tr::Key<'a>: IntoOwned<'a, Owned = K>,
-    for<'a> Tr::Diff : Semigroup<Tr::DiffGat<'a>>,
     K: Hashable + Ord + Default + 'static,
     R: Monoid+Multiply<Output = R>+ExchangeData,
     F: Fn(&P)->K+Clone+'static,
     P: ExchangeData,
 {
-    crate::operators::lookup_map(
+    crate::operators::lookup_core(
         prefixes,
         arrangement,
         move |p: &(P,usize,usize), k: &mut K| { *k = key_selector(&p.0); },
-        move |(p,c,i), r, _, s| {
-            let s = *s as usize;
-            if *c < s { ((p.clone(), *c, *i), r.clone()) }
-            else      { ((p.clone(), s, index), r.clone()) }
+        "Count",
+        move |(p,c,i), _time, r, cursor, storage, output| {
+            let s = cursor.count_vals(storage);
+            if *c < s { output.push(((p.clone(), *c, *i), r.clone())); }
+            else      { output.push(((p.clone(), s, index), r.clone())); }
         },
         Default::default(),
         Default::default(),
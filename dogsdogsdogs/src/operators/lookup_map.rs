@@ -19,7 +19,7 @@ use differential_dataflow::trace::cursor::IntoOwned;
 /// and values associated with the key in `arrangement`.
 pub fn lookup_map<G, D, K, R, Tr, F, DOut, ROut, S>(
     prefixes: &Collection<G, D, R>,
-    mut arrangement: Arranged<G, Tr>,
+    arrangement: Arranged<G, Tr>,
     key_selector: F,
     mut output_func: S,
     supplied_key0: K,
@@ -39,6 +39,116 @@ where
     DOut: Clone+'static,
     ROut: Monoid + 'static,
     S: FnMut(&D, &R, Tr::Val<'_>, &Tr::Diff)->(DOut, ROut)+'static,
+{
+    lookup_core(
+        prefixes,
+        arrangement,
+        key_selector,
+        "LookupMap",
+        move |prefix, time, diff, cursor, storage, output| {
+            while let Some(value) = cursor.get_val(storage) {
+                let mut count = Tr::Diff::zero();
+                cursor.map_times(storage, |t, d| {
+                    if t.into_owned().less_equal(time) { count.plus_equals(&d); }
+                });
+                if !count.is_zero() {
+                    output.push(output_func(prefix, diff, value, &count));
+                }
+                cursor.step_val(storage);
+            }
+        },
+        supplied_key0,
+        supplied_key1,
+        supplied_key2,
+    )
+}
+
+/// Proposes extensions to a stream of prefixes, presenting all values for a key at once.
+///
+/// As `lookup_map`, but `output_func` is called once for each prefix whose key is present,
+/// with all values associated with the key at the time of the prefix and their accumulated
+/// counts. Values whose counts accumulate to zero are not presented. This allows the output
+/// for each value to depend on the other values, gathered in the same pass over the cursor.
+pub fn lookup_map_grouped<G, D, K, R, Tr, F, DOut, ROut, S>(
+    prefixes: &Collection<G, D, R>,
+    arrangement: Arranged<G, Tr>,
+    key_selector: F,
+    mut output_func: S,
+    supplied_key0: K,
+    supplied_key1: K,
+    supplied_key2: K,
+) -> Collection<G, DOut, ROut>
+where
+    G: Scope<Timestamp=Tr::Time>,
+    Tr: TraceReader+Clone+'static,
+    for<'a> Tr::Key<'a>: IntoOwned<'a, Owned = K>,
+    for<'a> Tr::Diff : Semigroup<Tr::DiffGat<'a>>,
+    K: Hashable + Ord + 'static,
+    Tr::Diff: Monoid+ExchangeData,
+    F: FnMut(&D, &mut K)+Clone+'static,
+    D: ExchangeData,
+    R: ExchangeData+Monoid,
+    DOut: Clone+'static,
+    ROut: Monoid + 'static,
+    S: for<'a> FnMut(&D, &R, &[(Tr::Val<'a>, Tr::Diff)], &mut Vec<(DOut, ROut)>)+'static,
+{
+    lookup_core(
+        prefixes,
+        arrangement,
+        key_selector,
+        "LookupMapGrouped",
+        move |prefix, time, diff, cursor, storage, output| {
+            let mut values = Vec::new();
+            while let Some(value) = cursor.get_val(storage) {
+                let mut count = Tr::Diff::zero();
+                cursor.map_times(storage, |t, d| {
+                    if t.into_owned().less_equal(time) { count.plus_equals(&d); }
+                });
+                if !count.is_zero() {
+                    values.push((value, count));
+                }
+                cursor.step_val(storage);
+            }
+            if !values.is_empty() {
+                output_func(prefix, diff, &values[..], output);
+            }
+        },
+        supplied_key0,
+        supplied_key1,
+        supplied_key2,
+    )
+}
+
+/// Looks up the key of each prefix in an arrangement, as of the time of the prefix.
+///
+/// This is the operator underlying `lookup_map` and `lookup_map_grouped`. Prefixes are exchanged by
+/// the key `key_selector` extracts, and held until `arrangement` is complete through their times.
+/// For each prefix whose key is present, `lookup` is called with the prefix, its time and difference,
+/// and a cursor positioned at the first value of the key, and pushes outputs to the supplied vector.
+/// The cursor reflects all updates the arrangement has received, and `lookup` must itself restrict
+/// its attention to updates at times less or equal to that of the prefix. Prefixes whose key is absent
+/// produce no output, and outputs with zero differences are discarded.
+pub fn lookup_core<G, D, K, R, Tr, F, DOut, ROut, L>(
+    prefixes: &Collection<G, D, R>,
+    mut arrangement: Arranged<G, Tr>,
+    key_selector: F,
+    name: &str,
+    mut lookup: L,
+    supplied_key0: K,
+    supplied_key1: K,
+    supplied_key2: K,
+) -> Collection<G, DOut, ROut>
+where
+    G: Scope<Timestamp=Tr::Time>,
+    Tr: TraceReader+Clone+'static,
+    for<'a> Tr::Key<'a>: IntoOwned<'a, Owned = K>,
+    K: Hashable + Ord + 'static,
+    F: FnMut(&D, &mut K)+Clone+'static,
+    D: ExchangeData,
+    R: ExchangeData+Monoid,
+    DOut: Clone+'static,
+    ROut: Monoid + 'static,
+    L: FnMut(&D, &G::Timestamp, &R, &mut Tr::Cursor, &Tr::Storage, &mut Vec<(DOut, ROut)>)+'static,
 {
     // No need to block physical merging for this operator.
     arrangement.trace.set_physical_compaction(Antichain::new().borrow());
@@ -58,7 +168,9 @@ where
     let mut key1: K = supplied_key1;
     let mut key2: K = supplied_key2;
 
-    prefixes.inner.binary_frontier(&propose_stream, exchange, Pipeline, "LookupMap", move |_,_| move |input1, input2, output| {
+    let mut results = Vec::new();
+
+    prefixes.inner.binary_frontier(&propose_stream, exchange, Pipeline, name, move |_,_| move |input1, input2, output| {
 
         // drain the first input, stashing requests.
         input1.for_each(|capability, data| {
@@ -93,23 +205,15 @@ where
                     for &mut (ref prefix, ref time, ref mut diff) in prefixes.iter_mut() {
                         if !input2.frontier.less_equal(time) {
                             logic2(prefix, &mut key1);
-                            use differential_dataflow::trace::cursor::IntoOwned;
                             cursor.seek_key(&storage, IntoOwned::borrow_as(&key1));
                             if cursor.get_key(&storage) == Some(IntoOwned::borrow_as(&key1)) {
-                                while let Some(value) = cursor.get_val(&storage) {
-                                    let mut count = Tr::Diff::zero();
-                                    cursor.map_times(&storage, |t, d| {
-                                        if t.into_owned().less_equal(time) { count.plus_equals(&d); }
-                                    });
-                                    if !count.is_zero() {
-                                        let (dout, rout) = output_func(prefix, diff, value, &count);
-                                        if !rout.is_zero() {
-                                            session.give((dout, time.clone(), rout));
-                                        }
+                                lookup(prefix, time, diff, &mut cursor, &storage, &mut results);
+                                cursor.rewind_vals(&storage);
+                                for (dout, rout) in results.drain(..) {
+                                    if !rout.is_zero() {
+                                        session.give((dout, time.clone(), rout));
                                     }
-                                    cursor.step_val(&storage);
                                 }
-                                cursor.rewind_vals(&storage);
                             }
                             *diff = R::zero();
                         }
@@ -6,7 +6,7 @@ pub mod propose;
 pub mod validate;
 
 pub use self::half_join::half_join;
-pub use self::lookup_map::lookup_map;
+pub use self::lookup_map::{lookup_map, lookup_map_grouped, lookup_core};
 pub use self::count::count;
-pub use self::propose::{propose, propose_distinct};
+pub use self::propose::{propose, propose_distinct, propose_with_counts};
 pub use self::validate::validate;
\ No newline at end of file
@@ -42,6 +42,45 @@ where
     )
 }
 
+/// Proposes extensions to a prefix stream, each with the number of extensions of its prefix.
+///
+/// As `propose`, but each proposal `(prefix, value)` is accompanied by the number of distinct
+/// values proposed for `prefix`. The values and their number are determined in the same pass
+/// over `arrangement`, and so reflect the same restriction to times less or equal to that of
+/// the prefix update.
+pub fn propose_with_counts<G, Tr, K, F, P, V>(
+    prefixes: &Collection<G, P, Tr::Diff>,
+    arrangement: Arranged<G, Tr>,
+    key_selector: F,
+) -> Collection<G, (P, V, usize), Tr::Diff>
+where
+    G: Scope<Timestamp=Tr::Time>,
+    Tr: TraceReader+Clone+'static,
+    for<'a> Tr::Key<'a> : IntoOwned<'a, Owned = K>,
+    K: Hashable + Default + Ord + 'static,
+    Tr::Diff: Monoid+Multiply<Output = Tr::Diff>+ExchangeData,
+    for<'a> Tr::Diff : Semigroup<Tr::DiffGat<'a>>,
+    F: Fn(&P)->K+Clone+'static,
+    P: ExchangeData,
+    V: Clone + 'static,
+    for<'a> Tr::Val<'a> : IntoOwned<'a, Owned = V>,
+{
+    crate::operators::lookup_map_grouped(
+        prefixes,
+        arrangement,
+        move |p: &P, k: &mut K | { *k = key_selector(p); },
+        move |prefix, diff, values, output| {
+            let count = values.len();
+            for &(value, ref sum) in values.iter() {
+                output.push(((prefix.clone(), value.into_owned(), count), diff.clone().multiply(sum)));
+            }
+        },
+        Default::default(),
+        Default::default(),
+        Default::default(),
+    )
+}
+
 /// Proposes distinct extensions to a prefix stream.
 ///
 /// Unlike `propose`, this method does not scale the multiplicity of matched
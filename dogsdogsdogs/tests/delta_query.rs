@@ -0,0 +1,64 @@
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::Join;
+use differential_dataflow::consolidation::consolidate_updates;
+
+use differential_dogs3::delta_query::DeltaQuery;
+
+#[test]
+fn triangles_match_join() {
+
+    let (delta, naive) = timely::execute_directly(|worker| {
+
+        let (mut input, delta, naive) = worker.dataflow::<usize,_,_>(|scope| {
+
+            let (input, edges) = scope.new_collection::<(u32, u32), isize>();
+
+            // Q(a,b,c) := E(a,b), E(b,c), E(a,c)
+            let tuples = edges.map(|(x, y)| vec![x, y]);
+            let delta =
+            DeltaQuery::new()
+                .relation(&tuples, vec![0, 1])
+                .relation(&tuples, vec![1, 2])
+                .relation(&tuples, vec![0, 2])
+                .render();
+
+            let naive =
+            edges
+                .map(|(a, b)| (b, a))
+                .join(&edges)
+                .map(|(b, (a, c))| ((a, c), b))
+                .join(&edges.map(|edge| (edge, ())))
+                .map(|((a, c), (b, ()))| vec![a, b, c]);
+
+            (input, delta.inner.capture(), naive.inner.capture())
+        });
+
+        // rounds of changes, including simultaneous changes to edges of the same triangles.
+        // the extenders validate against sets, and so no edge is present more than once.
+        let rounds = vec![
+            vec![((1, 2), 1), ((2, 3), 1), ((1, 3), 1), ((3, 4), 1)],
+            vec![((2, 4), 1), ((1, 4), 1), ((1, 3), -1)],
+            vec![((1, 3), 1), ((2, 4), -1), ((4, 1), 1)],
+            vec![((1, 2), -1), ((1, 5), 1), ((5, 2), 1)],
+        ];
+        for (round, changes) in rounds.into_iter().enumerate() {
+            input.advance_to(round);
+            for (edge, diff) in changes {
+                input.update(edge, diff);
+            }
+        }
+
+        (delta, naive)
+    });
+
+    let mut delta = delta.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    let mut naive = naive.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    consolidate_updates(&mut delta);
+    consolidate_updates(&mut naive);
+
+    assert!(!naive.is_empty());
+    assert_eq!(delta, naive);
+}
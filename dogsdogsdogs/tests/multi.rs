@@ -0,0 +1,88 @@
+use timely::dataflow::operators::{ToStream, Capture};
+use timely::dataflow::operators::capture::{Event, Extract};
+
+use differential_dataflow::AsCollection;
+
+use differential_dogs3::{CollectionIndex, ProposeExtensionMethod};
+
+#[test]
+fn monochrome_triangles() {
+
+    let captured = timely::example(|scope| {
+
+        // directed edges `(src, dst, color)`, with one red triangle and one blue path that is not closed.
+        let edges =
+        vec![(1, 2, "red"), (2, 3, "red"), (1, 3, "red"), (1, 2, "blue"), (2, 3, "blue"), (4, 5, "blue")]
+            .into_iter()
+            .map(|(src, dst, color): (u32, u32, &str)| ((src, dst, color.to_string()), 0, 1isize))
+            .to_stream(scope)
+            .as_collection();
+
+        // edges indexed by the two-column key `(src, color)`, whose columns have different types.
+        let key_columns = (|x: &(u32, String)| x.0, |x: &(u32, String)| x.1.clone());
+        let index = CollectionIndex::index_multi(&edges.map(|(src, dst, color)| ((src, color), dst)), key_columns);
+
+        // prefixes `(a, b, color)` extend to `c` along edges `(b, c, color)` and `(a, c, color)`.
+        let b_color = (|p: &(u32, u32, String)| p.1, |p: &(u32, u32, String)| p.2.clone());
+        let a_color = (|p: &(u32, u32, String)| p.0, |p: &(u32, u32, String)| p.2.clone());
+        let mut extend_b = index.extend_using_multi(b_color);
+        let mut extend_a = index.extend_using_multi(a_color);
+
+        edges
+            .extend(&mut [&mut extend_b, &mut extend_a])
+            .inner
+            .capture()
+    });
+
+    let mut triangles = captured
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .map(|(triangle, _time, diff)| (triangle, diff))
+        .collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate(&mut triangles);
+
+    assert_eq!(triangles, vec![(((1, 2, "red".to_string()), 3), 1)]);
+}
+
+#[test]
+fn validate_leading_columns() {
+
+    let captured = timely::example(|scope| {
+
+        let edges =
+        vec![(1, 2, "red"), (2, 3, "red"), (1, 3, "red"), (1, 2, "blue"), (2, 3, "blue"), (4, 5, "blue")]
+            .into_iter()
+            .map(|(src, dst, color): (u32, u32, &str)| ((src, dst, color.to_string()), 0, 1isize))
+            .to_stream(scope)
+            .as_collection();
+
+        let key_columns = (|x: &(u32, String)| x.0, |x: &(u32, String)| x.1.clone());
+        let index = CollectionIndex::index_multi(&edges.map(|(src, dst, color)| ((src, color), dst)), key_columns);
+
+        // edges `(a, b, color)` for which `b` is the source of an edge of any color, and of an edge of `color`.
+        let any_color = index.validate_prefixes_using(&edges, (|p: &(u32, u32, String)| p.1,));
+        let same_color = index.validate_prefixes_using(&edges, (|p: &(u32, u32, String)| p.1, |p: &(u32, u32, String)| p.2.clone()));
+
+        (any_color.inner.capture(), same_color.inner.capture())
+    });
+
+    let (any_color, same_color) = captured;
+    let expected = vec![((1, 2, "blue".to_string()), 1), ((1, 2, "red".to_string()), 1)];
+    assert_eq!(extract_edges(any_color), expected);
+    assert_eq!(extract_edges(same_color), expected);
+}
+
+type Edge = (u32, u32, String);
+
+/// Extracts and consolidates captured edges.
+fn extract_edges(captured: std::sync::mpsc::Receiver<Event<usize, Vec<(Edge, usize, isize)>>>) -> Vec<(Edge, isize)> {
+    let mut edges = captured
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .map(|(edge, _time, diff)| (edge, diff))
+        .collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate(&mut edges);
+    edges
+}
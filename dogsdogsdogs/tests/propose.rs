@@ -0,0 +1,45 @@
+use timely::dataflow::operators::{ToStream, Capture};
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::AsCollection;
+
+use differential_dogs3::CollectionIndex;
+
+#[test]
+fn propose_with_counts_distinct() {
+
+    let captured = timely::example(|scope| {
+
+        // key 0 has three distinct values, one of them twice, and one retracted at time 1.
+        let edges =
+        vec![((0, 1), 0, 1isize), ((0, 2), 0, 1), ((0, 2), 0, 1), ((0, 3), 0, 1), ((0, 3), 1, -1), ((1, 4), 0, 1)]
+            .into_iter()
+            .to_stream(scope)
+            .as_collection();
+
+        let prefixes =
+        vec![(0u32, 0, 1isize), (0, 1, 1), (2, 0, 1)]
+            .into_iter()
+            .to_stream(scope)
+            .as_collection();
+
+        let index = CollectionIndex::<u32, u32, u64, isize>::index(&edges);
+        let mut extender = index.extend_using(|prefix: &u32| *prefix);
+        extender.propose_with_counts(&prefixes).inner.capture()
+    });
+
+    let mut results = captured
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut results);
+
+    assert_eq!(results, vec![
+        ((0, 1, 2), 1, 1),
+        ((0, 1, 3), 0, 1),
+        ((0, 2, 2), 1, 2),
+        ((0, 2, 3), 0, 2),
+        ((0, 3, 3), 0, 1),
+    ]);
+}
@@ -0,0 +1,105 @@
+use rand::{Rng, SeedableRng, StdRng};
+
+use timely::dataflow::*;
+use timely::dataflow::operators::probe::Handle;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::Collection;
+use differential_dataflow::operators::*;
+use differential_dataflow::lattice::Lattice;
+
+type Node = u32;
+type Edge = (Node, Node);
+
+fn main() {
+
+    let nodes: u32 = std::env::args().nth(1).unwrap().parse().unwrap();
+    let edges: u32 = std::env::args().nth(2).unwrap().parse().unwrap();
+    let batch: u32 = std::env::args().nth(3).unwrap().parse().unwrap();
+    let rounds: u32 = std::env::args().nth(4).unwrap().parse().unwrap();
+    let mode: String = std::env::args().nth(5).unwrap();
+
+    println!("Running reachability with [{:?}] distinct", mode);
+
+    timely::execute_from_args(std::env::args(), move |worker| {
+
+        let timer = ::std::time::Instant::now();
+
+        let mut probe = Handle::new();
+        let (mut roots, mut graph) = worker.dataflow(|scope| {
+
+            let (root_input, roots) = scope.new_collection();
+            let (edge_input, graph) = scope.new_collection();
+
+            let hashed = match mode.as_str() {
+                "sort" => false,
+                "hash" => true,
+                _ => panic!("unrecognized mode: {:?}; expected \"sort\" or \"hash\"", mode),
+            };
+
+            reach(&graph, &roots, hashed)
+                .map(|_| ())
+                .consolidate()
+                .inspect(|x| println!("\t{:?}", x))
+                .probe_with(&mut probe);
+
+            (root_input, edge_input)
+        });
+
+        let seed: &[_] = &[1, 2, 3, 4];
+        let mut rng1: StdRng = SeedableRng::from_seed(seed);    // rng for edge additions
+        let mut rng2: StdRng = SeedableRng::from_seed(seed);    // rng for edge deletions
+
+        roots.insert(0);
+        roots.close();
+
+        if worker.index() == 0 {
+            for _ in 0 .. edges {
+                graph.insert((rng1.gen_range(0, nodes), rng1.gen_range(0, nodes)));
+            }
+        }
+
+        graph.advance_to(1);
+        graph.flush();
+        worker.step_while(|| probe.less_than(graph.time()));
+
+        println!("{:?}\tstable", timer.elapsed());
+
+        for round in 0 .. rounds {
+            for element in 0 .. batch {
+                if worker.index() == 0 {
+                    graph.insert((rng1.gen_range(0, nodes), rng1.gen_range(0, nodes)));
+                    graph.remove((rng2.gen_range(0, nodes), rng2.gen_range(0, nodes)));
+                }
+                graph.advance_to(2 + round * batch + element);
+            }
+            graph.flush();
+
+            let timer2 = ::std::time::Instant::now();
+            worker.step_while(|| probe.less_than(graph.time()));
+
+            if worker.index() == 0 {
+                println!("{:?}\t{:?}:\t{:?}", timer.elapsed(), round, timer2.elapsed());
+            }
+        }
+        println!("finished; elapsed: {:?}", timer.elapsed());
+    }).unwrap();
+}
+
+// returns the nodes reachable from the roots, using `distinct_hashed` rather than `distinct` if `hashed`.
+fn reach<G: Scope>(edges: &Collection<G, Edge>, roots: &Collection<G, Node>, hashed: bool) -> Collection<G, Node>
+where G::Timestamp: Lattice+Ord {
+
+    roots.iterate(|inner| {
+
+        let edges = edges.enter(&inner.scope());
+        let roots = roots.enter(&inner.scope());
+
+        let reached =
+        inner.map(|x| (x, ()))
+             .join_map(&edges, |_k, &(), d| *d)
+             .concat(&roots);
+
+        if hashed { reached.distinct_hashed() } else { reached.distinct() }
+    })
+}
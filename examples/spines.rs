@@ -64,6 +64,13 @@ fn main() {
                     keys.join_core(&data, |_k, &(), &()| Option::<()>::None)
                         .probe_with(&mut probe);
                 },
+                "huffman" => {
+                    use differential_dataflow::trace::implementations::ord_neu::{HuffmanBatcher, HuffmanBuilder, HuffmanSpine};
+                    let data = data.map(|x| (x.into_bytes(), ())).arrange::<HuffmanBatcher<(),_,_>, HuffmanBuilder<(),_,_>, HuffmanSpine<(),_,_>>();
+                    let keys = keys.map(|x| (x.into_bytes(), ())).arrange::<HuffmanBatcher<(),_,_>, HuffmanBuilder<(),_,_>, HuffmanSpine<(),_,_>>();
+                    keys.join_core(&data, |_k, &(), &()| Option::<()>::None)
+                        .probe_with(&mut probe);
+                },
                 "flat" => {
                     use differential_dataflow::trace::implementations::ord_neu::{FlatKeyBatcherDefault, FlatKeyBuilderDefault, FlatKeySpineDefault};
                     let data = data.arrange::<FlatKeyBatcherDefault<String,usize,isize,_>, FlatKeyBuilderDefault<String,usize,isize>, FlatKeySpineDefault<String,usize,isize>>();
@@ -0,0 +1,77 @@
+use rand::{Rng, SeedableRng, StdRng};
+
+use timely::dataflow::operators::probe::Handle;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::*;
+
+fn main() {
+
+    let keys: u32 = std::env::args().nth(1).unwrap().parse().unwrap();
+    let records: u32 = std::env::args().nth(2).unwrap().parse().unwrap();
+    let batch: u32 = std::env::args().nth(3).unwrap().parse().unwrap();
+    let rounds: u32 = std::env::args().nth(4).unwrap().parse().unwrap();
+    let mode: String = std::env::args().nth(5).unwrap();
+
+    println!("Running per-key sums with [{:?}]", mode);
+
+    timely::execute_from_args(std::env::args(), move |worker| {
+
+        let timer = ::std::time::Instant::now();
+
+        let mut probe = Handle::new();
+        let mut input = worker.dataflow::<u32,_,_>(|scope| {
+
+            let (input, data) = scope.new_collection::<(u32, u64), isize>();
+
+            let sums = match mode.as_str() {
+                "reduce" => {
+                    data.reduce(|_key, input, output| {
+                            let sum: i128 = input.iter().map(|(val, diff)| (**val as i128) * (*diff as i128)).sum();
+                            output.push((sum, 1));
+                        })
+                },
+                "total" => data.sum_total(|val| *val as i128),
+                _ => panic!("unrecognized mode: {:?}; expected \"reduce\" or \"total\"", mode),
+            };
+
+            sums.probe_with(&mut probe);
+            input
+        });
+
+        let seed: &[_] = &[1, 2, 3, 4];
+        let mut rng1: StdRng = SeedableRng::from_seed(seed);    // rng for record additions
+        let mut rng2: StdRng = SeedableRng::from_seed(seed);    // rng for record deletions
+
+        if worker.index() == 0 {
+            for _ in 0 .. records {
+                input.insert((rng1.gen_range(0, keys), rng1.gen_range(0, 1000)));
+            }
+        }
+
+        input.advance_to(1);
+        input.flush();
+        worker.step_while(|| probe.less_than(input.time()));
+
+        println!("{:?}\tstable", timer.elapsed());
+
+        for round in 0 .. rounds {
+            for element in 0 .. batch {
+                if worker.index() == 0 {
+                    input.insert((rng1.gen_range(0, keys), rng1.gen_range(0, 1000)));
+                    input.remove((rng2.gen_range(0, keys), rng2.gen_range(0, 1000)));
+                }
+                input.advance_to(2 + round * batch + element);
+            }
+            input.flush();
+
+            let timer2 = ::std::time::Instant::now();
+            worker.step_while(|| probe.less_than(input.time()));
+
+            if worker.index() == 0 {
+                println!("{:?}\t{:?}:\t{:?}", timer.elapsed(), round, timer2.elapsed());
+            }
+        }
+        println!("finished; elapsed: {:?}", timer.elapsed());
+    }).unwrap();
+}
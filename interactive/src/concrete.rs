@@ -2,7 +2,8 @@
 
 use std::time::Duration;
 use serde::{Deserialize, Serialize};
-use super::{Datum, VectorFrom, Command};
+use super::{Datum, VectorFrom, Command, Diff};
+use super::plan::{AggFn, BinOp};
 
 /// A session.
 pub struct Session<W: std::io::Write> {
@@ -39,6 +40,79 @@ impl Datum for Value {
     type Expression = usize;
     fn subject_to(data: &[Self], expr: &Self::Expression) -> Self { data[*expr].clone() }
     fn projection(index: usize) -> Self::Expression { index }
+    fn aggregate<'a, I: Iterator<Item=(&'a Self, Diff)>>(function: &AggFn, values: I) -> Self where Self: 'a {
+        // Sum, Min, and Max apply only to integers and durations, and not to a mix of the two.
+        let numeric = |value: &'a Value| match value {
+            Value::Usize(_) | Value::Duration(_) => value,
+            _ => panic!("{:?} requires Usize or Duration values; found {:?}", function, value),
+        };
+        fn mismatch(function: &AggFn, value1: &Value, value2: &Value) -> ! {
+            panic!("{:?} requires values of a single type; found {:?} and {:?}", function, value1, value2)
+        }
+        match function {
+            // Counts and sums accumulate in `i128`, so that negative differences neither wrap nor truncate,
+            // and only the final result must be representable.
+            AggFn::Count => {
+                let count = values.map(|(_value, diff)| diff as i128).sum::<i128>();
+                Value::Usize(usize::try_from(count).unwrap_or_else(|_| panic!("Count of a group is not a valid Usize: {}", count)))
+            },
+            AggFn::Sum => {
+                let mut total: Option<(&Value, i128)> = None;
+                for (value, diff) in values {
+                    let amount = match numeric(value) {
+                        Value::Usize(x) => *x as i128,
+                        Value::Duration(x) => x.as_nanos() as i128,
+                        _ => unreachable!(),
+                    };
+                    let term = amount.checked_mul(diff as i128).unwrap_or_else(|| panic!("Sum overflows: {:?} * {}", value, diff));
+                    match total.as_mut() {
+                        None => { total = Some((value, term)); },
+                        Some((first, sum)) => {
+                            if std::mem::discriminant(*first) != std::mem::discriminant(value) { mismatch(function, *first, value) }
+                            *sum = sum.checked_add(term).unwrap_or_else(|| panic!("Sum overflows: {} + {}", sum, term));
+                        },
+                    }
+                }
+                match total.expect("Sum applied to an empty group") {
+                    (Value::Usize(_), sum) => {
+                        Value::Usize(usize::try_from(sum).unwrap_or_else(|_| panic!("Sum of a group is not a valid Usize: {}", sum)))
+                    },
+                    (Value::Duration(_), sum) => {
+                        let nanos = u128::try_from(sum).unwrap_or_else(|_| panic!("Sum of a group is a negative Duration: {}ns", sum));
+                        let secs = u64::try_from(nanos / 1_000_000_000).unwrap_or_else(|_| panic!("Sum of a group overflows a Duration: {}ns", nanos));
+                        Value::Duration(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+                    },
+                    _ => unreachable!(),
+                }
+            },
+            AggFn::Min | AggFn::Max => {
+                values
+                    .map(|(value, _diff)| numeric(value))
+                    .reduce(|value1, value2| {
+                        if std::mem::discriminant(value1) != std::mem::discriminant(value2) { mismatch(function, value1, value2) }
+                        if (function == &AggFn::Min) == (value1 <= value2) { value1 } else { value2 }
+                    })
+                    .expect("Min or Max applied to an empty group")
+                    .clone()
+            },
+        }
+    }
+    fn binary(op: &BinOp, left: &Self, right: &Self) -> Self {
+        match (op, left, right) {
+            (BinOp::Add, Value::Usize(x), Value::Usize(y)) => Value::Usize(x + y),
+            (BinOp::Add, Value::Duration(x), Value::Duration(y)) => Value::Duration(*x + *y),
+            (BinOp::Subtract, Value::Usize(x), Value::Usize(y)) => {
+                Value::Usize(x.checked_sub(*y).unwrap_or_else(|| panic!("Subtract underflows: {} - {}", x, y)))
+            },
+            (BinOp::Subtract, Value::Duration(x), Value::Duration(y)) => {
+                Value::Duration(x.checked_sub(*y).unwrap_or_else(|| panic!("Subtract underflows: {:?} - {:?}", x, y)))
+            },
+            (BinOp::Multiply, Value::Usize(x), Value::Usize(y)) => Value::Usize(x * y),
+            (BinOp::Concat, Value::String(x), Value::String(y)) => Value::String(format!("{}{}", x, y)),
+            (BinOp::Concat, Value::Vector(x), Value::Vector(y)) => Value::Vector(x.iter().chain(y.iter()).cloned().collect()),
+            _ => panic!("{:?} does not apply to {:?} and {:?}", op, left, right),
+        }
+    }
 }
 
 impl From<usize> for Value { fn from(x: usize) -> Self { Value::Usize(x) } }
@@ -123,7 +197,29 @@ impl VectorFrom<DifferentialEvent> for Value {
                     x.complete.is_some().into(),
                 ]
             },
+            DifferentialEvent::HoldingPen(x) => {
+                vec![
+                    x.operator.into(),
+                    x.records.into(),
+                    x.held_back.into(),
+                ]
+            },
             _ => { vec![] },
         }
     }
-}
\ No newline at end of file
+}
+
+use crate::logging::ArrangementSize;
+
+impl VectorFrom<ArrangementSize> for Value {
+    fn vector_from(item: ArrangementSize) -> Vec<Value> {
+        vec![
+            item.plan.into(),
+            item.keys.is_some().into(),
+            item.keys.unwrap_or_default().into(),
+            item.key_count.into(),
+            item.val_count.into(),
+            item.update_count.into(),
+        ]
+    }
+}
@@ -36,6 +36,14 @@ pub trait Datum : Hash+Sized+Debug {
     fn subject_to(data: &[Self], expr: &Self::Expression) -> Self;
     /// Creates a expression that implements projection.
     fn projection(index: usize) -> Self::Expression;
+    /// Applies an aggregate function to a sequence of values and their multiplicities.
+    ///
+    /// Implementations may panic if the function does not apply to the values.
+    fn aggregate<'a, I: Iterator<Item=(&'a Self, Diff)>>(function: &plan::AggFn, values: I) -> Self where Self: 'a;
+    /// Applies a binary operation to two values.
+    ///
+    /// Implementations may panic if the operation does not apply to the values.
+    fn binary(op: &plan::BinOp, left: &Self, right: &Self) -> Self;
 }
 
 /// A type that can be converted to a vector of another type.
@@ -15,8 +15,25 @@ use crate::{Plan, VectorFrom, Datum};
 use crate::manager::Manager;
 
 /// A composite trait for values accommodating logging types.
-pub trait LoggingValue : VectorFrom<TimelyEvent>+VectorFrom<DifferentialEvent> { }
-impl<V: VectorFrom<TimelyEvent>+VectorFrom<DifferentialEvent>> LoggingValue for V { }
+pub trait LoggingValue : VectorFrom<TimelyEvent>+VectorFrom<DifferentialEvent>+VectorFrom<ArrangementSize> { }
+impl<V: VectorFrom<TimelyEvent>+VectorFrom<DifferentialEvent>+VectorFrom<ArrangementSize>> LoggingValue for V { }
+
+/// The estimated size of an arrangement held by a `TraceManager`.
+///
+/// Each count is summed across the batches of the arrangement, as by `TraceReader::estimate_size`.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ArrangementSize {
+    /// A description of the plan whose results are arranged.
+    pub plan: String,
+    /// The columns by which the arrangement is keyed, or `None` for an unkeyed arrangement.
+    pub keys: Option<Vec<usize>>,
+    /// The number of keys.
+    pub key_count: usize,
+    /// The number of `(key, val)` pairs.
+    pub val_count: usize,
+    /// The number of updates.
+    pub update_count: usize,
+}
 
 /// Timely logging capture and arrangement.
 pub fn publish_timely_logging<V, A, I>(
@@ -215,7 +232,7 @@ where
     I : IntoIterator,
     <I as IntoIterator>::Item: EventIterator<Duration, Vec<(Duration, usize, DifferentialEvent)>>+'static
 {
-    let (merge,batch) =
+    let (merge,batch,holding) =
     worker.dataflow(move |scope| {
 
         use timely::dataflow::operators::capture::Replay;
@@ -230,6 +247,7 @@ where
 
         let (mut batch_out, batch) = demux.new_output();
         let (mut merge_out, merge) = demux.new_output();
+        let (mut holding_out, holding) = demux.new_output();
 
         demux.build(move |_capability| {
 
@@ -237,11 +255,13 @@ where
 
                 let mut batch = batch_out.activate();
                 let mut merge = merge_out.activate();
+                let mut holding = holding_out.activate();
 
                 input.for_each(|time, data| {
 
                     let mut batch_session = batch.session(&time);
                     let mut merge_session = merge.session(&time);
+                    let mut holding_session = holding.session(&time);
 
                     for (time, _worker, datum) in data.drain(..) {
 
@@ -256,6 +276,9 @@ where
                             DifferentialEvent::Merge(_) => {
                                 merge_session.give((V::vector_from(datum), time, 1));
                             },
+                            DifferentialEvent::HoldingPen(_) => {
+                                holding_session.give((V::vector_from(datum), time, 1));
+                            },
                             _ => { },
                         }
                     }
@@ -267,10 +290,12 @@ where
         use differential_dataflow::operators::arrange::ArrangeBySelf;
         let batch = batch.as_collection().arrange_by_self().trace;
         let merge = merge.as_collection().arrange_by_self().trace;
+        let holding = holding.as_collection().arrange_by_self().trace;
 
-        (merge,batch)
+        (merge,batch,holding)
     });
 
     manager.traces.set_unkeyed(&Plan::Source(format!("logs/{}/differential/arrange/batch", name)), &batch);
     manager.traces.set_unkeyed(&Plan::Source(format!("logs/{}/differential/arrange/merge", name)), &merge);
+    manager.traces.set_unkeyed(&Plan::Source(format!("logs/{}/differential/arrange/holding", name)), &holding);
 }
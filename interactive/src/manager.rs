@@ -1,10 +1,13 @@
 //! Management of inputs and traces.
 
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
+use std::rc::Rc;
 // use std::time::Duration;
 
-use timely::dataflow::ProbeHandle;
+use timely::dataflow::{ProbeHandle, Scope, Stream};
+use timely::dataflow::operators::Inspect;
 use timely::communication::Allocate;
 use timely::worker::Worker;
 use timely::logging::TimelyEventBuilder;
@@ -12,13 +15,15 @@ use timely::logging::TimelyEventBuilder;
 // use timely::dataflow::operators::capture::event::EventIterator;
 
 use differential_dataflow::ExchangeData;
+use differential_dataflow::trace::{BatchReader, TraceReader};
 use differential_dataflow::trace::implementations::{KeySpine, ValSpine};
-use differential_dataflow::operators::arrange::TraceAgent;
+use differential_dataflow::operators::arrange::{Arranged, TraceAgent, PublishedTrace, TracePublisher};
 use differential_dataflow::input::InputSession;
 
 use differential_dataflow::logging::DifferentialEventBuilder;
 
 use crate::{Time, Diff, Plan, Datum};
+use crate::logging::ArrangementSize;
 
 /// A trace handle for key-only data.
 pub type TraceKeyHandle<K, T, R> = TraceAgent<KeySpine<K, T, R>>;
@@ -28,6 +33,10 @@ pub type TraceValHandle<K, V, T, R> = TraceAgent<ValSpine<K, V, T, R>>;
 pub type KeysOnlyHandle<V> = TraceKeyHandle<Vec<V>, Time, Diff>;
 /// A key-value trace handle binding `Time` and `Diff` using `Vec<V>` as data.
 pub type KeysValsHandle<V> = TraceValHandle<Vec<V>, Vec<V>, Time, Diff>;
+/// A published key-only trace binding `Time` and `Diff` using `Vec<V>` as data.
+pub type KeysOnlyTrace<V> = PublishedTrace<KeySpine<Vec<V>, Time, Diff>>;
+/// A published key-value trace binding `Time` and `Diff` using `Vec<V>` as data.
+pub type KeysValsTrace<V> = PublishedTrace<ValSpine<Vec<V>, Vec<V>, Time, Diff>>;
 
 /// Manages inputs and traces.
 pub struct Manager<V: ExchangeData+Datum> {
@@ -81,8 +90,10 @@ impl<V: ExchangeData+Datum> Manager<V>
     /// Clear the managed inputs and traces.
     pub fn shutdown<A: Allocate>(&mut self, worker: &mut Worker<A>) {
         self.inputs.sessions.clear();
-        self.traces.inputs.clear();
-        self.traces.arrangements.clear();
+        self.traces.release_all();
+        self.traces.last_used.borrow_mut().clear();
+        self.traces.pinned.clear();
+        self.traces.imports.borrow_mut().clear();
 
         // Deregister loggers, so that the logging dataflows can shut down.
         worker
@@ -157,6 +168,10 @@ impl<V: ExchangeData> InputManager<V> {
 ///
 /// Manages a map from plan (describing a collection)
 /// to various arranged forms of that collection.
+///
+/// Arrangements are held as published traces, and the manager is a `TracePublisher` that registers
+/// published traces as sources under their names. Releasing an arrangement, by eviction or on shutdown,
+/// releases it for every clone of its handle, including those held outside of the manager.
 pub struct TraceManager<V: ExchangeData+Datum> {
 
     /// Arrangements where the record itself is they key.
@@ -164,10 +179,33 @@ pub struct TraceManager<V: ExchangeData+Datum> {
     /// This contains both input collections, which are here cached so that
     /// they can be re-used, intermediate collections that are cached, and
     /// any collections that are explicitly published.
-    inputs: HashMap<Plan<V>, KeysOnlyHandle<V>>,
+    inputs: HashMap<Plan<V>, KeysOnlyTrace<V>>,
 
     /// Arrangements of collections by key.
-    arrangements: HashMap<Plan<V>, HashMap<Vec<usize>, KeysValsHandle<V>>>,
+    arrangements: HashMap<Plan<V>, HashMap<Vec<usize>, KeysValsTrace<V>>>,
+
+    /// An approximate limit on the bytes held by registered arrangements, if any.
+    budget: Option<usize>,
+
+    /// Advanced with time, to order the uses of arrangements.
+    clock: usize,
+
+    /// The value of `clock` when each evictable arrangement was last registered or used.
+    ///
+    /// Unkeyed arrangements are recorded with keys `None`. Sources are absent, as they
+    /// name inputs and rules rather than cache the results of rendering. Uses are recorded
+    /// through shared references, as recovering an arrangement does not otherwise modify
+    /// the manager.
+    last_used: RefCell<HashMap<(Plan<V>, Option<Vec<usize>>), usize>>,
+
+    /// Arrangements that must not be released, whether or not they are registered.
+    pinned: HashSet<(Plan<V>, Option<Vec<usize>>)>,
+
+    /// Tokens shared with the dataflows that import each arrangement.
+    ///
+    /// Each importing dataflow holds a clone of the token until it shuts down, and an arrangement
+    /// is imported, and so pinned, while its token has holders other than the manager.
+    imports: RefCell<HashMap<(Plan<V>, Option<Vec<usize>>), Rc<()>>>,
 
 }
 
@@ -177,45 +215,101 @@ impl<V: ExchangeData+Hash+Datum> TraceManager<V> {
     pub fn new() -> Self {
         Self {
             inputs: HashMap::new(),
-            arrangements: HashMap::new()
+            arrangements: HashMap::new(),
+            budget: None,
+            clock: 0,
+            last_used: RefCell::new(HashMap::new()),
+            pinned: HashSet::new(),
+            imports: RefCell::new(HashMap::new()),
         }
     }
 
+    /// Limits the approximate number of bytes held by arrangements that may be released.
+    ///
+    /// When registering an arrangement would exceed the budget, the least recently used arrangements
+    /// are released until the total fits. Sizes are estimated from the number of updates in each batch.
+    /// Unkeyed sources, pinned arrangements, and arrangements imported by running dataflows are never released,
+    /// and do not count against the budget.
+    /// Arrangements used since the last call to `advance_time` are not released either, and the budget
+    /// may be exceeded if no other arrangements can be released.
+    pub fn set_budget(&mut self, bytes: usize) {
+        self.budget = Some(bytes);
+        self.evict();
+    }
+
+    /// Prevents the arrangement of `plan` by `keys`, or its unkeyed arrangement if `None`, from being released.
+    ///
+    /// Arrangements may be pinned before they are registered, and remain pinned until `unpin` is called.
+    /// Pinning is not counted: a single call to `unpin` undoes any number of calls to `pin`. Arrangements
+    /// imported through `import_unkeyed` and `import_keyed` need not be pinned, as they are held until the
+    /// importing dataflows shut down.
+    pub fn pin(&mut self, plan: &Plan<V>, keys: Option<&[usize]>) {
+        self.pinned.insert((plan.clone(), keys.map(|k| k.to_vec())));
+    }
+
+    /// Allows the arrangement of `plan` by `keys`, or its unkeyed arrangement if `None`, to be released.
+    ///
+    /// The arrangement is not released immediately, but may be when the budget is next enforced.
+    pub fn unpin(&mut self, plan: &Plan<V>, keys: Option<&[usize]>) {
+        self.pinned.remove(&(plan.clone(), keys.map(|k| k.to_vec())));
+    }
+
     /// Advances the frontier of each maintained trace.
     pub fn advance_time(&mut self, time: &Time) {
-        use differential_dataflow::trace::TraceReader;
         use timely::progress::frontier::Antichain;
         let frontier = Antichain::from_elem(time.clone());
-        for trace in self.inputs.values_mut() {
-            trace.set_logical_compaction(frontier.borrow());
-            trace.set_physical_compaction(frontier.borrow());
+        self.clock += 1;
+        for trace in self.inputs.values() {
+            trace.set_compaction(frontier.borrow());
         }
-        for map in self.arrangements.values_mut() {
-            for trace in map.values_mut() {
-                trace.set_logical_compaction(frontier.borrow());
-                trace.set_physical_compaction(frontier.borrow());
+        for map in self.arrangements.values() {
+            for trace in map.values() {
+                trace.set_compaction(frontier.borrow());
             }
         }
     }
 
     /// Recover an arrangement by plan and keys, if it is cached.
     pub fn get_unkeyed(&self, plan: &Plan<V>) -> Option<KeysOnlyHandle<V>> {
-        self.inputs
-            .get(plan)
-            .map(|x| x.clone())
+        let handle = self.inputs.get(plan).and_then(|trace| trace.with_trace(|trace| trace.clone()));
+        if handle.is_some() { self.touch(plan, None); }
+        handle
+    }
+
+    /// Imports the unkeyed arrangement of `plan` into `scope`, if it is cached.
+    ///
+    /// The arrangement is not released until the dataflow containing `scope` shuts down.
+    pub fn import_unkeyed<G: Scope<Timestamp=Time>>(&self, plan: &Plan<V>, scope: &G) -> Option<Arranged<G, KeysOnlyHandle<V>>> {
+        let arranged = self.inputs.get(plan)?.import(scope)?;
+        self.touch(plan, None);
+        self.hold(plan, None, &arranged.stream);
+        Some(arranged)
     }
 
     /// Installs an unkeyed arrangement for a specified plan.
     pub fn set_unkeyed(&mut self, plan: &Plan<V>, handle: &KeysOnlyHandle<V>) {
-        self.inputs
-            .insert(plan.clone(), handle.clone());
+        self.insert_unkeyed(plan, PublishedTrace::new(handle.clone()));
     }
 
     /// Recover an arrangement by plan and keys, if it is cached.
     pub fn get_keyed(&self, plan: &Plan<V>, keys: &[usize]) -> Option<KeysValsHandle<V>> {
+        let handle =
         self.arrangements
             .get(plan)
-            .and_then(|map| map.get(keys).map(|x| x.clone()))
+            .and_then(|map| map.get(keys))
+            .and_then(|trace| trace.with_trace(|trace| trace.clone()));
+        if handle.is_some() { self.touch(plan, Some(keys)); }
+        handle
+    }
+
+    /// Imports the arrangement of `plan` by `keys` into `scope`, if it is cached.
+    ///
+    /// The arrangement is not released until the dataflow containing `scope` shuts down.
+    pub fn import_keyed<G: Scope<Timestamp=Time>>(&self, plan: &Plan<V>, keys: &[usize], scope: &G) -> Option<Arranged<G, KeysValsHandle<V>>> {
+        let arranged = self.arrangements.get(plan)?.get(keys)?.import(scope)?;
+        self.touch(plan, Some(keys));
+        self.hold(plan, Some(keys), &arranged.stream);
+        Some(arranged)
     }
 
     /// Installs a keyed arrangement for a specified plan and sequence of keys.
@@ -223,7 +317,200 @@ impl<V: ExchangeData+Hash+Datum> TraceManager<V> {
         self.arrangements
             .entry(plan.clone())
             .or_insert(HashMap::new())
-            .insert(keys.to_vec(), handle.clone());
+            .insert(keys.to_vec(), PublishedTrace::new(handle.clone()));
+        self.touch(plan, Some(keys));
+        self.evict();
+    }
+
+    /// Lists the plans with unkeyed arrangements, in order.
+    ///
+    /// Sources are listed by their names, and other plans by their `Debug` representations.
+    pub fn list_unkeyed(&self) -> Vec<String> {
+        let mut list = self.inputs.keys().map(plan_name).collect::<Vec<_>>();
+        list.sort();
+        list
+    }
+
+    /// Lists the plans with keyed arrangements and the keys of each arrangement, in order.
+    ///
+    /// Plans are named as by `list_unkeyed`, and a plan arranged by several keys appears once for each.
+    pub fn list_keyed(&self) -> Vec<(String, Vec<usize>)> {
+        let mut list =
+        self.arrangements
+            .iter()
+            .flat_map(|(plan, map)| map.keys().map(move |keys| (plan_name(plan), keys.clone())))
+            .collect::<Vec<_>>();
+        list.sort();
+        list
+    }
+
+    /// The logical compaction frontier of the arrangement of `plan` by `keys`, or its unkeyed arrangement if `None`.
+    ///
+    /// Returns `None` if the arrangement is not registered. Accumulations are correct only at times in advance of
+    /// the frontier.
+    pub fn logical_compaction(&mut self, plan: &Plan<V>, keys: Option<&[usize]>) -> Option<Vec<Time>> {
+        match keys {
+            None => self.inputs.get(plan).and_then(|trace| trace.with_trace(|trace| trace.get_logical_compaction().to_vec())),
+            Some(keys) => {
+                self.arrangements
+                    .get(plan)
+                    .and_then(|map| map.get(keys))
+                    .and_then(|trace| trace.with_trace(|trace| trace.get_logical_compaction().to_vec()))
+            },
+        }
     }
 
+    /// Estimates the sizes of the maintained arrangements.
+    ///
+    /// The sizes may be converted by `VectorFrom` into records of a collection, for example to be
+    /// introduced into an input and queried to monitor the sizes of arrangements over time.
+    pub fn sizes(&self) -> Vec<ArrangementSize> {
+        let unkeyed = self.inputs.iter().map(|(plan, trace)| (plan, None, trace.with_trace(|trace| trace.estimate_size())));
+        let keyed = self.arrangements.iter().flat_map(|(plan, map)| {
+            map.iter().map(move |(keys, trace)| (plan, Some(keys.clone()), trace.with_trace(|trace| trace.estimate_size())))
+        });
+        let mut sizes =
+        unkeyed
+            .chain(keyed)
+            .filter_map(|(plan, keys, size)| size.map(|size| (plan, keys, size)))
+            .map(|(plan, keys, (key_count, val_count, update_count))| ArrangementSize {
+                plan: format!("{:?}", plan),
+                keys,
+                key_count,
+                val_count,
+                update_count,
+            })
+            .collect::<Vec<_>>();
+        sizes.sort();
+        sizes
+    }
+
+    /// Installs a published unkeyed arrangement for a specified plan.
+    fn insert_unkeyed(&mut self, plan: &Plan<V>, trace: KeysOnlyTrace<V>) {
+        self.inputs.insert(plan.clone(), trace);
+        self.touch(plan, None);
+        self.evict();
+    }
+
+    /// Releases all arrangements, including for clones of their handles held outside of the manager.
+    fn release_all(&mut self) {
+        for (_, trace) in self.inputs.drain() {
+            trace.release();
+        }
+        for (_, map) in self.arrangements.drain() {
+            for (_, trace) in map {
+                trace.release();
+            }
+        }
+    }
+
+    /// Records a use of the arrangement of `plan` by `keys`, unless it is an unkeyed source.
+    fn touch(&self, plan: &Plan<V>, keys: Option<&[usize]>) {
+        if keys.is_some() || !matches!(plan, Plan::Source(_)) {
+            self.last_used.borrow_mut().insert((plan.clone(), keys.map(|k| k.to_vec())), self.clock);
+        }
+    }
+
+    /// Pins the arrangement of `plan` by `keys` for as long as the dataflow containing `stream` runs.
+    ///
+    /// A clone of the arrangement's token is moved into an operator reading `stream`, and is dropped
+    /// with the operator when the dataflow shuts down.
+    fn hold<G: Scope, D: timely::Data>(&self, plan: &Plan<V>, keys: Option<&[usize]>, stream: &Stream<G, D>) {
+        let token =
+        self.imports
+            .borrow_mut()
+            .entry((plan.clone(), keys.map(|k| k.to_vec())))
+            .or_insert_with(|| Rc::new(()))
+            .clone();
+        stream.inspect_batch(move |_time, _data| { let _held = &token; });
+    }
+
+    /// Indicates that the arrangement of `plan` by `keys` may be released, and counts against the budget.
+    fn evictable(&self, plan: &Plan<V>, keys: Option<&Vec<usize>>) -> bool {
+        let entry = (plan.clone(), keys.cloned());
+        let source = keys.is_none() && matches!(plan, Plan::Source(_));
+        let imported = self.imports.borrow().get(&entry).map(|token| Rc::strong_count(token) > 1).unwrap_or(false);
+        !source && !imported && !self.pinned.contains(&entry)
+    }
+
+    /// An estimate of the bytes held by an unkeyed arrangement.
+    fn unkeyed_size(trace: &KeysOnlyTrace<V>) -> usize {
+        trace.with_trace(|trace| update_count(trace)).unwrap_or(0) * std::mem::size_of::<(Vec<V>, Time, Diff)>()
+    }
+
+    /// An estimate of the bytes held by a keyed arrangement.
+    fn keyed_size(trace: &KeysValsTrace<V>) -> usize {
+        trace.with_trace(|trace| update_count(trace)).unwrap_or(0) * std::mem::size_of::<(Vec<V>, Vec<V>, Time, Diff)>()
+    }
+
+    /// Releases least recently used arrangements until the estimated size fits the budget.
+    fn evict(&mut self) {
+        if let Some(budget) = self.budget {
+            let mut size =
+            self.inputs.iter().filter(|(plan, _)| self.evictable(plan, None)).map(|(_, trace)| Self::unkeyed_size(trace)).sum::<usize>() +
+            self.arrangements.iter().flat_map(|(plan, map)| map.iter().map(move |(keys, trace)| (plan, keys, trace)))
+                .filter(|(plan, keys, _)| self.evictable(plan, Some(keys)))
+                .map(|(_, _, trace)| Self::keyed_size(trace))
+                .sum::<usize>();
+
+            while size > budget {
+                let candidate =
+                self.last_used
+                    .borrow()
+                    .iter()
+                    .filter(|(_, used)| **used < self.clock)
+                    .filter(|((plan, keys), _)| self.evictable(plan, keys.as_ref()))
+                    .min_by_key(|(_, used)| **used)
+                    .map(|(entry, _)| entry.clone());
+
+                if let Some((plan, keys)) = candidate {
+                    self.last_used.borrow_mut().remove(&(plan.clone(), keys.clone()));
+                    self.imports.borrow_mut().remove(&(plan.clone(), keys.clone()));
+                    match keys {
+                        None => {
+                            if let Some(trace) = self.inputs.remove(&plan) {
+                                size -= Self::unkeyed_size(&trace);
+                                trace.release();
+                            }
+                        },
+                        Some(keys) => {
+                            if let Some(map) = self.arrangements.get_mut(&plan) {
+                                if let Some(trace) = map.remove(&keys) {
+                                    size -= Self::keyed_size(&trace);
+                                    trace.release();
+                                }
+                                if map.is_empty() { self.arrangements.remove(&plan); }
+                            }
+                        },
+                    }
+                }
+                else {
+                    break;
+                }
+            }
+        }
+    }
+
+}
+
+impl<V: ExchangeData+Hash+Datum> TracePublisher<KeySpine<Vec<V>, Time, Diff>> for TraceManager<V> {
+    /// Registers `trace` as the unkeyed arrangement of the source `name`.
+    fn publish(&mut self, name: &str, trace: KeysOnlyTrace<V>) {
+        self.insert_unkeyed(&Plan::Source(name.to_owned()), trace);
+    }
+}
+
+/// The name by which `plan` is listed: the name of a source, and otherwise its `Debug` representation.
+fn plan_name<V: Datum>(plan: &Plan<V>) -> String {
+    match plan {
+        Plan::Source(name) => name.clone(),
+        plan => format!("{:?}", plan),
+    }
+}
+
+/// The number of updates in the batches of a trace, from which its size is estimated.
+fn update_count<Tr: TraceReader>(trace: &TraceAgent<Tr>) -> usize {
+    let mut updates = 0;
+    trace.map_batches(|batch| updates += batch.len());
+    updates
 }
@@ -0,0 +1,69 @@
+//! Grouped aggregation expression plan.
+
+use std::hash::Hash;
+use serde::{Deserialize, Serialize};
+
+use timely::dataflow::Scope;
+
+use differential_dataflow::{Collection, ExchangeData};
+use crate::plan::{Plan, Render};
+use crate::{TraceManager, Time, Diff, Datum};
+
+/// Functions that aggregate the values of a group.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum AggFn {
+    /// The sum of the values, accounting for multiplicities.
+    Sum,
+    /// The least value.
+    Min,
+    /// The greatest value.
+    Max,
+    /// The number of tuples, accounting for multiplicities.
+    Count,
+}
+
+/// A plan stage aggregating the values of each group.
+///
+/// Tuples are grouped by their values at `group_key`, and each output tuple contains
+/// the values of the group key followed by the result of each of `aggregates`, which
+/// name the function to apply and the index of the values to which it applies.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Aggregate<V: Datum> {
+    /// Plan for the data source.
+    pub plan: Box<Plan<V>>,
+    /// Indices of the values that determine the group of a tuple.
+    pub group_key: Vec<usize>,
+    /// Pairs of value index and the function to apply to the values at that index.
+    pub aggregates: Vec<(usize, AggFn)>,
+}
+
+impl<V: ExchangeData+Hash+Datum> Render for Aggregate<V> {
+
+    type Value = V;
+
+    fn render<S: Scope<Timestamp = Time>>(
+        &self,
+        scope: &mut S,
+        collections: &mut std::collections::HashMap<Plan<Self::Value>, Collection<S, Vec<Self::Value>, Diff>>,
+        arrangements: &mut TraceManager<Self::Value>,
+    ) -> Collection<S, Vec<Self::Value>, Diff>
+    {
+        use differential_dataflow::operators::Reduce;
+
+        let group_key = self.group_key.clone();
+        let aggregates = self.aggregates.clone();
+
+        self.plan
+            .render(scope, collections, arrangements)
+            .map(move |tuple| (group_key.iter().map(|index| tuple[*index].clone()).collect::<Vec<_>>(), tuple))
+            .reduce_named("Aggregate", move |_key, input, output| {
+                let results =
+                aggregates
+                    .iter()
+                    .map(|(index, function)| V::aggregate(function, input.iter().map(|(tuple, diff)| (&tuple[*index], *diff))))
+                    .collect::<Vec<_>>();
+                output.push((results, 1));
+            })
+            .map(|(key, results)| key.into_iter().chain(results).collect())
+    }
+}
@@ -0,0 +1,86 @@
+//! Derived column expression plan.
+
+use std::hash::Hash;
+use serde::{Deserialize, Serialize};
+
+use timely::dataflow::Scope;
+
+use differential_dataflow::{Collection, ExchangeData};
+use crate::plan::{Plan, Render};
+use crate::{TraceManager, Time, Diff, Datum};
+
+/// Built-in operations combining two values.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum BinOp {
+    /// The sum of two numbers.
+    Add,
+    /// The difference of two numbers.
+    Subtract,
+    /// The product of two numbers.
+    Multiply,
+    /// The concatenation of two strings or two vectors.
+    Concat,
+}
+
+/// An expression computing a value from the values of a tuple.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Expr<Value> {
+    /// The value at an index.
+    Column(usize),
+    /// A constant value.
+    Constant(Value),
+    /// An operation applied to the results of two expressions.
+    Binary(BinOp, Box<Expr<Value>>, Box<Expr<Value>>),
+}
+
+impl<Value: Datum+Clone> Expr<Value> {
+    /// Creates an expression applying `op` to the results of `left` and `right`.
+    pub fn binary(op: BinOp, left: Self, right: Self) -> Self {
+        Expr::Binary(op, Box::new(left), Box::new(right))
+    }
+    /// Evaluates the expression against the values of a tuple.
+    ///
+    /// Panics if an index is out of bounds, or if an operation does not apply to its arguments.
+    pub fn evaluate(&self, values: &[Value]) -> Value {
+        match self {
+            Expr::Column(index) => values[*index].clone(),
+            Expr::Constant(value) => value.clone(),
+            Expr::Binary(op, left, right) => Value::binary(op, &left.evaluate(values), &right.evaluate(values)),
+        }
+    }
+}
+
+/// A plan stage appending derived values to each tuple.
+///
+/// Each of `exprs` is evaluated against the input tuple, and the results are appended
+/// to the tuple in order. Expressions do not observe the results of other expressions.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Extend<V: Datum> {
+    /// Plan for the data source.
+    pub plan: Box<Plan<V>>,
+    /// Expressions whose results are appended to each tuple.
+    pub exprs: Vec<Expr<V>>,
+}
+
+impl<V: ExchangeData+Hash+Datum> Render for Extend<V> {
+
+    type Value = V;
+
+    fn render<S: Scope<Timestamp = Time>>(
+        &self,
+        scope: &mut S,
+        collections: &mut std::collections::HashMap<Plan<Self::Value>, Collection<S, Vec<Self::Value>, Diff>>,
+        arrangements: &mut TraceManager<Self::Value>,
+    ) -> Collection<S, Vec<Self::Value>, Diff>
+    {
+        let exprs = self.exprs.clone();
+
+        self.plan
+            .render(scope, collections, arrangements)
+            .map(move |mut tuple| {
+                let derived = exprs.iter().map(|expr| expr.evaluate(&tuple[..])).collect::<Vec<_>>();
+                tuple.extend(derived);
+                tuple
+            })
+    }
+}
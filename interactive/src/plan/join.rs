@@ -33,68 +33,13 @@ impl<V: ExchangeData+Hash+Datum> Render for Join<V> {
         arrangements: &mut TraceManager<Self::Value>,
     ) -> Collection<S, Vec<Self::Value>, Diff>
     {
-        use differential_dataflow::operators::arrange::ArrangeByKey;
-
         // acquire arrangements for each input.
         let keys1 = self.keys.iter().map(|key| key.0).collect::<Vec<_>>();
-        let mut trace1 =
-        if let Some(arrangement) = arrangements.get_keyed(&self.plan1, &keys1[..]) {
-            arrangement
-        }
-        else {
-            let keys = keys1.clone();
-            let arrangement =
-            self.plan1
-                .render(scope, collections, arrangements)
-                .map(move |tuple|
-                    (
-                        // TODO: Re-use `tuple` for values.
-                        keys.iter().map(|index| tuple[*index].clone()).collect::<Vec<_>>(),
-                        tuple
-                            .into_iter()
-                            .enumerate()
-                            .filter(|(index,_value)| !keys.contains(index))
-                            .map(|(_index,value)| value)
-                            .collect::<Vec<_>>(),
-                    )
-                )
-                .arrange_by_key();
-
-            arrangements.set_keyed(&self.plan1, &keys1[..], &arrangement.trace);
-            arrangement.trace
-        };
+        let arrange1 = self.plan1.render_keyed(&keys1[..], scope, collections, arrangements);
 
         // extract relevant fields for each index.
         let keys2 = self.keys.iter().map(|key| key.1).collect::<Vec<_>>();
-        let mut trace2 =
-        if let Some(arrangement) = arrangements.get_keyed(&self.plan2, &keys2[..]) {
-            arrangement
-        }
-        else {
-            let keys = keys2.clone();
-            let arrangement =
-            self.plan2
-                .render(scope, collections, arrangements)
-                .map(move |tuple|
-                    (
-                        // TODO: Re-use `tuple` for values.
-                        keys.iter().map(|index| tuple[*index].clone()).collect::<Vec<_>>(),
-                        tuple
-                            .into_iter()
-                            .enumerate()
-                            .filter(|(index,_value)| !keys.contains(index))
-                            .map(|(_index,value)| value)
-                            .collect::<Vec<_>>(),
-                    )
-                )
-                .arrange_by_key();
-
-            arrangements.set_keyed(&self.plan2, &keys2[..], &arrangement.trace);
-            arrangement.trace
-        };
-
-        let arrange1 = trace1.import(scope);
-        let arrange2 = trace2.import(scope);
+        let arrange2 = self.plan2.render_keyed(&keys2[..], scope, collections, arrangements);
 
         arrange1
             .join_core(&arrange2, |keys, vals1, vals2| {
@@ -107,3 +52,79 @@ impl<V: ExchangeData+Hash+Datum> Render for Join<V> {
             })
     }
 }
+
+/// A plan stage retaining tuples whose values at `key_indices` are present in `keys`.
+///
+/// The output tuples are those of `plan`, and have the same arity. Each key is matched at most once,
+/// however many tuples of `keys` present it.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Semijoin<Value: Datum> {
+    /// Plan for the input to restrict.
+    pub plan: Box<Plan<Value>>,
+    /// Plan for the keys, whose tuples have arity `key_indices.len()`.
+    pub keys: Box<Plan<Value>>,
+    /// Indices of `plan` tuples to match against `keys` tuples.
+    pub key_indices: Vec<usize>,
+}
+
+impl<V: ExchangeData+Hash+Datum> Render for Semijoin<V> {
+
+    type Value = V;
+
+    fn render<S: Scope<Timestamp = Time>>(
+        &self,
+        scope: &mut S,
+        collections: &mut std::collections::HashMap<Plan<Self::Value>, Collection<S, Vec<Self::Value>, Diff>>,
+        arrangements: &mut TraceManager<Self::Value>,
+    ) -> Collection<S, Vec<Self::Value>, Diff>
+    {
+        use differential_dataflow::operators::{Join, Threshold};
+
+        // Keys are made distinct, so that each is matched once however many tuples present it.
+        let keys = self.keys.render(scope, collections, arrangements).distinct();
+        let indices = self.key_indices.clone();
+        self.plan
+            .render(scope, collections, arrangements)
+            .map(move |tuple| (indices.iter().map(|index| tuple[*index].clone()).collect::<Vec<_>>(), tuple))
+            .semijoin(&keys)
+            .map(|(_key, tuple)| tuple)
+    }
+}
+
+/// A plan stage retaining tuples whose values at `key_indices` are absent from `keys`.
+///
+/// The output tuples are those of `plan`, and have the same arity. Each key is matched at most once,
+/// however many tuples of `keys` present it.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Antijoin<Value: Datum> {
+    /// Plan for the input to restrict.
+    pub plan: Box<Plan<Value>>,
+    /// Plan for the keys, whose tuples have arity `key_indices.len()`.
+    pub keys: Box<Plan<Value>>,
+    /// Indices of `plan` tuples to match against `keys` tuples.
+    pub key_indices: Vec<usize>,
+}
+
+impl<V: ExchangeData+Hash+Datum> Render for Antijoin<V> {
+
+    type Value = V;
+
+    fn render<S: Scope<Timestamp = Time>>(
+        &self,
+        scope: &mut S,
+        collections: &mut std::collections::HashMap<Plan<Self::Value>, Collection<S, Vec<Self::Value>, Diff>>,
+        arrangements: &mut TraceManager<Self::Value>,
+    ) -> Collection<S, Vec<Self::Value>, Diff>
+    {
+        use differential_dataflow::operators::{Join, Threshold};
+
+        // Keys are made distinct, so that each is matched once however many tuples present it.
+        let keys = self.keys.render(scope, collections, arrangements).distinct();
+        let indices = self.key_indices.clone();
+        self.plan
+            .render(scope, collections, arrangements)
+            .map(move |tuple| (indices.iter().map(|index| tuple[*index].clone()).collect::<Vec<_>>(), tuple))
+            .antijoin(&keys)
+            .map(|(_key, tuple)| tuple)
+    }
+}
@@ -5,22 +5,30 @@ use serde::{Deserialize, Serialize};
 
 use timely::dataflow::Scope;
 use differential_dataflow::{Collection, ExchangeData};
+use differential_dataflow::operators::arrange::Arranged;
 
 use crate::{TraceManager, Time, Diff};
+use crate::manager::KeysValsHandle;
 
+pub mod aggregate;
 // pub mod count;
+pub mod extend;
 pub mod filter;
 pub mod join;
 pub mod map;
 pub mod sfw;
+pub mod top_k;
 
 use crate::Datum;
 
+pub use self::aggregate::{Aggregate, AggFn};
 // pub use self::count::Count;
+pub use self::extend::{Extend, Expr, BinOp};
 pub use self::filter::{Filter, Predicate};
-pub use self::join::Join;
+pub use self::join::{Join, Semijoin, Antijoin};
 pub use self::sfw::MultiwayJoin;
 pub use self::map::Map;
+pub use self::top_k::TopK;
 
 /// A type that can be rendered as a collection.
 pub trait Render : Sized {
@@ -49,18 +57,37 @@ pub enum Plan<V: Datum> {
     Distinct(Box<Plan<V>>),
     /// Concat
     Concat(Vec<Plan<V>>),
+    /// Union, with multiplicities if `all` and as distinct tuples otherwise
+    Union {
+        /// The collections to merge.
+        plans: Vec<Plan<V>>,
+        /// Retains multiplicities, rather than reducing to distinct tuples.
+        all: bool,
+    },
     /// Consolidate
     Consolidate(Box<Plan<V>>),
     /// Equijoin
     Join(Join<V>),
     /// MultiwayJoin
     MultiwayJoin(MultiwayJoin<V>),
+    /// Restriction to tuples whose keys are present in another collection
+    Semijoin(Semijoin<V>),
+    /// Restriction to tuples whose keys are absent from another collection
+    Antijoin(Antijoin<V>),
     /// Negation
     Negate(Box<Plan<V>>),
     /// Filters bindings by one of the built-in predicates
     Filter(Filter<V>),
+    /// Appends values computed from each tuple
+    Extend(Extend<V>),
+    /// Retains a limited number of the least tuples of each group
+    TopK(TopK<V>),
+    /// Aggregates the values of each group
+    Aggregate(Aggregate<V>),
     /// Sources data from another relation.
     Source(String),
+    /// Sources data from another relation, arranged by the values at the indicated indices.
+    SourceKeyed(String, Vec<usize>),
     /// Prints resulting updates.
     Inspect(String, Box<Plan<V>>),
 }
@@ -85,6 +112,13 @@ impl<V: ExchangeData+Hash+Datum> Plan<V> {
     pub fn concatenate(plans: Vec<Self>) -> Self {
         Plan::Concat(plans)
     }
+    /// Merges multiple collections, as SQL's `UNION ALL` if `all` and as its `UNION` otherwise.
+    ///
+    /// With `all` the union retains multiplicities, as does `concatenate`, and without it the union
+    /// contains each tuple of the merged collections once.
+    pub fn union(plans: Vec<Self>, all: bool) -> Self {
+        Plan::Union { plans, all }
+    }
     /// Merges multiple collections.
     pub fn consolidate(self) -> Self {
         Plan::Consolidate(Box::new(self))
@@ -113,6 +147,30 @@ impl<V: ExchangeData+Hash+Datum> Plan<V> {
             equalities,
         })
     }
+    /// Retains tuples whose values at `key_indices` are present in `keys`.
+    pub fn semijoin(self, keys: Plan<V>, key_indices: Vec<usize>) -> Self {
+        Plan::Semijoin(Semijoin {
+            plan: Box::new(self),
+            keys: Box::new(keys),
+            key_indices,
+        })
+    }
+    /// Retains tuples whose values at `key_indices` are absent from `keys`.
+    pub fn antijoin(self, keys: Plan<V>, key_indices: Vec<usize>) -> Self {
+        Plan::Antijoin(Antijoin {
+            plan: Box::new(self),
+            keys: Box::new(keys),
+            key_indices,
+        })
+    }
+    /// Retains tuples with no match in `other`, matching values by the specified pairs of indices.
+    ///
+    /// Each pair `(index1, index2)` equates the value at `index1` in tuples of `self` with the value
+    /// at `index2` in tuples of `other`. The output tuples are those of `self`, unchanged.
+    pub fn antijoin_by(self, other: Plan<V>, keys: Vec<(usize, usize)>) -> Self {
+        let (key_indices, other_indices) = keys.into_iter().unzip();
+        self.antijoin(other.project(other_indices), key_indices)
+    }
     /// Negates a collection (negating multiplicities).
     pub fn negate(self) -> Self {
         Plan::Negate(Box::new(self))
@@ -121,10 +179,32 @@ impl<V: ExchangeData+Hash+Datum> Plan<V> {
     pub fn filter(self, predicate: Predicate<V>) -> Self {
         Plan::Filter(Filter { predicate, plan: Box::new(self) } )
     }
+    /// Appends to each tuple the results of evaluating `exprs` against it.
+    pub fn extend(self, exprs: Vec<Expr<V>>) -> Self {
+        Plan::Extend(Extend { plan: Box::new(self), exprs })
+    }
+    /// Retains the `limit` tuples of each group that are least by their values at `order_key`.
+    ///
+    /// Groups are determined by the values at `group_key`, and ties are broken by the full tuple.
+    pub fn top_k(self, group_key: Vec<usize>, order_key: Vec<usize>, limit: usize) -> Self {
+        Plan::TopK(TopK { plan: Box::new(self), group_key, order_key, limit })
+    }
+    /// Groups tuples by their values at `group_key`, and applies each of `aggregates` to each group.
+    ///
+    /// Each aggregate names the index of the values to aggregate and the function to apply.
+    pub fn aggregate(self, group_key: Vec<usize>, aggregates: Vec<(usize, AggFn)>) -> Self {
+        Plan::Aggregate(Aggregate { plan: Box::new(self), group_key, aggregates })
+    }
     /// Loads a source of data by name.
     pub fn source(name: &str) -> Self {
         Plan::Source(name.to_string())
     }
+    /// Loads a source of data by name, using or installing an arrangement by the values at `keys`.
+    ///
+    /// Joins on `keys` will re-use the arrangement, rather than arrange the source again.
+    pub fn source_keyed(name: &str, keys: Vec<usize>) -> Self {
+        Plan::SourceKeyed(name.to_string(), keys)
+    }
     /// Prints each tuple prefixed by `text`.
     pub fn inspect(self, text: &str) -> Self {
         Plan::Inspect(text.to_string(), Box::new(self))
@@ -161,8 +241,8 @@ impl<V: ExchangeData+Hash+Datum> Render for Plan<V> {
                     use differential_dataflow::trace::implementations::{KeyBuilder, KeySpine};
 
                     let input =
-                    if let Some(mut trace) = arrangements.get_unkeyed(&self) {
-                        trace.import(scope)
+                    if let Some(arranged) = arrangements.import_unkeyed(&self, scope) {
+                        arranged
                     }
                     else {
                         let input_arrangement = distinct.render(scope, collections, arrangements).arrange_by_self();
@@ -191,9 +271,19 @@ impl<V: ExchangeData+Hash+Datum> Render for Plan<V> {
                         .concatenate(plans)
                         .as_collection()
                 }
+                Plan::Union { plans, all } => {
+                    // Rendered as the plans it abbreviates, which share their collections and arrangements.
+                    let concat = Plan::Concat(plans.clone());
+                    if *all {
+                        concat.render(scope, collections, arrangements)
+                    }
+                    else {
+                        concat.distinct().render(scope, collections, arrangements)
+                    }
+                },
                 Plan::Consolidate(consolidate) => {
-                    if let Some(mut trace) = arrangements.get_unkeyed(&self) {
-                        trace.import(scope).as_collection(|k,&()| k.clone())
+                    if let Some(arranged) = arrangements.import_unkeyed(&self, scope) {
+                        arranged.as_collection(|k,&()| k.clone())
                     }
                     else {
                         consolidate.render(scope, collections, arrangements).consolidate()
@@ -201,17 +291,40 @@ impl<V: ExchangeData+Hash+Datum> Render for Plan<V> {
                 },
                 Plan::Join(join) => join.render(scope, collections, arrangements),
                 Plan::MultiwayJoin(join) => join.render(scope, collections, arrangements),
+                Plan::Semijoin(semijoin) => semijoin.render(scope, collections, arrangements),
+                Plan::Antijoin(antijoin) => antijoin.render(scope, collections, arrangements),
                 Plan::Negate(negate) => {
                     negate.render(scope, collections, arrangements).negate()
                 },
                 Plan::Filter(filter) => filter.render(scope, collections, arrangements),
+                Plan::Extend(extend) => extend.render(scope, collections, arrangements),
+                Plan::TopK(top_k) => top_k.render(scope, collections, arrangements),
+                Plan::Aggregate(aggregate) => aggregate.render(scope, collections, arrangements),
                 Plan::Source(source) => {
                     arrangements
-                        .get_unkeyed(self)
+                        .import_unkeyed(self, scope)
                         .expect(&format!("Failed to find source collection: {:?}", source))
-                        .import(scope)
                         .as_collection(|k,()| k.to_vec())
                 },
+                Plan::SourceKeyed(source, keys) => {
+                    let keys = keys.clone();
+                    let mut distinct = keys.clone();
+                    distinct.sort();
+                    distinct.dedup();
+                    let key_count = distinct.len();
+                    Plan::Source(source.clone())
+                        .render_keyed(&keys[..], scope, collections, arrangements)
+                        .as_collection(move |key, val| {
+                            // interleave key and value fields back into their original positions.
+                            let mut vals = val.iter();
+                            (0 .. key_count + val.len())
+                                .map(|index| match keys.iter().position(|k| *k == index) {
+                                    Some(position) => key[position].clone(),
+                                    None => vals.next().expect("Insufficient values").clone(),
+                                })
+                                .collect()
+                        })
+                },
                 Plan::Inspect(text, plan) => {
                     let text = text.clone();
                     plan.render(scope, collections, arrangements)
@@ -225,3 +338,50 @@ impl<V: ExchangeData+Hash+Datum> Render for Plan<V> {
         collections.get(self).expect("We just installed this").clone()
     }
 }
+
+impl<V: ExchangeData+Hash+Datum> Plan<V> {
+    /// Acquires an arrangement of the plan keyed by the values at `keys`.
+    ///
+    /// A registered arrangement is imported if one exists, and otherwise the plan is rendered,
+    /// arranged, and the arrangement registered. Keyed sources are registered as their source,
+    /// so that all uses of a source with the same keys share one arrangement.
+    pub fn render_keyed<S: Scope<Timestamp = Time>>(
+        &self,
+        keys: &[usize],
+        scope: &mut S,
+        collections: &mut std::collections::HashMap<Plan<V>, Collection<S, Vec<V>, Diff>>,
+        arrangements: &mut TraceManager<V>,
+    ) -> Arranged<S, KeysValsHandle<V>>
+    {
+        use differential_dataflow::operators::arrange::ArrangeByKey;
+
+        if let Plan::SourceKeyed(source, _) = self {
+            return Plan::Source(source.clone()).render_keyed(keys, scope, collections, arrangements);
+        }
+
+        if let Some(arrangement) = arrangements.import_keyed(self, keys, scope) {
+            arrangement
+        }
+        else {
+            let keys2 = keys.to_vec();
+            let arrangement =
+            self.render(scope, collections, arrangements)
+                .map(move |tuple|
+                    (
+                        // TODO: Re-use `tuple` for values.
+                        keys2.iter().map(|index| tuple[*index].clone()).collect::<Vec<_>>(),
+                        tuple
+                            .into_iter()
+                            .enumerate()
+                            .filter(|(index,_value)| !keys2.contains(index))
+                            .map(|(_index,value)| value)
+                            .collect::<Vec<_>>(),
+                    )
+                )
+                .arrange_by_key();
+
+            arrangements.set_keyed(self, keys, &arrangement.trace);
+            arrangement
+        }
+    }
+}
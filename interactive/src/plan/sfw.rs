@@ -140,9 +140,8 @@ impl<V: ExchangeData+Hash+Datum> Render for MultiwayJoin<V> {
             }
             let changes =
             arrangements
-                .get_unkeyed(&plan)
+                .import_unkeyed(&plan, scope)
                 .expect("Surely we just ensured this")
-                .import(scope)
                 .as_collection(|val,&()| val.clone())
                 .map(move |tuple| attributes_init.iter().map(|&(attr,_)|
                     tuple[attr].clone()).collect::<Vec<_>>()
@@ -215,7 +214,7 @@ impl<V: ExchangeData+Hash+Datum> Render for MultiwayJoin<V> {
 
                 let arrangement =
                 arrangements
-                    .get_keyed(&plan, &keys[..])
+                    .import_keyed(&plan, &keys[..], scope)
                     .expect("Surely we just ensured this");
 
                 let key_selector = move |change: &Vec<V>|
@@ -241,7 +240,7 @@ impl<V: ExchangeData+Hash+Datum> Render for MultiwayJoin<V> {
                     .enter(inner)
                     ;
 
-                for (join_idx, key_selector, mut trace) in join_plan.into_iter() {
+                for (join_idx, key_selector, arranged) in join_plan.into_iter() {
 
                     // Use alt or neu timestamps based on relative indices.
                     // Must have an `if` statement here as the two arrangement have different
@@ -249,11 +248,11 @@ impl<V: ExchangeData+Hash+Datum> Render for MultiwayJoin<V> {
                     // tuple in the cursor.
                     changes =
                     if join_idx < index {
-                        let arrangement = trace.import(scope).enter_at(inner, |_,_,t| AltNeu::alt(t.clone()), |_| unimplemented!());
+                        let arrangement = arranged.enter_at(inner, |_,_,t| AltNeu::alt(t.clone()), |_| unimplemented!());
                         differential_dogs3::operators::propose(&changes, arrangement, key_selector)
                     }
                     else {
-                        let arrangement = trace.import(scope).enter_at(inner, |_,_,t| AltNeu::neu(t.clone()), |_| unimplemented!());
+                        let arrangement = arranged.enter_at(inner, |_,_,t| AltNeu::neu(t.clone()), |_| unimplemented!());
                         differential_dogs3::operators::propose(&changes, arrangement, key_selector)
                     }
                     .map(|(mut prefix, extensions)| { prefix.extend(extensions.into_iter()); prefix })
@@ -0,0 +1,70 @@
+//! Per-group limit expression plan.
+
+use std::hash::Hash;
+use serde::{Deserialize, Serialize};
+
+use timely::dataflow::Scope;
+
+use differential_dataflow::{Collection, ExchangeData};
+use crate::plan::{Plan, Render};
+use crate::{TraceManager, Time, Diff, Datum};
+
+/// A plan stage retaining the `limit` least tuples of each group.
+///
+/// Tuples are grouped by their values at `group_key`, and ordered by their values at
+/// `order_key`. Ties are broken by the ordering of the full tuples, so that the retained
+/// tuples are determined by the input alone. The output tuples are those of `plan`.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct TopK<V: Datum> {
+    /// Plan for the data source.
+    pub plan: Box<Plan<V>>,
+    /// Indices of the values that determine the group of a tuple.
+    pub group_key: Vec<usize>,
+    /// Indices of the values by which tuples within a group are ordered.
+    pub order_key: Vec<usize>,
+    /// Number of tuples to retain for each group.
+    pub limit: usize,
+}
+
+impl<V: ExchangeData+Hash+Datum> Render for TopK<V> {
+
+    type Value = V;
+
+    fn render<S: Scope<Timestamp = Time>>(
+        &self,
+        scope: &mut S,
+        collections: &mut std::collections::HashMap<Plan<Self::Value>, Collection<S, Vec<Self::Value>, Diff>>,
+        arrangements: &mut TraceManager<Self::Value>,
+    ) -> Collection<S, Vec<Self::Value>, Diff>
+    {
+        use differential_dataflow::operators::arrange::ArrangeByKey;
+        use differential_dataflow::trace::implementations::{ValBuilder, ValSpine};
+
+        let group_key = self.group_key.clone();
+        let order_key = self.order_key.clone();
+        let limit = self.limit as Diff;
+
+        self.plan
+            .render(scope, collections, arrangements)
+            .map(move |tuple| (group_key.iter().map(|index| tuple[*index].clone()).collect::<Vec<_>>(), tuple))
+            .arrange_by_key()
+            .reduce_abelian::<_,_,_,ValBuilder<_,_,_,_>,ValSpine<_,_,_,_>>("TopK", move |_key, input, output| {
+                let mut records =
+                input
+                    .iter()
+                    .filter(|(_tuple, diff)| *diff > 0)
+                    .map(|(tuple, diff)| (order_key.iter().map(|index| &tuple[*index]).collect::<Vec<_>>(), *tuple, *diff))
+                    .collect::<Vec<_>>();
+                records.sort();
+
+                let mut remaining = limit;
+                for (_order, tuple, diff) in records {
+                    if remaining == 0 { break; }
+                    let count = std::cmp::min(diff, remaining);
+                    output.push((tuple.clone(), count));
+                    remaining -= count;
+                }
+            })
+            .as_collection(|_key, tuple| tuple.clone())
+    }
+}
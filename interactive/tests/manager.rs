@@ -0,0 +1,261 @@
+use std::time::Duration;
+
+use timely::dataflow::ProbeHandle;
+use timely::dataflow::operators::Probe;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::arrange::ArrangeByKey;
+
+use interactive::{Plan, TraceManager};
+use interactive::concrete::Value;
+
+#[test]
+fn budget_evicts_unpinned() {
+
+    timely::execute_directly(|worker| {
+
+        // four arrangements of the same collection, keyed by each of its four columns.
+        let mut probe = ProbeHandle::new();
+        let (mut input, unkeyed, traces) = worker.dataflow::<Duration,_,_>(|scope| {
+            use differential_dataflow::operators::arrange::ArrangeBySelf;
+            let (input, data) = scope.new_collection::<Vec<Value>, isize>();
+            let unkeyed = data.arrange_by_self();
+            unkeyed.stream.probe_with(&mut probe);
+            let traces =
+            (0 .. 4)
+                .map(|index| {
+                    let arranged = data.map(move |tuple| (vec![tuple[index].clone()], tuple)).arrange_by_key();
+                    arranged.stream.probe_with(&mut probe);
+                    arranged.trace
+                })
+                .collect::<Vec<_>>();
+            (input, unkeyed.trace, traces)
+        });
+
+        for x in 0 .. 100 {
+            input.insert(vec![Value::Usize(x); 4]);
+        }
+        input.advance_to(Duration::from_secs(1));
+        input.flush();
+        worker.step_while(|| probe.less_than(input.time()));
+
+        // a budget of one arrangement's worth of updates, with the first arrangement pinned.
+        let mut manager = TraceManager::<Value>::new();
+        manager.set_budget(100 * std::mem::size_of::<(Vec<Value>, Vec<Value>, Duration, isize)>());
+        let source = Plan::source("data");
+        manager.pin(&source, Some(&[0]));
+
+        // the unkeyed source does not count against the budget.
+        manager.set_unkeyed(&source, &unkeyed);
+
+        for (index, trace) in traces.iter().enumerate() {
+            manager.advance_time(&Duration::from_secs(1));
+            manager.set_keyed(&source, &[index], trace);
+        }
+
+        // the pinned and most recently registered arrangements survive, as does the source.
+        assert!(manager.get_unkeyed(&source).is_some());
+        assert!(manager.get_keyed(&source, &[0]).is_some());
+        assert!(manager.get_keyed(&source, &[1]).is_none());
+        assert!(manager.get_keyed(&source, &[2]).is_none());
+        assert!(manager.get_keyed(&source, &[3]).is_some());
+
+        // once unpinned, the first arrangement may be released in favor of one in use.
+        manager.unpin(&source, Some(&[0]));
+        manager.advance_time(&Duration::from_secs(1));
+        assert!(manager.get_keyed(&source, &[3]).is_some());
+        manager.set_budget(100 * std::mem::size_of::<(Vec<Value>, Vec<Value>, Duration, isize)>());
+        assert!(manager.get_keyed(&source, &[0]).is_none());
+        assert!(manager.get_keyed(&source, &[3]).is_some());
+    });
+}
+
+#[test]
+fn imports_held_until_shutdown() {
+
+    timely::execute_directly(|worker| {
+
+        // three arrangements of the same collection, keyed by each of its three columns.
+        let mut probe = ProbeHandle::new();
+        let (mut input, traces) = worker.dataflow::<Duration,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<Vec<Value>, isize>();
+            let traces =
+            (0 .. 3)
+                .map(|index| {
+                    let arranged = data.map(move |tuple| (vec![tuple[index].clone()], tuple)).arrange_by_key();
+                    arranged.stream.probe_with(&mut probe);
+                    arranged.trace
+                })
+                .collect::<Vec<_>>();
+            (input, traces)
+        });
+
+        for x in 0 .. 100 {
+            input.insert(vec![Value::Usize(x); 3]);
+        }
+        input.advance_to(Duration::from_secs(1));
+        input.flush();
+        worker.step_while(|| probe.less_than(input.time()));
+
+        let mut manager = TraceManager::<Value>::new();
+        let source = Plan::source("data");
+        manager.set_keyed(&source, &[0], &traces[0]);
+
+        // a running dataflow imports the first arrangement, which is held without being pinned.
+        let dataflow = worker.next_dataflow_index();
+        worker.dataflow::<Duration,_,_>(|scope| {
+            assert!(manager.import_keyed(&source, &[0], scope).is_some());
+        });
+
+        manager.set_budget(100 * std::mem::size_of::<(Vec<Value>, Vec<Value>, Duration, isize)>());
+        for index in 1 .. 3 {
+            manager.advance_time(&Duration::from_secs(1));
+            manager.set_keyed(&source, &[index], &traces[index]);
+        }
+
+        assert!(manager.get_keyed(&source, &[0]).is_some());
+        assert!(manager.get_keyed(&source, &[1]).is_none());
+        assert!(manager.get_keyed(&source, &[2]).is_some());
+
+        // once the importing dataflow shuts down, the first arrangement may be released.
+        worker.drop_dataflow(dataflow);
+        manager.advance_time(&Duration::from_secs(1));
+        assert!(manager.get_keyed(&source, &[2]).is_some());
+        manager.set_budget(100 * std::mem::size_of::<(Vec<Value>, Vec<Value>, Duration, isize)>());
+        assert!(manager.get_keyed(&source, &[0]).is_none());
+        assert!(manager.get_keyed(&source, &[2]).is_some());
+    });
+}
+
+#[test]
+fn sizes_report_arrangements() {
+
+    use differential_dataflow::operators::arrange::ArrangeBySelf;
+    use interactive::VectorFrom;
+    use interactive::logging::ArrangementSize;
+
+    timely::execute_directly(|worker| {
+
+        // ten records, arranged by themselves and keyed by their first column, which takes four values.
+        let mut probe = ProbeHandle::new();
+        let (mut input, unkeyed, keyed) = worker.dataflow::<Duration,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<Vec<Value>, isize>();
+            let unkeyed = data.arrange_by_self();
+            let keyed = data.map(|tuple| (vec![tuple[0].clone()], tuple)).arrange_by_key();
+            unkeyed.stream.probe_with(&mut probe);
+            keyed.stream.probe_with(&mut probe);
+            (input, unkeyed.trace, keyed.trace)
+        });
+
+        for x in 0 .. 10 {
+            input.insert(vec![Value::Usize(x % 4), Value::Usize(x)]);
+        }
+        input.advance_to(Duration::from_secs(1));
+        input.remove(vec![Value::Usize(0), Value::Usize(0)]);
+        input.advance_to(Duration::from_secs(2));
+        input.flush();
+        worker.step_while(|| probe.less_than(input.time()));
+
+        let mut manager = TraceManager::<Value>::new();
+        let source = Plan::source("data");
+        manager.set_unkeyed(&source, &unkeyed);
+        manager.set_keyed(&source, &[0], &keyed);
+
+        // the batches may or may not have been merged, which determines whether the removed record is counted twice.
+        let sizes = manager.sizes();
+        assert_eq!(sizes.len(), 2);
+        assert_eq!(sizes[0].keys, None);
+        assert_eq!(sizes[0].update_count, 11);
+        assert!(sizes[0].key_count == 10 || sizes[0].key_count == 11);
+        assert_eq!(sizes[0].key_count, sizes[0].val_count);
+        assert_eq!(sizes[1].keys, Some(vec![0]));
+        assert_eq!(sizes[1].update_count, 11);
+        assert!(sizes[1].key_count == 4 || sizes[1].key_count == 5);
+        assert!(sizes[1].val_count == 10 || sizes[1].val_count == 11);
+
+        let size = ArrangementSize {
+            plan: "data".to_string(),
+            keys: Some(vec![0]),
+            key_count: 4,
+            val_count: 10,
+            update_count: 11,
+        };
+        assert_eq!(Value::vector_from(size), vec![
+            Value::String("data".to_string()),
+            Value::Bool(true),
+            Value::Vector(vec![Value::Usize(0)]),
+            Value::Usize(4),
+            Value::Usize(10),
+            Value::Usize(11),
+        ]);
+    });
+}
+
+#[test]
+fn listings_report_registrations() {
+
+    use differential_dataflow::operators::arrange::ArrangeBySelf;
+
+    timely::execute_directly(|worker| {
+
+        let (unkeyed, keyed) = worker.dataflow::<Duration,_,_>(|scope| {
+            let (_input, data) = scope.new_collection::<Vec<Value>, isize>();
+            let unkeyed = data.arrange_by_self().trace;
+            let keyed = data.map(|tuple| (vec![tuple[0].clone()], tuple)).arrange_by_key().trace;
+            (unkeyed, keyed)
+        });
+
+        let mut manager = TraceManager::<Value>::new();
+        assert!(manager.list_unkeyed().is_empty());
+        assert!(manager.list_keyed().is_empty());
+
+        let source = Plan::source("data");
+        let distinct = Plan::source("other").distinct();
+        manager.set_unkeyed(&source, &unkeyed);
+        manager.set_unkeyed(&distinct, &unkeyed);
+        manager.set_keyed(&source, &[1], &keyed);
+        manager.set_keyed(&source, &[0], &keyed);
+
+        let mut unkeyed_names = vec!["data".to_string(), format!("{:?}", distinct)];
+        unkeyed_names.sort();
+        assert_eq!(manager.list_unkeyed(), unkeyed_names);
+        assert_eq!(manager.list_keyed(), vec![("data".to_string(), vec![0]), ("data".to_string(), vec![1])]);
+
+        manager.advance_time(&Duration::from_secs(2));
+        assert_eq!(manager.logical_compaction(&source, None), Some(vec![Duration::from_secs(2)]));
+        assert_eq!(manager.logical_compaction(&source, Some(&[0])), Some(vec![Duration::from_secs(2)]));
+        assert_eq!(manager.logical_compaction(&source, Some(&[2])), None);
+        assert_eq!(manager.logical_compaction(&Plan::source("absent"), None), None);
+    });
+}
+
+#[test]
+fn published_traces_registered_as_sources() {
+
+    use differential_dataflow::operators::arrange::{ArrangeBySelf, PublishedTrace, TracePublisher};
+    use differential_dataflow::trace::TraceReader;
+    use interactive::Manager;
+
+    timely::execute_directly(|worker| {
+
+        let unkeyed = worker.dataflow::<Duration,_,_>(|scope| {
+            let (_input, data) = scope.new_collection::<Vec<Value>, isize>();
+            data.arrange_by_self().trace
+        });
+
+        // a trace published with the manager is registered as the source of its name.
+        let mut manager = Manager::<Value>::new();
+        let published = PublishedTrace::new(unkeyed);
+        manager.traces.publish("data", published.clone());
+        assert_eq!(manager.traces.list_unkeyed(), vec!["data".to_string()]);
+        assert!(manager.traces.get_unkeyed(&Plan::source("data")).is_some());
+
+        // the manager and the publisher share the trace's compaction frontier.
+        manager.advance_time(&Duration::from_secs(2));
+        assert_eq!(published.with_trace(|trace| trace.get_logical_compaction().to_vec()), Some(vec![Duration::from_secs(2)]));
+
+        // shutting down the manager releases the trace for all of its handles.
+        manager.shutdown(worker);
+        assert!(published.is_released());
+    });
+}
@@ -0,0 +1,412 @@
+use std::time::Duration;
+
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::arrange::ArrangeBySelf;
+
+use interactive::{Plan, TraceManager};
+use interactive::concrete::Value;
+use interactive::plan::{Render, AggFn};
+
+/// Renders `plan` against a source named "data" containing `tuples`, and returns the consolidated output.
+fn render(plan: Plan<Value>, tuples: Vec<Vec<Value>>) -> Vec<(Vec<Value>, isize)> {
+
+    let captured = timely::execute_directly(move |worker| {
+
+        let mut traces = TraceManager::new();
+        let (mut input, captured) = worker.dataflow::<Duration,_,_>(|scope| {
+            let (input, data) = scope.new_collection();
+            traces.set_unkeyed(&Plan::Source("data".to_string()), &data.arrange_by_self().trace);
+            let mut collections = std::collections::HashMap::new();
+            let captured = plan.render(scope, &mut collections, &mut traces).inner.capture();
+            (input, captured)
+        });
+
+        for tuple in tuples {
+            input.insert(tuple);
+        }
+
+        captured
+    });
+
+    let mut results = captured
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .map(|(tuple, _time, diff)| (tuple, diff))
+        .collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate(&mut results);
+    results
+}
+
+/// Serializes and deserializes `plan`, asserting that the result is unchanged.
+fn round_trip(plan: Plan<Value>) {
+    let bytes = bincode::serialize(&plan).expect("bincode: serialization failed");
+    let decoded: Plan<Value> = bincode::deserialize(&bytes[..]).expect("bincode: deserialization failed");
+    assert_eq!(decoded, plan);
+}
+
+fn tuple(values: &[usize]) -> Vec<Value> {
+    values.iter().map(|x| Value::Usize(*x)).collect()
+}
+
+#[test]
+fn top_k_limits_groups() {
+    // group 0 orders by the second value with a tie at the limit; group 1 has fewer tuples than the limit.
+    let plan = Plan::source("data").top_k(vec![0], vec![1], 2);
+    let results = render(plan, vec![
+        tuple(&[0, 3, 0]),
+        tuple(&[0, 1, 0]),
+        tuple(&[0, 2, 5]),
+        tuple(&[0, 2, 4]),
+        tuple(&[1, 7, 0]),
+    ]);
+    assert_eq!(results, vec![
+        (tuple(&[0, 1, 0]), 1),
+        (tuple(&[0, 2, 4]), 1),
+        (tuple(&[1, 7, 0]), 1),
+    ]);
+}
+
+#[test]
+fn top_k_counts_multiplicities() {
+    let plan = Plan::source("data").top_k(vec![], vec![0], 3);
+    let results = render(plan, vec![tuple(&[1]), tuple(&[1]), tuple(&[2]), tuple(&[2])]);
+    assert_eq!(results, vec![(tuple(&[1]), 2), (tuple(&[2]), 1)]);
+}
+
+#[test]
+fn top_k_serialization() {
+    round_trip(Plan::source("data").top_k(vec![0, 2], vec![1], 10));
+}
+
+#[test]
+fn aggregate_functions() {
+    let plan = Plan::source("data").aggregate(vec![0], vec![(1, AggFn::Sum), (1, AggFn::Min), (1, AggFn::Max), (1, AggFn::Count)]);
+    let results = render(plan, vec![
+        tuple(&[0, 3]),
+        tuple(&[0, 3]),
+        tuple(&[0, 5]),
+        tuple(&[1, 7]),
+    ]);
+    assert_eq!(results, vec![
+        (tuple(&[0, 11, 3, 5, 3]), 1),
+        (tuple(&[1, 7, 7, 7, 1]), 1),
+    ]);
+}
+
+#[test]
+fn aggregate_durations() {
+    let plan = Plan::source("data").aggregate(vec![], vec![(0, AggFn::Sum), (0, AggFn::Max)]);
+    let results = render(plan, vec![
+        vec![Value::Duration(Duration::from_secs(1))],
+        vec![Value::Duration(Duration::from_secs(2))],
+    ]);
+    assert_eq!(results, vec![(vec![Value::Duration(Duration::from_secs(3)), Value::Duration(Duration::from_secs(2))], 1)]);
+}
+
+#[test]
+#[should_panic(expected = "Sum requires Usize or Duration values")]
+fn aggregate_mismatched_type() {
+    let plan = Plan::source("data").aggregate(vec![], vec![(0, AggFn::Sum)]);
+    render(plan, vec![vec![Value::String("text".to_string())]]);
+}
+
+#[test]
+fn aggregate_negative_differences() {
+    use interactive::Datum;
+    // negative differences are accumulated exactly, as long as the result is representable.
+    let usizes = vec![(Value::Usize(5), 2), (Value::Usize(3), -1), (Value::Usize(1), 1)];
+    assert_eq!(Value::aggregate(&AggFn::Sum, usizes.iter().map(|(v, d)| (v, *d))), Value::Usize(8));
+    assert_eq!(Value::aggregate(&AggFn::Count, usizes.iter().map(|(v, d)| (v, *d))), Value::Usize(2));
+    let durations = vec![(Value::Duration(Duration::from_secs(3)), 1), (Value::Duration(Duration::from_millis(500)), -2)];
+    assert_eq!(Value::aggregate(&AggFn::Sum, durations.iter().map(|(v, d)| (v, *d))), Value::Duration(Duration::from_secs(2)));
+}
+
+#[test]
+#[should_panic(expected = "Sum of a group is not a valid Usize: -1")]
+fn aggregate_negative_sum() {
+    use interactive::Datum;
+    let usizes = vec![(Value::Usize(1), 1), (Value::Usize(2), -1)];
+    Value::aggregate(&AggFn::Sum, usizes.iter().map(|(v, d)| (v, *d)));
+}
+
+#[test]
+#[should_panic(expected = "Count of a group is not a valid Usize: -2")]
+fn aggregate_negative_count() {
+    use interactive::Datum;
+    let usizes = vec![(Value::Usize(1), -2)];
+    Value::aggregate(&AggFn::Count, usizes.iter().map(|(v, d)| (v, *d)));
+}
+
+#[test]
+fn aggregate_serialization() {
+    round_trip(Plan::source("data").aggregate(vec![0], vec![(1, AggFn::Sum), (2, AggFn::Count)]));
+}
+
+#[test]
+fn source_keyed_restores_tuples() {
+    let plan = Plan::source_keyed("data", vec![2, 0]);
+    let results = render(plan, vec![tuple(&[0, 1, 2]), tuple(&[3, 4, 5])]);
+    assert_eq!(results, vec![(tuple(&[0, 1, 2]), 1), (tuple(&[3, 4, 5]), 1)]);
+}
+
+#[test]
+fn source_keyed_shares_arrangement() {
+
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use timely::logging::{TimelyEvent, TimelyEventBuilder};
+
+    let arrangements = Arc::new(AtomicUsize::new(0));
+    let arrangements2 = Arc::clone(&arrangements);
+
+    let captured = timely::execute_directly(move |worker| {
+
+        // count the arrangement operators constructed by the joins.
+        worker
+            .log_register()
+            .insert::<TimelyEventBuilder,_>("timely", move |_time, data| {
+                if let Some(data) = data {
+                    for (_, event) in data.iter() {
+                        if let TimelyEvent::Operates(operates) = event {
+                            if operates.name == "ArrangeByKey" {
+                                arrangements2.fetch_add(1, Ordering::SeqCst);
+                            }
+                        }
+                    }
+                }
+            });
+
+        let mut traces = TraceManager::new();
+        let (mut input, captured) = worker.dataflow::<Duration,_,_>(|scope| {
+            let (input, data) = scope.new_collection();
+            traces.set_unkeyed(&Plan::Source("data".to_string()), &data.arrange_by_self().trace);
+            let keyed = Plan::source_keyed("data", vec![0]);
+            let plan = Plan::concatenate(vec![
+                keyed.clone().join(keyed.clone(), vec![(0, 0)]),
+                Plan::source("data").join(keyed, vec![(0, 0)]),
+            ]);
+            let mut collections = std::collections::HashMap::new();
+            let captured = plan.render(scope, &mut collections, &mut traces).inner.capture();
+            (input, captured)
+        });
+
+        input.insert(tuple(&[0, 1]));
+        input.insert(tuple(&[0, 2]));
+        input.insert(tuple(&[1, 3]));
+
+        captured
+    });
+
+    let mut results = captured
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .map(|(tuple, _time, diff)| (tuple, diff))
+        .collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate(&mut results);
+    assert_eq!(results, vec![
+        (tuple(&[0, 1, 1]), 2),
+        (tuple(&[0, 1, 2]), 2),
+        (tuple(&[0, 2, 1]), 2),
+        (tuple(&[0, 2, 2]), 2),
+        (tuple(&[1, 3, 3]), 2),
+    ]);
+
+    assert_eq!(arrangements.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn source_keyed_serialization() {
+    round_trip(Plan::source_keyed("data", vec![1, 0]));
+}
+
+#[test]
+fn antijoin_toggles_with_keys() {
+
+    // retains tuples of "left" whose first value does not match the second value of any tuple of "right".
+    let plan = Plan::source("left").antijoin_by(Plan::source("right"), vec![(0, 1)]);
+
+    let captured = timely::execute_directly(move |worker| {
+
+        let mut traces = TraceManager::new();
+        let (mut left, mut right, captured) = worker.dataflow::<Duration,_,_>(|scope| {
+            let (left_input, left) = scope.new_collection();
+            let (right_input, right) = scope.new_collection();
+            traces.set_unkeyed(&Plan::Source("left".to_string()), &left.arrange_by_self().trace);
+            traces.set_unkeyed(&Plan::Source("right".to_string()), &right.arrange_by_self().trace);
+            let mut collections = std::collections::HashMap::new();
+            let captured = plan.render(scope, &mut collections, &mut traces).inner.capture();
+            (left_input, right_input, captured)
+        });
+
+        left.insert(tuple(&[0, 10]));
+        left.insert(tuple(&[1, 11]));
+        left.insert(tuple(&[2, 12]));
+        // two tuples of "right" share the key 1.
+        right.insert(tuple(&[5, 1]));
+        right.insert(tuple(&[6, 1]));
+        left.advance_to(Duration::from_secs(1));
+        right.advance_to(Duration::from_secs(1));
+        // key 1 remains present, and key 2 appears.
+        right.remove(tuple(&[5, 1]));
+        right.insert(tuple(&[7, 2]));
+        left.advance_to(Duration::from_secs(2));
+        right.advance_to(Duration::from_secs(2));
+        // key 1 is removed, and key 2 toggles back out.
+        right.remove(tuple(&[6, 1]));
+        right.remove(tuple(&[7, 2]));
+
+        captured
+    });
+
+    let mut results = captured
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .map(|(tuple, time, diff)| (tuple, time.as_secs(), diff))
+        .collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut results);
+    assert_eq!(results, vec![
+        (tuple(&[0, 10]), 0, 1),
+        (tuple(&[1, 11]), 2, 1),
+        (tuple(&[2, 12]), 0, 1),
+        (tuple(&[2, 12]), 1, -1),
+        (tuple(&[2, 12]), 2, 1),
+    ]);
+}
+
+/// Renders `plan` against sources named "left" and "right" containing `left` and `right`, and returns the consolidated output.
+fn render_pair(plan: Plan<Value>, left: Vec<Vec<Value>>, right: Vec<Vec<Value>>) -> Vec<(Vec<Value>, isize)> {
+
+    let captured = timely::execute_directly(move |worker| {
+
+        let mut traces = TraceManager::new();
+        let (mut left_input, mut right_input, captured) = worker.dataflow::<Duration,_,_>(|scope| {
+            let (left_input, left) = scope.new_collection();
+            let (right_input, right) = scope.new_collection();
+            traces.set_unkeyed(&Plan::Source("left".to_string()), &left.arrange_by_self().trace);
+            traces.set_unkeyed(&Plan::Source("right".to_string()), &right.arrange_by_self().trace);
+            let mut collections = std::collections::HashMap::new();
+            let captured = plan.render(scope, &mut collections, &mut traces).inner.capture();
+            (left_input, right_input, captured)
+        });
+
+        for tuple in left { left_input.insert(tuple); }
+        for tuple in right { right_input.insert(tuple); }
+
+        captured
+    });
+
+    let mut results = captured
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .map(|(tuple, _time, diff)| (tuple, diff))
+        .collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate(&mut results);
+    results
+}
+
+#[test]
+fn semijoin_duplicate_keys() {
+    // the key 1 is presented twice, and key 2 three times; each restricts "left" once.
+    let left = vec![tuple(&[0, 10]), tuple(&[1, 11]), tuple(&[2, 12])];
+    let right = vec![tuple(&[1]), tuple(&[1]), tuple(&[2]), tuple(&[2]), tuple(&[2])];
+    let results = render_pair(Plan::source("left").semijoin(Plan::source("right"), vec![0]), left, right);
+    assert_eq!(results, vec![(tuple(&[1, 11]), 1), (tuple(&[2, 12]), 1)]);
+}
+
+#[test]
+fn antijoin_duplicate_keys() {
+    // the key 1 is presented twice, and must not drive the output for it negative.
+    let left = vec![tuple(&[0, 10]), tuple(&[1, 11]), tuple(&[2, 12])];
+    let right = vec![tuple(&[1]), tuple(&[1])];
+    let results = render_pair(Plan::source("left").antijoin(Plan::source("right"), vec![0]), left, right);
+    assert_eq!(results, vec![(tuple(&[0, 10]), 1), (tuple(&[2, 12]), 1)]);
+}
+
+#[test]
+fn antijoin_serialization() {
+    round_trip(Plan::source("left").antijoin_by(Plan::source("right"), vec![(0, 1), (2, 0)]));
+    round_trip(Plan::source("left").antijoin(Plan::source("keys"), vec![1]));
+}
+
+#[test]
+fn union_distinguishes_all() {
+    // the two sides overlap in the tuple `[1]`, which also appears twice on the left.
+    use interactive::plan::Predicate;
+    use interactive::plan::filter::SecondArgument;
+    let left = Plan::source("data").filter(Predicate::LessThan(0, SecondArgument::Constant(Value::Usize(2))));
+    let right = Plan::source("data").filter(Predicate::GreaterThan(0, SecondArgument::Constant(Value::Usize(0))));
+    let tuples = vec![tuple(&[0]), tuple(&[1]), tuple(&[1]), tuple(&[2])];
+
+    let all = render(Plan::union(vec![left.clone(), right.clone()], true), tuples.clone());
+    assert_eq!(all, vec![(tuple(&[0]), 1), (tuple(&[1]), 4), (tuple(&[2]), 1)]);
+    assert_eq!(all, render(Plan::concatenate(vec![left.clone(), right.clone()]), tuples.clone()));
+
+    let distinct = render(Plan::union(vec![left, right], false), tuples);
+    assert_eq!(distinct, vec![(tuple(&[0]), 1), (tuple(&[1]), 1), (tuple(&[2]), 1)]);
+}
+
+#[test]
+fn union_serialization() {
+    round_trip(Plan::union(vec![Plan::source("left"), Plan::source("right")], true));
+    round_trip(Plan::union(vec![Plan::source("left"), Plan::source("right")], false));
+    assert_ne!(
+        Plan::union(vec![Plan::source("data")], true),
+        Plan::union(vec![Plan::source("data")], false),
+    );
+}
+
+#[test]
+fn extend_operations() {
+    use interactive::plan::{Expr, BinOp};
+    let string = |text: &str| Value::String(text.to_string());
+    let plan = Plan::source("data").extend(vec![
+        Expr::binary(BinOp::Add, Expr::Column(0), Expr::Column(1)),
+        Expr::binary(BinOp::Subtract, Expr::Column(0), Expr::Constant(Value::Usize(1))),
+        Expr::binary(BinOp::Multiply, Expr::Column(0), Expr::Column(1)),
+        Expr::binary(BinOp::Concat, Expr::Column(2), Expr::Constant(string("!"))),
+        Expr::binary(BinOp::Add, Expr::Column(3), Expr::Column(3)),
+        Expr::binary(BinOp::Concat, Expr::Column(4), Expr::Column(4)),
+    ]);
+    let input = vec![
+        Value::Usize(5),
+        Value::Usize(3),
+        string("hi"),
+        Value::Duration(Duration::from_secs(2)),
+        Value::Vector(vec![Value::Bool(true)]),
+    ];
+    let mut output = input.clone();
+    output.extend(vec![
+        Value::Usize(8),
+        Value::Usize(4),
+        Value::Usize(15),
+        string("hi!"),
+        Value::Duration(Duration::from_secs(4)),
+        Value::Vector(vec![Value::Bool(true), Value::Bool(true)]),
+    ]);
+    assert_eq!(render(plan, vec![input]), vec![(output, 1)]);
+}
+
+#[test]
+#[should_panic(expected = "Add does not apply to String(\"text\") and Bool(true)")]
+fn extend_mismatched_type() {
+    use interactive::plan::{Expr, BinOp};
+    let plan = Plan::source("data").extend(vec![Expr::binary(BinOp::Add, Expr::Column(0), Expr::Column(1))]);
+    render(plan, vec![vec![Value::String("text".to_string()), Value::Bool(true)]]);
+}
+
+#[test]
+fn extend_serialization() {
+    use interactive::plan::{Expr, BinOp};
+    round_trip(Plan::source("data").extend(vec![
+        Expr::Column(1),
+        Expr::binary(BinOp::Concat, Expr::Column(0), Expr::Constant(Value::String("suffix".to_string()))),
+        Expr::binary(BinOp::Multiply, Expr::binary(BinOp::Add, Expr::Column(0), Expr::Column(1)), Expr::Constant(Value::Usize(2))),
+    ]));
+}
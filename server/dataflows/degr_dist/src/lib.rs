@@ -1,9 +1,6 @@
-use std::rc::Rc;
-use std::cell::RefCell;
-
 // use timely::dataflow::operators::{Probe, Operator};
 use differential_dataflow::operators::CountTotal;
-use dd_server::{Environment, TraceHandle};
+use dd_server::{Environment, SharedTrace};
 
 // load ./dataflows/degr_dist/target/release/libdegr_dist.dylib build <graph_name>
 
@@ -13,9 +10,9 @@ pub fn build((dataflow, handles, probe, _timer, args): Environment) -> Result<()
     if args.len() != 1 { return Err(format!("expected one argument, instead: {:?}", args)); }
 
     handles
-        .get_mut::<Rc<RefCell<Option<TraceHandle>>>>(&args[0])?
-        .borrow_mut().as_mut().unwrap()
+        .get_mut::<SharedTrace>(&args[0])?
         .import(dataflow)
+        .ok_or(format!("trace released: {:?}", args[0]))?
         .as_collection(|&src,_dst| src)
         .count_total()
         .map(|(_deg, cnt)| cnt as usize)
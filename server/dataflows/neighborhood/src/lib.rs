@@ -1,10 +1,7 @@
-use std::rc::Rc;
-use std::cell::RefCell;
-
 use differential_dataflow::input::Input;
 use differential_dataflow::operators::JoinCore;
 
-use dd_server::{Environment, TraceHandle};
+use dd_server::{Environment, SharedTrace};
 
 // load ./dataflows/neighborhood/target/release/libneighborhood.dylib build <graph_name> 0
 
@@ -14,8 +11,9 @@ pub fn build((dataflow, handles, probe, _timer, args): Environment) -> Result<()
     if args.len() != 2 { return Err(format!("expected two arguments; instead: {:?}", args)); }
 
     let edges = handles
-        .get_mut::<Rc<RefCell<Option<TraceHandle>>>>(&args[0])?
-        .borrow_mut().as_mut().unwrap().import(dataflow);
+        .get_mut::<SharedTrace>(&args[0])?
+        .import(dataflow)
+        .ok_or(format!("trace released: {:?}", args[0]))?;
 
     let source = args[1].parse::<usize>().map_err(|_| format!("parse error, source: {:?}", args[1]))?; 
     let (_input, query) = dataflow.new_collection_from(Some(source));
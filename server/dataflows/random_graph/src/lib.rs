@@ -12,7 +12,9 @@ use differential_dataflow::AsCollection;
 use differential_dataflow::operators::arrange::ArrangeByKey;
 use differential_dataflow::trace::TraceReader;
 
-use dd_server::{Environment, TraceHandle};
+use differential_dataflow::operators::arrange::TracePublisher;
+
+use dd_server::{Environment, SharedTrace};
 
 // load ./dataflows/random_graph/target/release/librandom_graph.dylib build <graph_name> 1000 2000 1000000
 // load ./dataflows/random_graph/target/release/librandom_graph.dylib build <graph_name> 10000000 100000000 1000000
@@ -39,7 +41,7 @@ pub fn build((dataflow, handles, probe, timer, args): Environment) -> Result<(),
     // To terminate the operator it is sufficient to drop the capability, as the
     // operator holds only a weak reference to it.
     //
-    // The operator also holds an `Weak<RefCell<Option<TraceHandle>>>` which it will
+    // The operator also holds a weak reference to the published trace, which it will
     // attempt to borrow and call `set_logical_compaction` in order to advance the capability
     // as it runs, to allow compaction and the maintenance of bounded state.
 
@@ -56,9 +58,9 @@ pub fn build((dataflow, handles, probe, timer, args): Environment) -> Result<(),
     // shared capability keeps graph generation going.
     let capability = Rc::new(RefCell::new(None));
 
-    // shared (optional) trace handle, so that the operator can advance capabilities.
-    let trace_handle: Rc<RefCell<Option<TraceHandle>>> = Rc::new(RefCell::new(None));
-    let trace_handle_weak = Rc::downgrade(&trace_handle);
+    // shared (initially empty) trace handle, so that the operator can advance capabilities.
+    let trace_handle = SharedTrace::empty();
+    let trace_handle_weak = trace_handle.downgrade();
 
     let timer = timer.clone();
 
@@ -186,9 +188,9 @@ pub fn build((dataflow, handles, probe, timer, args): Environment) -> Result<(),
 
     // release all blocks on merging.
     trace.set_physical_compaction(Antichain::new().borrow());
-    *trace_handle.borrow_mut() = Some(trace);
+    trace_handle.set(trace);
 
-    handles.set::<Rc<RefCell<Option<TraceHandle>>>>(name.to_owned(), trace_handle);
+    handles.publish(name, trace_handle);
     handles.set(format!("{}-capability", name), capability);
 
     println!("handles set");
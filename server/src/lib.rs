@@ -11,13 +11,14 @@ use timely::dataflow::scopes::Child;
 use timely::dataflow::operators::probe::Handle as ProbeHandle;
 
 // stuff for talking about shared trace types ...
-use differential_dataflow::operators::arrange::TraceAgent;
+use differential_dataflow::operators::arrange::{TraceAgent, PublishedTrace, TracePublisher};
 use differential_dataflow::trace::implementations::ValSpine;
 
 // These are all defined here so that users can be assured a common layout.
 pub type RootTime = usize;
 type TraceSpine = ValSpine<usize, usize, RootTime, isize>;
 pub type TraceHandle = TraceAgent<TraceSpine>;
+pub type SharedTrace = PublishedTrace<TraceSpine>;
 
 /// Arguments provided to each shared library to help build their dataflows and register their results.
 pub type Environment<'a, 'b> = (
@@ -93,4 +94,10 @@ impl TraceHandler {
     pub fn remove(&mut self, name: &str) -> Option<Box<dyn Any>> {
         self.handles.remove(name)
     }
-}
\ No newline at end of file
+}
+
+impl TracePublisher<TraceSpine> for TraceHandler {
+    fn publish(&mut self, name: &str, trace: SharedTrace) {
+        self.set(name.to_owned(), trace);
+    }
+}
@@ -0,0 +1,40 @@
+//! Transitive closure of a directed graph.
+
+use std::hash::Hash;
+
+use timely::dataflow::*;
+
+use crate::{Collection, ExchangeData};
+use crate::operators::*;
+use crate::lattice::Lattice;
+use crate::difference::Semigroup;
+
+/// Returns pairs (a, b) for each node b reachable from node a by a non-empty path.
+///
+/// Edges present with any positive multiplicity are treated as present once. A node reaches
+/// itself only if it lies on a cycle (including a self-loop). The result is maintained as the
+/// edges change, and pairs are retracted when the last path between them is removed.
+pub fn transitive_closure<G, N, R>(edges: &Collection<G, (N,N), R>) -> Collection<G, (N,N)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+    R: ExchangeData+Semigroup,
+{
+    use crate::operators::arrange::arrangement::ArrangeByKey;
+
+    let edges = edges.distinct();
+    let forward = edges.arrange_by_key();
+
+    // repeatedly extend reachable pairs (a, b) by edges (b, c), until no new pairs emerge.
+    edges.iterate(|reach| {
+
+        let forward = forward.enter(&reach.scope());
+        let edges = edges.enter(&reach.scope());
+
+        reach.map(|(a,b)| (b,a))
+             .join_core(&forward, |_b,a,c| Some((a.clone(), c.clone())))
+             .concat(&edges)
+             .distinct()
+    })
+}
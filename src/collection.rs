@@ -133,6 +133,10 @@ impl<G: Scope, D, R, C: Container + Clone + 'static> Collection<G, D, R, C> {
     /// timely dataflow capability associated with the batch of updates. The observed batching depends
     /// on how the system executes, and may vary run to run.
     ///
+    /// Each batch is presented as `Ok` with the container the operator received, whatever its type, rather
+    /// than converted to a slice of updates. The function only observes the containers, which are passed
+    /// along unchanged. Changes in the frontier of the input are presented as `Err`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -221,6 +225,36 @@ impl<G: Scope, D: Clone+'static, R: Clone+'static> Collection<G, D, R> {
             .map_in_place(move |&mut (ref mut data, _, _)| logic(data))
             .as_collection()
     }
+    /// Creates a new collection by replacing some input elements, and leaving others unchanged.
+    ///
+    /// Each input element is presented to `logic`, and is replaced by `d2` if the result is `Some(d2)`,
+    /// and retained unchanged if the result is `None`. Unlike `flat_map` with an `Option`, no element
+    /// is dropped, and each output carries the difference of its input. As `logic` takes its argument
+    /// by value, each element is cloned before it is presented, to be retained in the `None` case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     // replaces odd numbers by their doubles.
+    ///     scope.new_collection_from(1 .. 10).1
+    ///          .map_partial(|x| if x % 2 == 1 { Some(2 * x) } else { None })
+    ///          .filter(|x| x % 2 == 1)
+    ///          .assert_empty();
+    /// });
+    /// ```
+    pub fn map_partial<L>(&self, mut logic: L) -> Collection<G, D, R>
+    where L: FnMut(D) -> Option<D> + 'static {
+        self.inner
+            .map_in_place(move |&mut (ref mut data, _, _)| {
+                if let Some(replacement) = logic(data.clone()) {
+                    *data = replacement;
+                }
+            })
+            .as_collection()
+    }
     /// Creates a new collection by applying the supplied function to each input element and accumulating the results.
     ///
     /// This method extracts an iterator from each input element, and extracts the full contents of the iterator. Be
@@ -246,6 +280,89 @@ impl<G: Scope, D: Clone+'static, R: Clone+'static> Collection<G, D, R> {
             .flat_map(move |(data, time, delta)| logic(data).into_iter().map(move |x| (x, time.clone(), delta.clone())))
             .as_collection()
     }
+    /// Creates two new collections from the results of a fallible function applied to each input element.
+    ///
+    /// The first collection contains the contents of the iterators of successful applications, and the
+    /// second contains the errors of failed applications. Each output record carries the difference of
+    /// the input record that produced it, and so retracting an input record retracts its outputs from
+    /// whichever collection received them, as long as `logic` is deterministic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let (oks, errs) =
+    ///     scope.new_collection_from(vec!["1", "two", "3"]).1
+    ///          .flat_map_fallible(|x| x.parse::<u32>().map(Some).map_err(|_| x.to_string()));
+    ///
+    ///     oks.assert_eq(&scope.new_collection_from(vec![1, 3]).1);
+    ///     errs.assert_eq(&scope.new_collection_from(vec!["two".to_string()]).1);
+    /// });
+    /// ```
+    pub fn flat_map_fallible<I, E, L>(&self, mut logic: L) -> (Collection<G, I::Item, R>, Collection<G, E, R>)
+        where G::Timestamp: Clone,
+              I: IntoIterator,
+              I::Item: Data,
+              E: Data,
+              L: FnMut(D) -> Result<I, E> + 'static {
+        let results = self.flat_map(move |data| {
+            let (oks, errs) = match logic(data) {
+                Ok(iter) => (Some(iter), None),
+                Err(error) => (None, Some(error)),
+            };
+            oks.into_iter().flatten().map(Ok).chain(errs.map(Err))
+        });
+        (results.flat_map(|result| result.ok()), results.flat_map(|result| result.err()))
+    }
+    /// Creates a new collection by applying the supplied function to whole batches of input updates.
+    ///
+    /// The function receives a batch of `(data, time, diff)` updates and appends its output updates
+    /// to the second argument, which allows it to perform setup once per batch rather than once per
+    /// record, for example building a lookup structure for the batch. Output updates should keep the
+    /// time and difference of the input update they derive from, unless the function deliberately
+    /// assigns them; any assigned time must be greater or equal to the time of the batch, which is
+    /// the earliest time of its updates, as the outputs are sent with the capability of the batch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let x = scope.new_collection_from(1 .. 10).1;
+    ///
+    ///     x.flat_map_batched(|input, output| {
+    ///          // setup shared by the records of the batch.
+    ///          let offset = 10;
+    ///          output.extend(input.drain(..).map(|(x, t, r)| (x + offset, t, r)));
+    ///      })
+    ///      .assert_eq(&x.map(|x| x + 10));
+    /// });
+    /// ```
+    pub fn flat_map_batched<D2, L>(&self, mut logic: L) -> Collection<G, D2, R>
+        where D2: Data,
+              L: FnMut(&mut Vec<(D, G::Timestamp, R)>, &mut Vec<(D2, G::Timestamp, R)>) + 'static {
+        use timely::dataflow::channels::pact::Pipeline;
+        use timely::order::PartialOrder;
+
+        self.inner
+            .unary(Pipeline, "FlatMapBatched", move |_capability, _info| {
+                let mut buffer = Vec::new();
+                move |input, output| {
+                    input.for_each(|time, data| {
+                        logic(data, &mut buffer);
+                        debug_assert!(buffer.iter().all(|(_, t, _)| time.time().less_equal(t)));
+                        output.session(&time).give_container(&mut buffer);
+                        buffer.clear();
+                    });
+                }
+            })
+            .as_collection()
+    }
     /// Creates a new collection containing those input records satisfying the supplied predicate.
     ///
     /// # Examples
@@ -266,6 +383,82 @@ impl<G: Scope, D: Clone+'static, R: Clone+'static> Collection<G, D, R> {
             .filter(move |(data, _, _)| logic(data))
             .as_collection()
     }
+    /// Splits the collection into those records satisfying the supplied predicate, and those that do not.
+    ///
+    /// The result is equivalent to filtering by the predicate and by its negation, but the predicate is
+    /// applied once to each record, in a single operator with two outputs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     let data = scope.new_collection_from(1 .. 10).1;
+    ///     let (evens, odds) = data.split(|x| x % 2 == 0);
+    ///     evens.assert_eq(&data.filter(|x| x % 2 == 0));
+    ///     odds.assert_eq(&data.filter(|x| x % 2 == 1));
+    /// });
+    /// ```
+    pub fn split<L>(&self, mut logic: L) -> (Collection<G, D, R>, Collection<G, D, R>)
+    where L: FnMut(&D) -> bool + 'static {
+
+        use timely::dataflow::channels::pact::Pipeline;
+        use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
+
+        let mut builder = OperatorBuilder::new("Split".to_string(), self.scope());
+        let mut input = builder.new_input(&self.inner, Pipeline);
+        let (mut accepted_out, accepted) = builder.new_output();
+        let (mut rejected_out, rejected) = builder.new_output();
+
+        builder.build(move |_capabilities| {
+            move |_frontiers| {
+                let mut accepted_out = accepted_out.activate();
+                let mut rejected_out = rejected_out.activate();
+                while let Some((capability, updates)) = input.next() {
+                    let mut accepted_session = accepted_out.session(&capability);
+                    let mut rejected_session = rejected_out.session(&capability);
+                    for (data, time, diff) in updates.drain(..) {
+                        if logic(&data) { accepted_session.give((data, time, diff)); }
+                        else { rejected_session.give((data, time, diff)); }
+                    }
+                }
+            }
+        });
+
+        (accepted.as_collection(), rejected.as_collection())
+    }
+    /// Retains a deterministic sample of the records, of about a fraction `rate` of the distinct records.
+    ///
+    /// Each record is retained or not according to a hash of the record and `seed`, so that a record is
+    /// treated identically at all times, on all workers, and for all of its updates; in particular a record
+    /// and its retraction are both retained or both discarded. The result is a filter of the collection,
+    /// and commutes with consolidation. Different seeds select independent samples. A `rate` of zero or
+    /// less produces an empty collection, and a `rate` of one or more produces the input collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     let data = scope.new_collection_from(1 .. 10).1;
+    ///     data.sample(0.0, 7).assert_empty();
+    ///     data.sample(1.0, 7).assert_eq(&data);
+    ///     // samples with the same seed and lesser rates are subsets.
+    ///     data.sample(0.5, 7)
+    ///         .sample(0.25, 7)
+    ///         .assert_eq(&data.sample(0.25, 7));
+    /// });
+    /// ```
+    pub fn sample(&self, rate: f64, seed: u64) -> Collection<G, D, R>
+    where D: Hashable {
+        // Records whose hash is less than `threshold` are retained, or all records if `threshold` is `None`.
+        let threshold = if rate >= 1.0 { None } else { Some((rate.max(0.0) * 18_446_744_073_709_551_616.0) as u64) };
+        self.filter(move |data| {
+            threshold.map(|threshold| (seed, Into::<u64>::into(data.hashed())).hashed() < threshold).unwrap_or(true)
+        })
+    }
     /// Replaces each record with another, with a new difference type.
     ///
     /// This method is most commonly used to take records containing aggregatable data (e.g. numbers to be summed)
@@ -298,6 +491,38 @@ impl<G: Scope, D: Clone+'static, R: Clone+'static> Collection<G, D, R> {
             .as_collection()
     }
 
+    /// Multiplies the difference of each record by a weight determined from the record.
+    ///
+    /// This method is most commonly used to take a collection of records that counts each record,
+    /// and produce a collection that sums a magnitude associated with each record. Records whose
+    /// weight is zero produce zero differences, which consolidate away.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let nums = scope.new_collection_from(0 .. 10isize).1;
+    ///     let x1 = nums.map(|x| x % 2)
+    ///                  .reweight(|x| *x * 2);
+    ///     let x2 = nums.map(|x| x % 2)
+    ///                  .explode(|x| Some((x, x * 2)));
+    ///
+    ///     x1.assert_eq(&x2);
+    /// });
+    /// ```
+    pub fn reweight<R2, L>(&self, mut logic: L) -> Collection<G, D, <R as Multiply<R2>>::Output>
+    where R: Multiply<R2>,
+          <R as Multiply<R2>>::Output: Semigroup+'static,
+          L: FnMut(&D)->R2+'static,
+    {
+        self.inner
+            .map(move |(x, t, d)| { let d2 = logic(&x); (x, t, d.multiply(&d2)) })
+            .as_collection()
+    }
+
     /// Joins each record against a collection defined by the function `logic`.
     ///
     /// This method performs what is essentially a join with the collection of records `(x, logic(x))`.
@@ -402,19 +627,191 @@ impl<G: Scope, D: Clone+'static, R: Clone+'static> Collection<G, D, R> {
 
     /// Delays each difference by a supplied function.
     ///
-    /// It is assumed that `func` only advances timestamps; this is not verified, and things may go horribly
-    /// wrong if that assumption is incorrect. It is also critical that `func` be monotonic: if two times are
-    /// ordered, they should have the same order once `func` is applied to them (this is because we advance the
-    /// timely capability with the same logic, and it must remain `less_equal` to all of the data timestamps).
+    /// It is assumed that `func` only advances timestamps, and things may go horribly wrong if that assumption
+    /// is incorrect. It is also critical that `func` be monotonic: if two times are ordered, they should have the
+    /// same order once `func` is applied to them (this is because we advance the timely capability with the same
+    /// logic, and it must remain `less_equal` to all of the data timestamps). In builds with debug assertions both
+    /// properties are checked as updates pass by, monotonicity only against the previously observed time.
+    ///
+    /// Updates are held back until their new time is complete, and the updates for each new time are released
+    /// together, so that updates for the same data whose times are delayed to the same time (e.g. when rounding
+    /// times to a grid) are merged by subsequent consolidation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     let data = scope.new_collection_from(1 .. 10).1;
+    ///     // round times up to the next multiple of ten.
+    ///     data.delay(|t| ((*t + 9) / 10) * 10)
+    ///         .consolidate()
+    ///         .assert_eq(&data);
+    /// });
+    /// ```
     pub fn delay<F>(&self, func: F) -> Collection<G, D, R>
     where F: FnMut(&G::Timestamp) -> G::Timestamp + Clone + 'static {
 
         let mut func1 = func.clone();
         let mut func2 = func.clone();
 
+        let mut prev = None;
         self.inner
             .delay_batch(move |x| func1(x))
-            .map_in_place(move |x| x.1 = func2(&x.1))
+            .map_in_place(move |x| {
+                let new_time = func2(&x.1);
+                if cfg!(debug_assertions) {
+                    check_time_function("delay", &mut prev, &x.1, &new_time);
+                }
+                x.1 = new_time;
+            })
+            .as_collection()
+    }
+    /// Coarsens the time of each difference by a supplied function.
+    ///
+    /// Unlike `delay`, this method does not hold back updates or their capabilities, and is intended to
+    /// reduce the number of distinct times (e.g. rounding times up to the next second), so that subsequent
+    /// consolidation and arrangement can merge updates that now share a time. The function `func` must
+    /// advance times and be monotone: if two times are ordered, they should have the same order once `func`
+    /// is applied to them. In builds with debug assertions both properties are checked as updates pass by;
+    /// monotonicity only against the previously observed time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     let data = scope.new_collection_from(1 .. 10).1;
+    ///     // round times up to the next multiple of ten.
+    ///     data.coarsen_time(|t| ((*t + 9) / 10) * 10)
+    ///         .assert_eq(&data);
+    /// });
+    /// ```
+    pub fn coarsen_time<F>(&self, mut func: F) -> Collection<G, D, R>
+    where F: FnMut(&G::Timestamp) -> G::Timestamp + 'static {
+
+        let mut prev = None;
+        self.inner
+            .map_in_place(move |x| {
+                let new_time = func(&x.1);
+                if cfg!(debug_assertions) {
+                    check_time_function("coarsen_time", &mut prev, &x.1, &new_time);
+                }
+                x.1 = new_time;
+            })
+            .as_collection()
+    }
+    /// Pairs each record with the time of its update, as data.
+    ///
+    /// Each update `(data, time, diff)` becomes `((data, time), time, diff)`, and so the record and time
+    /// pairs accumulate to non-zero differences exactly when the record has a non-zero accumulated update
+    /// at that time. The time of each update is unchanged, which means that the capabilities of the input
+    /// suffice for the output and nothing is held back. This is useful for assertions about when records
+    /// change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use timely::dataflow::operators::ToStream;
+    /// use differential_dataflow::AsCollection;
+    ///
+    /// ::timely::example(|scope| {
+    ///     vec![('a', 0u64, 1isize), ('b', 3, 1), ('a', 5, -1)]
+    ///         .to_stream(scope)
+    ///         .as_collection()
+    ///         .times()
+    ///         .assert_eq(&vec![(('a', 0), 0, 1), (('b', 3), 3, 1), (('a', 5), 5, -1)].to_stream(scope).as_collection());
+    /// });
+    /// ```
+    pub fn times(&self) -> Collection<G, (D, G::Timestamp), R> {
+        self.inner
+            .map(|(data, time, diff)| ((data, time.clone()), time, diff))
+            .as_collection()
+    }
+    /// Limits the number of updates emitted at each time, deferring the remainder to later times.
+    ///
+    /// Once the updates at a time are complete, they are consolidated and the first `max_per_time` of them,
+    /// in the order of their data, are emitted at that time; the remainder are deferred to the time `next`
+    /// produces from it, where they join any updates at that time. The function `next` must strictly advance
+    /// times. The limit applies to the updates of each worker separately. Complete times are released in
+    /// the order of `Ord`, and so with partially ordered times an update may wait on an earlier incomplete time.
+    ///
+    /// This reshapes the changes of the collection over time, which is generally only appropriate for output
+    /// to be presented, but once all times are complete the accumulated collection equals the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     let data = scope.new_collection_from(1 .. 10).1;
+    ///     // emit at most two updates at each time, which are all emitted by time five.
+    ///     let round = |t: &u64| std::cmp::max(*t, 5);
+    ///     data.throttle(2, |t| *t + 1)
+    ///         .delay(round)
+    ///         .assert_eq(&data.delay(round));
+    /// });
+    /// ```
+    pub fn throttle<F>(&self, max_per_time: usize, mut next: F) -> Collection<G, D, R>
+    where
+        D: crate::Data,
+        R: Semigroup+'static,
+        G::Timestamp: Ord,
+        F: FnMut(&G::Timestamp) -> G::Timestamp + 'static,
+    {
+        use std::collections::BTreeMap;
+        use timely::order::PartialOrder;
+        use timely::progress::Antichain;
+        use timely::dataflow::channels::pact::Pipeline;
+
+        assert!(max_per_time > 0, "throttle requires a positive number of updates per time");
+
+        self.inner
+            .unary_frontier(Pipeline, "Throttle", move |_,_| {
+
+                // Capabilities for the times of pending updates.
+                let mut capabilities = CapabilitySet::new();
+                // Updates not yet emitted, by time.
+                let mut pending = BTreeMap::<G::Timestamp, Vec<(D, R)>>::new();
+
+                move |input, output| {
+
+                    input.for_each(|capability, data| {
+                        capabilities.insert(capability.retain());
+                        for (data, time, diff) in data.drain(..) {
+                            pending.entry(time).or_insert_with(Vec::new).push((data, diff));
+                        }
+                    });
+
+                    // Emit updates at complete times in order, deferring those beyond the limit.
+                    // Deferred updates move to later times, and so are visited again in this loop
+                    // if their new time is also complete.
+                    let frontier = input.frontier().frontier();
+                    while let Some(entry) = pending.first_entry() {
+                        if frontier.less_equal(entry.key()) { break; }
+                        let (time, mut updates) = entry.remove_entry();
+                        crate::consolidation::consolidate(&mut updates);
+                        if updates.len() > max_per_time {
+                            let deferred = updates.split_off(max_per_time);
+                            let later = next(&time);
+                            assert!(time.less_than(&later), "throttle: function does not advance {:?} (to {:?})", time, later);
+                            pending.entry(later).or_insert_with(Vec::new).extend(deferred);
+                        }
+                        let capability = capabilities.delayed(&time);
+                        output.session(&capability).give_iterator(updates.into_iter().map(|(data, diff)| (data, time.clone(), diff)));
+                    }
+
+                    // Retain capabilities only for the times of pending updates.
+                    let mut retained = Antichain::new();
+                    for time in pending.keys() {
+                        retained.insert(time.clone());
+                    }
+                    capabilities.downgrade(retained.elements());
+                }
+            })
             .as_collection()
     }
     /// Applies a supplied function to each update.
@@ -470,6 +867,84 @@ impl<G: Scope, D: Clone+'static, R: Clone+'static> Collection<G, D, R> {
             .inspect_batch(move |time, data| func(time, data))
             .as_collection()
     }
+    /// Applies a supplied function to the input frontier each time it advances.
+    ///
+    /// Updates pass through unchanged, and `logic` observes the elements of each new frontier as it is
+    /// first seen, which is useful when diagnosing why downstream probes do not advance. The initial
+    /// frontier of the minimal time is not reported, but the empty frontier is, once the input is complete.
+    /// Frontiers are only observed when the operator is scheduled, and so an advance through several
+    /// frontiers between schedulings is reported only as the last of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     scope.new_collection_from(1 .. 10).1
+    ///          .inspect_frontier(|frontier| println!("frontier: {:?}", frontier));
+    /// });
+    /// ```
+    pub fn inspect_frontier<F>(&self, mut logic: F) -> Collection<G, D, R>
+    where F: FnMut(&[G::Timestamp])+'static {
+        use timely::dataflow::channels::pact::Pipeline;
+        use timely::progress::Antichain;
+
+        let mut prev_frontier = Antichain::from_elem(<G::Timestamp as Timestamp>::minimum());
+
+        self.inner
+            .unary_frontier(Pipeline, "InspectFrontier", move |_capability, _info| {
+                move |input, output| {
+                    input.for_each(|time, data| {
+                        output.session(&time).give_container(data);
+                    });
+
+                    if prev_frontier.borrow() != input.frontier().frontier() {
+                        prev_frontier = input.frontier().frontier().to_owned();
+                        logic(prev_frontier.elements());
+                    }
+                }
+            })
+            .as_collection()
+    }
+
+    /// Assert if a batch of updates contains more than one update for the same record and time.
+    ///
+    /// Such updates could have been merged by their producer, and while they are not incorrect they may
+    /// indicate an operator that fails to consolidate its output. Each batch is checked separately, and
+    /// only updates with exactly equal records and times are reported; updates to the same record at
+    /// distinct times, or in distinct batches, are legitimate. The panic reports the record and time.
+    ///
+    /// The check is only installed in builds with debug assertions enabled; otherwise the method returns
+    /// the collection unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     scope.new_collection_from(1 .. 10).1
+    ///          .map(|x| x / 2)
+    ///          .consolidate()
+    ///          .debug_assert_consolidated();
+    /// });
+    /// ```
+    pub fn debug_assert_consolidated(&self) -> Collection<G, D, R>
+    where D: Ord+std::fmt::Debug, G::Timestamp: Ord {
+        if cfg!(debug_assertions) {
+            self.inspect_batch(|_time, updates| {
+                let mut sorted = updates.iter().map(|(data, time, _)| (data, time)).collect::<Vec<_>>();
+                sorted.sort();
+                if let Some(pair) = sorted.windows(2).find(|pair| pair[0] == pair[1]) {
+                    panic!("Assertion failed: un-consolidated updates for {:?} at time {:?}", pair[0].0, pair[0].1);
+                }
+            })
+        }
+        else {
+            self.clone()
+        }
+    }
 
     /// Assert if the collection is ever non-empty.
     ///
@@ -498,6 +973,416 @@ impl<G: Scope, D: Clone+'static, R: Clone+'static> Collection<G, D, R> {
         self.consolidate()
             .inspect(|x| panic!("Assertion failed: non-empty collection: {:?}", x));
     }
+
+    /// Assert if the collection is ever non-empty, reporting `message` on failure.
+    ///
+    /// Updates are exchanged by the hash of their data and held back until their time is complete, at which
+    /// point the updates at that time are consolidated. Any record that accumulates to a non-zero difference
+    /// causes a panic reporting `message`, the record, its time, and the residual difference. Transient updates
+    /// that cancel before their time completes do not cause a panic. As with `assert_empty`, the test is only
+    /// applied as the computation is run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     let data = scope.new_collection_from(1 .. 10).1;
+    ///     data.map(|x| x * 2)
+    ///         .concat(&data.map(|x| x + x).negate())
+    ///         .assert_empty_with("doubling is not addition");
+    /// });
+    /// ```
+    pub fn assert_empty_with(&self, message: &str)
+    where D: crate::ExchangeData+Hashable,
+          R: crate::ExchangeData+Semigroup,
+          G::Timestamp: Lattice+Ord,
+    {
+        use timely::dataflow::channels::pact::Exchange;
+
+        let message = message.to_string();
+        let mut stash = Vec::new();
+
+        let exchange = Exchange::new(|update: &(D,G::Timestamp,R)| update.0.hashed().into());
+        let _: timely::dataflow::Stream<G, ()> = self.inner.unary_frontier(exchange, "AssertEmpty", move |_capability, _info| {
+            move |input, _output| {
+                input.for_each(|_time, data| stash.append(data));
+
+                // Check consolidated updates at completed times.
+                let frontier = input.frontier().frontier();
+                if stash.iter().any(|(_, time, _)| !frontier.less_equal(time)) {
+                    let (mut ready, pending): (Vec<_>, Vec<_>) = stash.drain(..).partition(|(_, time, _)| !frontier.less_equal(time));
+                    stash = pending;
+                    crate::consolidation::consolidate_updates(&mut ready);
+                    if let Some((data, time, diff)) = ready.first() {
+                        panic!("Assertion failed: {}: non-empty collection: {:?} at time {:?} with difference {:?}", message, data, time, diff);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Assert if two distinct records ever share a key.
+    ///
+    /// The key of each record is determined by `key`, and at each completed time the collection is checked
+    /// for keys with more than one distinct record. On a violation the assertion reports the key and two of
+    /// the conflicting records, with their accumulated multiplicities. As with `assert_empty`, the test is only
+    /// applied as the computation is run.
+    ///
+    /// The check maintains an arrangement of the collection, and is only installed in builds with debug
+    /// assertions enabled; otherwise the method does nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     scope.new_collection_from(1 .. 10).1
+    ///          .map(|x| (x, x * 2))
+    ///          .assert_key_unique(|x| x.0);
+    /// });
+    /// ```
+    pub fn assert_key_unique<K, F>(&self, mut key: F)
+    where K: crate::ExchangeData+Hashable,
+          D: crate::ExchangeData,
+          R: crate::ExchangeData+Semigroup,
+          F: FnMut(&D)->K+'static,
+          G::Timestamp: Lattice+Ord,
+    {
+        if cfg!(debug_assertions) {
+            use crate::operators::Reduce;
+            self.map(move |data| (key(&data), data))
+                .reduce_named::<_, (), isize>("AssertKeyUnique", |key, input, _output| {
+                    if input.len() > 1 {
+                        panic!("Assertion failed: key {:?} has multiple records: {:?} and {:?}", key, input[0], input[1]);
+                    }
+                });
+        }
+    }
+
+    /// Tracks the net number of records in the collection, as a single record `(name, count)`.
+    ///
+    /// The count is the accumulation of the differences of all records, and it is maintained
+    /// incrementally: each worker first coalesces its updates for each time, and only their
+    /// accumulated differences are sent to the worker that maintains the count. When the collection
+    /// accumulates to zero there is no output record. The method can be applied at several points
+    /// of a computation, using distinct names, and the results concatenated for monitoring.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let data = scope.new_collection_from(1 .. 10).1;
+    ///     let evens = data.filter(|x| x % 2 == 0);
+    ///
+    ///     data.diagnostic_cardinality("data")
+    ///         .concat(&evens.diagnostic_cardinality("evens"))
+    ///         .assert_eq(&scope.new_collection_from(vec![("data".to_string(), 9), ("evens".to_string(), 4)]).1);
+    /// });
+    /// ```
+    pub fn diagnostic_cardinality(&self, name: &str) -> Collection<G, (String, R), isize>
+    where R: crate::ExchangeData+Semigroup,
+          G::Timestamp: Lattice+Ord,
+    {
+        use crate::operators::Count;
+        let name = name.to_string();
+        self.map(move |_| name.clone())
+            .coalesce_updates_in_flight()
+            .count()
+    }
+
+    /// Replays the updates of the collection into an input session, typically of another dataflow.
+    ///
+    /// As each time completes, the updates at that time are consolidated and introduced into `session`
+    /// at the same time and with the same differences. The session is advanced to the frontier of the
+    /// collection whenever the frontier is a single time, and it is closed once the collection is complete.
+    /// This allows stages of a computation to be tested in isolation, against the outputs of prior stages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use std::cell::RefCell;
+    /// use timely::Config;
+    /// use differential_dataflow::input::{Input, InputSession};
+    ///
+    /// ::timely::execute(Config::thread(), |worker| {
+    ///
+    ///     // A second dataflow, fed by the output of the first.
+    ///     let mut replay = InputSession::new();
+    ///     let seen = Rc::new(RefCell::new(Vec::new()));
+    ///     let seen2 = seen.clone();
+    ///     worker.dataflow::<u32,_,_>(|scope| {
+    ///         replay.to_collection(scope)
+    ///               .inspect(move |x| seen2.borrow_mut().push(x.clone()));
+    ///     });
+    ///
+    ///     let mut input = worker.dataflow::<u32,_,_>(|scope| {
+    ///         let (input, data) = scope.new_collection();
+    ///         data.map(|x: u32| x * 2)
+    ///             .into_input_session(replay);
+    ///         input
+    ///     });
+    ///
+    ///     input.insert(1);
+    ///     input.advance_to(1);
+    ///     input.insert(2);
+    ///     input.remove(1);
+    ///     input.close();
+    ///     while worker.step() { }
+    ///
+    ///     let mut seen = seen.borrow().clone();
+    ///     seen.sort();
+    ///     assert_eq!(seen, vec![(2, 0, 1), (2, 1, -1), (4, 1, 1)]);
+    ///
+    /// }).unwrap();
+    /// ```
+    pub fn into_input_session(&self, session: crate::input::InputSession<G::Timestamp, D, R>)
+    where
+        D: crate::Data,
+        R: Semigroup,
+    {
+        use timely::dataflow::channels::pact::Pipeline;
+        use timely::progress::Antichain;
+
+        let mut session = Some(session);
+        let mut stash = Vec::new();
+        let mut prev_frontier = Antichain::from_elem(<G::Timestamp as Timestamp>::minimum());
+
+        let _: timely::dataflow::Stream<G, ()> = self.inner.unary_frontier(Pipeline, "IntoInputSession", move |_capability, _info| {
+            move |input, _output| {
+                input.for_each(|_time, data| stash.append(data));
+
+                if prev_frontier.borrow() != input.frontier().frontier() {
+                    let frontier = input.frontier().frontier().to_owned();
+                    if let Some(session) = session.as_mut() {
+                        // Introduce consolidated updates at completed times.
+                        let (mut ready, pending): (Vec<_>, Vec<_>) = stash.drain(..).partition(|(_, time, _)| !frontier.less_equal(time));
+                        stash = pending;
+                        crate::consolidation::consolidate_updates(&mut ready);
+                        for (data, time, diff) in ready.drain(..) {
+                            session.update_at(data, time, diff);
+                        }
+                        if let [time] = frontier.elements() {
+                            session.advance_to(time.clone());
+                            session.flush();
+                        }
+                    }
+                    if frontier.is_empty() {
+                        // Dropping the session flushes and closes it.
+                        session = None;
+                    }
+                    prev_frontier = frontier;
+                }
+            }
+        });
+    }
+
+    /// Re-chunks the updates of the collection into numbered batches of at most `size` updates.
+    ///
+    /// Each update is paired with the number of the batch it lands in, and keeps its time. As times
+    /// complete, their updates are consolidated and assigned in order of time to batches of `size`
+    /// updates, numbered consecutively from zero on each worker. The batch number acts as a synthetic
+    /// time: each update lands in a batch no earlier than the batches of updates at lesser times, and so
+    /// retractions land at or after the corresponding insertions. Discarding the batch numbers yields
+    /// the collection itself.
+    ///
+    /// Batches are emitted as soon as the times of their updates complete, and so the output frontier
+    /// follows the input frontier. A batch that is not full once the updates at completed times have been
+    /// assigned is closed rather than held open, and later updates start a new batch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let data = scope.new_collection_from(1 .. 10).1;
+    ///
+    ///     data.rebatch(4)
+    ///         .map(|(_number, x)| x)
+    ///         .assert_eq(&data);
+    /// });
+    /// ```
+    pub fn rebatch(&self, size: usize) -> Collection<G, (usize, D), R>
+    where
+        D: crate::Data,
+        R: Semigroup,
+    {
+        use std::collections::BTreeMap;
+        use timely::dataflow::channels::pact::Pipeline;
+
+        assert!(size > 0, "rebatch requires a positive batch size");
+
+        self.inner.unary_frontier(Pipeline, "Rebatch", move |_capability, _info| {
+
+            // Updates not yet emitted by time, with a capability for the time.
+            let mut stash = BTreeMap::<G::Timestamp, (Capability<G::Timestamp>, Vec<(D, R)>)>::new();
+            let mut ready = Vec::new();
+            // The number of the current batch, and the number of updates assigned to it.
+            let mut batch = 0;
+            let mut filled = 0;
+
+            move |input, output| {
+                input.for_each(|cap, data| {
+                    for (data, time, diff) in data.drain(..) {
+                        stash.entry(time)
+                             .or_insert_with_key(|time| (cap.delayed(time), Vec::new()))
+                             .1
+                             .push((data, diff));
+                    }
+                });
+
+                // Complete times leave the stash in order of time.
+                let frontier = input.frontier();
+                stash.retain(|time, (cap, updates)| {
+                    if frontier.less_equal(time) {
+                        true
+                    }
+                    else {
+                        ready.push((cap.clone(), std::mem::take(updates)));
+                        false
+                    }
+                });
+
+                for (cap, mut updates) in ready.drain(..) {
+                    crate::consolidation::consolidate(&mut updates);
+                    let time = cap.time().clone();
+                    let mut session = output.session(&cap);
+                    for (data, diff) in updates.drain(..) {
+                        session.give(((batch, data), time.clone(), diff));
+                        filled += 1;
+                        if filled == size {
+                            batch += 1;
+                            filled = 0;
+                        }
+                    }
+                }
+
+                // Close a partial batch, rather than hold its times open awaiting more updates.
+                if filled > 0 {
+                    batch += 1;
+                    filled = 0;
+                }
+            }
+        })
+        .as_collection()
+    }
+
+    /// Buffers the updates of completed times in a bounded queue, from which they can be pulled.
+    ///
+    /// This is a pull counterpart to `inspect`, for hosts that step a worker and then consume the
+    /// updates produced so far. Updates are moved to the queue only once their time is complete, and
+    /// so the updates in the queue are final: no later update at the same time will be produced. Within
+    /// the queue updates are consolidated per time and ordered by time.
+    ///
+    /// The queue holds at most `capacity` updates. Once it is full, further completed updates are held
+    /// by the operator rather than the queue, and are moved to the queue as the host drains it. This
+    /// bounds the queue rather than the computation, which is not slowed by an undrained queue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use timely::Config;
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::execute(Config::thread(), |worker| {
+    ///
+    ///     let (mut input, mut observer) = worker.dataflow::<u32,_,_>(|scope| {
+    ///         let (input, data) = scope.new_collection();
+    ///         let observer = data.map(|x: u32| x * 2).observe_updates(2);
+    ///         (input, observer)
+    ///     });
+    ///
+    ///     input.insert(1);
+    ///     input.insert(2);
+    ///     input.insert(3);
+    ///     input.close();
+    ///
+    ///     let mut seen = Vec::new();
+    ///     while !observer.is_complete() {
+    ///         worker.step();
+    ///         seen.extend(observer.drain());
+    ///     }
+    ///
+    ///     seen.sort();
+    ///     assert_eq!(seen, vec![(2, 0, 1), (4, 0, 1), (6, 0, 1)]);
+    ///
+    /// }).unwrap();
+    /// ```
+    pub fn observe_updates(&self, capacity: usize) -> UpdateObserver<D, G::Timestamp, R>
+    where
+        D: crate::Data,
+        G::Timestamp: Data+Ord,
+        R: Semigroup,
+    {
+        use std::rc::Rc;
+        use std::cell::RefCell;
+        use std::collections::VecDeque;
+        use timely::dataflow::channels::pact::Pipeline;
+        use timely::dataflow::operators::CapabilitySet;
+        use timely::progress::Antichain;
+        use timely::scheduling::Activator;
+
+        assert!(capacity > 0, "observe_updates requires a positive capacity");
+
+        let shared = Rc::new(RefCell::new(ObserverState { queue: VecDeque::new(), activator: None, complete: false }));
+        let observer = UpdateObserver { shared: Rc::clone(&shared), capacity };
+
+        let activations = self.scope().activations().clone();
+        let _: timely::dataflow::Stream<G, ()> = self.inner.unary_frontier(Pipeline, "ObserveUpdates", move |_capability, info| {
+
+            shared.borrow_mut().activator = Some(Activator::new(info.address, activations));
+
+            // Updates at times that are not yet complete.
+            let mut pending = Vec::new();
+            // Updates at completed times, in order of time, not yet moved to the queue.
+            let mut ready = VecDeque::new();
+            // Capabilities for the times of updates in `pending` and `ready`, which keep the
+            // operator scheduled until its updates are moved to the queue, even once the input
+            // is complete.
+            let mut capabilities = CapabilitySet::new();
+
+            move |input, _output| {
+                input.for_each(|time, data| {
+                    capabilities.insert(time.retain());
+                    pending.append(data);
+                });
+
+                let frontier = input.frontier();
+                let (mut batch, rest): (Vec<(D, G::Timestamp, R)>, _) = pending.drain(..).partition(|(_, time, _)| !frontier.less_equal(time));
+                pending = rest;
+                crate::consolidation::consolidate_updates(&mut batch);
+                batch.sort_by(|x, y| x.1.cmp(&y.1));
+                ready.extend(batch);
+
+                let mut shared = shared.borrow_mut();
+                while shared.queue.len() < capacity {
+                    if let Some(update) = ready.pop_front() {
+                        shared.queue.push_back(update);
+                    }
+                    else {
+                        break;
+                    }
+                }
+                shared.complete = frontier.frontier().is_empty() && pending.is_empty() && ready.is_empty();
+
+                let mut held = Antichain::new();
+                for (_, time, _) in pending.iter().chain(ready.iter()) {
+                    held.insert(time.clone());
+                }
+                capabilities.downgrade(held.elements());
+            }
+        });
+
+        observer
+    }
 }
 
 use timely::dataflow::scopes::ScopeParent;
@@ -550,6 +1435,108 @@ impl<'a, G: Scope, D: Clone+'static, R: Clone+'static> Collection<Child<'a, G, G
     }
 }
 
+/// Methods requiring records that are results.
+impl<G: Scope, D: Clone+'static, E: Clone+'static, R: Clone+'static> Collection<G, Result<D, E>, R> {
+    /// Splits a collection of results into a collection of the `Ok` values and a collection of the `Err` values.
+    ///
+    /// The result is equivalent to filtering by `Result::is_ok` and `Result::is_err` and unwrapping each, but each
+    /// record is moved once to one of the two outputs of a single operator, which preserves times and differences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     let data = scope.new_collection_from(1 .. 10).1;
+    ///     let (oks, errs) = data.map(|x| if x % 3 == 0 { Err(x) } else { Ok(x) }).partition_results();
+    ///     oks.assert_eq(&data.filter(|x| x % 3 != 0));
+    ///     errs.assert_eq(&data.filter(|x| x % 3 == 0));
+    /// });
+    /// ```
+    pub fn partition_results(&self) -> (Collection<G, D, R>, Collection<G, E, R>) {
+
+        use timely::dataflow::channels::pact::Pipeline;
+        use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
+
+        let mut builder = OperatorBuilder::new("PartitionResults".to_string(), self.scope());
+        let mut input = builder.new_input(&self.inner, Pipeline);
+        let (mut ok_out, oks) = builder.new_output();
+        let (mut err_out, errs) = builder.new_output();
+
+        builder.build(move |_capabilities| {
+            move |_frontiers| {
+                let mut ok_out = ok_out.activate();
+                let mut err_out = err_out.activate();
+                while let Some((capability, updates)) = input.next() {
+                    let mut ok_session = ok_out.session(&capability);
+                    let mut err_session = err_out.session(&capability);
+                    for (data, time, diff) in updates.drain(..) {
+                        match data {
+                            Ok(data) => ok_session.give((data, time, diff)),
+                            Err(error) => err_session.give((error, time, diff)),
+                        }
+                    }
+                }
+            }
+        });
+
+        (oks.as_collection(), errs.as_collection())
+    }
+}
+
+/// Methods requiring integer differences.
+impl<G: Scope, D: Hashable+Clone+'static> Collection<G, D, isize> where G::Timestamp: Data+Lattice+Ord {
+    /// Maintains an order-independent checksum of the contents of the collection.
+    ///
+    /// The checksum is the wrapping sum of the hash of each record multiplied by its accumulated count,
+    /// and the result collection contains exactly one record, the checksum, at every time. As the
+    /// checksum is a function of the accumulated contents, it does not depend on how updates are
+    /// batched or ordered, and collections with equal contents have equal checksums; in particular
+    /// the checksum of an empty collection is zero. Distinct contents have equal checksums only with
+    /// small probability, and so comparing checksums is a cheap test for equality.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let data = scope.new_collection_from(1 .. 10u32).1;
+    ///     let odds = data.filter(|x| x % 2 == 1);
+    ///     let evens = data.filter(|x| x % 2 == 0);
+    ///
+    ///     odds.concat(&evens)
+    ///         .checksum()
+    ///         .assert_eq(&data.checksum());
+    ///
+    ///     data.filter(|_| false)
+    ///         .checksum()
+    ///         .assert_eq(&scope.new_collection_from(Some(0u64)).1);
+    /// });
+    /// ```
+    pub fn checksum(&self) -> Collection<G, u64, isize> {
+        use std::num::Wrapping;
+        use crate::operators::Count;
+
+        // A sentinel record ensures the accumulation is never zero, so that an empty collection reports zero.
+        let mut scope = self.scope();
+        let sentinel = if scope.index() == 0 { Some(((), G::Timestamp::minimum(), (1isize, Wrapping(0i64)))) } else { None };
+        let sentinel = sentinel.to_stream(&mut scope).as_collection();
+
+        self.inner
+            .map(|(data, time, diff)| {
+                let hash: u64 = data.hashed().into();
+                ((), time, (0isize, Wrapping(hash as i64) * Wrapping(diff as i64)))
+            })
+            .as_collection()
+            .concat(&sentinel)
+            .count()
+            .map(|((), (_, checksum))| checksum.0 as u64)
+    }
+}
+
 /// Methods requiring an Abelian difference, to support negation.
 impl<G: Scope, D: Clone+'static, R: Abelian+'static> Collection<G, D, R> where G::Timestamp: Data {
     /// Creates a new collection whose counts are the negation of those in the input.
@@ -616,6 +1603,22 @@ impl<G: Scope, D: Clone+'static, R: Abelian+'static> Collection<G, D, R> where G
     }
 }
 
+/// Asserts that a function on times advances `time` to `new_time`, and is monotone with respect to the
+/// previously observed pair of time and new time in `prev`, which it then replaces.
+fn check_time_function<T: Timestamp>(name: &str, prev: &mut Option<(T, T)>, time: &T, new_time: &T) {
+    use timely::order::PartialOrder;
+    assert!(time.less_equal(new_time), "{}: function does not advance {:?} (to {:?})", name, time, new_time);
+    if let Some((prev_time, prev_new)) = prev.as_ref() {
+        if prev_time.less_equal(time) {
+            assert!(prev_new.less_equal(new_time), "{}: function is not monotone on {:?} and {:?}", name, prev_time, time);
+        }
+        if time.less_equal(prev_time) {
+            assert!(new_time.less_equal(prev_new), "{}: function is not monotone on {:?} and {:?}", name, time, prev_time);
+        }
+    }
+    *prev = Some((time.clone(), new_time.clone()));
+}
+
 /// Conversion to a differential dataflow Collection.
 pub trait AsCollection<G: Scope, D, R, C> {
     /// Converts the type to a differential dataflow collection.
@@ -631,7 +1634,9 @@ impl<G: Scope, D, R, C: Clone> AsCollection<G, D, R, C> for StreamCore<G, C> {
 /// Concatenates multiple collections.
 ///
 /// This method has the effect of a sequence of calls to `concat`, but it does
-/// so in one operator rather than a chain of many operators.
+/// so in one operator rather than a chain of many operators. Unlike the method
+/// `Collection::concatenate` it does not require a first collection, and an empty
+/// iterator results in an empty collection in `scope`.
 ///
 /// # Examples
 ///
@@ -661,3 +1666,75 @@ where
         .concatenate(iterator.into_iter().map(|x| x.inner))
         .as_collection()
 }
+
+/// Concatenates multiple collections, consolidating their updates as times complete.
+///
+/// This method has the effect of `concatenate` followed by `Collection::coalesce_updates_in_flight`.
+/// The updates of all collections are buffered by time, and each time's updates are consolidated and
+/// emitted once the time is complete, so that updates that cancel across collections are not sent on.
+/// The buffered updates are those at incomplete times, and the result accumulates to the same contents
+/// as the plain concatenation.
+///
+/// # Examples
+///
+/// ```
+/// use differential_dataflow::input::Input;
+///
+/// ::timely::example(|scope| {
+///
+///     let data = scope.new_collection_from(1 .. 10).1;
+///
+///     let odds = data.filter(|x| x % 2 == 1);
+///     let evens = data.filter(|x| x % 2 == 0);
+///
+///     differential_dataflow::collection::union_all_consolidate_at_frontier(scope, vec![odds, evens.negate(), evens])
+///         .assert_eq(&data);
+/// });
+/// ```
+pub fn union_all_consolidate_at_frontier<G, D, R, I>(scope: &mut G, iterator: I) -> Collection<G, D, R>
+where
+    G: Scope,
+    G::Timestamp: Data+Lattice,
+    D: crate::ExchangeData+Hashable,
+    R: crate::ExchangeData+Semigroup,
+    I: IntoIterator<Item=Collection<G, D, R>>,
+{
+    concatenate(scope, iterator)
+        .coalesce_updates_in_flight()
+}
+
+/// State shared between the `observe_updates` operator and its `UpdateObserver`.
+struct ObserverState<D, T, R> {
+    queue: std::collections::VecDeque<(D, T, R)>,
+    activator: Option<timely::scheduling::Activator>,
+    complete: bool,
+}
+
+/// A handle from which the updates of completed times can be pulled.
+///
+/// Produced by `Collection::observe_updates`, the handle should be drained between steps of the worker.
+pub struct UpdateObserver<D, T, R> {
+    shared: std::rc::Rc<std::cell::RefCell<ObserverState<D, T, R>>>,
+    capacity: usize,
+}
+
+impl<D, T, R> UpdateObserver<D, T, R> {
+    /// Removes and returns the buffered updates, in order of time.
+    ///
+    /// If the queue was full, the operator is scheduled to refill it with updates it holds back.
+    pub fn drain(&mut self) -> std::collections::vec_deque::IntoIter<(D, T, R)> {
+        let mut shared = self.shared.borrow_mut();
+        if shared.queue.len() >= self.capacity {
+            if let Some(activator) = shared.activator.as_ref() {
+                activator.activate();
+            }
+        }
+        std::mem::take(&mut shared.queue).into_iter()
+    }
+    /// The number of buffered updates.
+    pub fn len(&self) -> usize { self.shared.borrow().queue.len() }
+    /// True if there are no buffered updates.
+    pub fn is_empty(&self) -> bool { self.shared.borrow().queue.is_empty() }
+    /// True once the collection is complete and all of its updates have been moved to the queue.
+    pub fn is_complete(&self) -> bool { self.shared.borrow().complete }
+}
@@ -78,6 +78,40 @@ pub fn consolidate_slice<T: Ord, R: Semigroup>(slice: &mut [(T, R)]) -> usize {
     }
 }
 
+/// Sorts and consolidates `vec` by a key extracted from the first elements.
+///
+/// This method will stably sort `vec` by `key` of the first elements, and then consolidate runs of
+/// entries with equal keys by accumulating the second elements of the pairs. The first element of
+/// each run, that is the earliest in `vec` among those with its key, is kept as the representative
+/// of the run; the first elements of the other entries are discarded. Should the final accumulation
+/// be zero, the run is discarded.
+///
+/// The `key` function is called multiple times for each element, and should be cheap.
+pub fn consolidate_by<T, R: Semigroup, K: Ord, F: Fn(&T) -> K>(vec: &mut Vec<(T, R)>, key: F) {
+
+    vec.sort_by_key(|x| key(&x.0));
+
+    // Counts the number of distinct known-non-zero accumulations. Indexes the write location.
+    let mut offset = 0;
+    let mut start = 0;
+    while start < vec.len() {
+        let run_key = key(&vec[start].0);
+        let mut accum = vec[start].1.clone();
+        let mut end = start + 1;
+        while end < vec.len() && key(&vec[end].0) == run_key {
+            accum.plus_equals(&vec[end].1);
+            end += 1;
+        }
+        if !accum.is_zero() {
+            vec.swap(offset, start);
+            vec[offset].1 = accum;
+            offset += 1;
+        }
+        start = end;
+    }
+    vec.truncate(offset);
+}
+
 /// Sorts and consolidates `vec`.
 ///
 /// This method will sort `vec` and then consolidate runs of more than one entry with
@@ -387,6 +421,38 @@ mod tests {
     }
 
 
+    #[test]
+    fn test_consolidate_by() {
+        // payloads are `(identity, provenance)` pairs, consolidated by identity alone.
+        let test_cases = vec![
+            (
+                vec![(("a", 0), 1), (("b", 1), -2), (("a", 2), 1)],
+                vec![(("a", 0), 2), (("b", 1), -2)],
+            ),
+            (
+                vec![(("b", 0), 1), (("a", 1), 1), (("b", 2), -1)],
+                vec![(("a", 1), 1)],
+            ),
+            (
+                vec![(("a", 2), 1), (("a", 1), 1), (("a", 0), 1)],
+                vec![(("a", 2), 3)],
+            ),
+            (
+                vec![(("a", 0), 0)],
+                vec![],
+            ),
+            (
+                vec![],
+                vec![],
+            ),
+        ];
+
+        for (mut input, output) in test_cases {
+            consolidate_by(&mut input, |(identity, _provenance)| *identity);
+            assert_eq!(input, output);
+        }
+    }
+
     #[test]
     fn test_consolidate_updates() {
         let test_cases = vec![
@@ -186,6 +186,143 @@ mod present {
     }
 }
 
+pub use self::decimal::Decimal;
+mod decimal {
+
+    use serde::{Deserialize, Serialize};
+
+    use super::{IsZero, Semigroup, Monoid, Abelian, Multiply};
+
+    /// A fixed-point decimal difference, with `SCALE` digits after the decimal point.
+    ///
+    /// The value is represented as an integer number of units of `10^-SCALE`, which makes addition
+    /// exact and associative, unlike floating point differences whose accumulations depend on the
+    /// order in which updates are merged. This makes it suitable for aggregating monetary amounts.
+    ///
+    /// The scale is part of the type, and differences of different scales cannot be combined.
+    /// Arithmetic that overflows the underlying `i128` panics, rather than wrapping silently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::difference::Decimal;
+    /// use differential_dataflow::operators::Count;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     // (account, cents) pairs, accumulated to a balance per account.
+    ///     let expected = scope.new_collection_from(vec![
+    ///         (0u32, Decimal::<2>::new(1050)),
+    ///         (1, Decimal::<2>::new(-25)),
+    ///     ]).1;
+    ///
+    ///     scope.new_collection_from(vec![(0u32, 1000i128), (0, 50), (1, -25)]).1
+    ///          .explode(|(account, cents)| Some((account, Decimal::<2>::new(cents))))
+    ///          .count()
+    ///          .assert_eq(&expected);
+    /// });
+    /// ```
+    ///
+    /// Differences of different scales cannot be added.
+    ///
+    /// ```compile_fail
+    /// use differential_dataflow::difference::{Decimal, Semigroup};
+    ///
+    /// let mut cents = Decimal::<2>::new(100);
+    /// cents.plus_equals(&Decimal::<4>::new(100));
+    /// ```
+    #[derive(Copy, Ord, PartialOrd, Eq, PartialEq, Debug, Clone, Default, Serialize, Deserialize, Hash)]
+    pub struct Decimal<const SCALE: u32> {
+        units: i128,
+    }
+
+    impl<const SCALE: u32> Decimal<SCALE> {
+        /// A decimal of `units` multiples of `10^-SCALE`.
+        pub fn new(units: i128) -> Self {
+            Decimal { units }
+        }
+        /// A decimal equal to the integer `value`, panicking if it is not representable at this scale.
+        pub fn from_integer(value: i128) -> Self {
+            let units = 10i128.checked_pow(SCALE)
+                .and_then(|factor| value.checked_mul(factor))
+                .unwrap_or_else(|| panic!("Decimal: {} is not representable with scale {}", value, SCALE));
+            Decimal { units }
+        }
+        /// The number of multiples of `10^-SCALE` the decimal represents.
+        pub fn units(&self) -> i128 {
+            self.units
+        }
+    }
+
+    impl<const SCALE: u32> std::fmt::Display for Decimal<SCALE> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let sign = if self.units < 0 { "-" } else { "" };
+            let magnitude = self.units.unsigned_abs();
+            if SCALE == 0 {
+                write!(f, "{}{}", sign, magnitude)
+            }
+            else {
+                let factor = 10u128.pow(SCALE);
+                write!(f, "{}{}.{:0width$}", sign, magnitude / factor, magnitude % factor, width = SCALE as usize)
+            }
+        }
+    }
+
+    impl<const SCALE: u32> IsZero for Decimal<SCALE> {
+        #[inline] fn is_zero(&self) -> bool { self.units == 0 }
+    }
+
+    impl<const SCALE: u32> Semigroup for Decimal<SCALE> {
+        #[inline] fn plus_equals(&mut self, rhs: &Self) {
+            self.units = self.units.checked_add(rhs.units).expect("Decimal: overflow in addition");
+        }
+    }
+
+    impl<const SCALE: u32> Monoid for Decimal<SCALE> {
+        #[inline] fn zero() -> Self { Decimal { units: 0 } }
+    }
+
+    impl<const SCALE: u32> Abelian for Decimal<SCALE> {
+        #[inline] fn negate(&mut self) {
+            self.units = self.units.checked_neg().expect("Decimal: overflow in negation");
+        }
+    }
+
+    impl<const SCALE: u32> Multiply<isize> for Decimal<SCALE> {
+        type Output = Self;
+        fn multiply(self, rhs: &isize) -> Self::Output {
+            let units = self.units.checked_mul(*rhs as i128).expect("Decimal: overflow in multiplication");
+            Decimal { units }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use crate::difference::{Decimal, IsZero, Semigroup, Abelian, Multiply};
+
+        #[test]
+        fn test_decimal_arithmetic() {
+            let mut a = Decimal::<2>::from_integer(3);
+            a.plus_equals(&Decimal::new(-125));
+            assert_eq!(a, Decimal::new(175));
+            assert_eq!(a.to_string(), "1.75");
+            let mut b = a.multiply(&-2);
+            assert_eq!(b.to_string(), "-3.50");
+            b.negate();
+            b.plus_equals(&Decimal::new(-350));
+            assert!(b.is_zero());
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_decimal_overflow() {
+            let mut a = Decimal::<2>::new(i128::MAX);
+            a.plus_equals(&Decimal::new(1));
+        }
+    }
+}
+
 // Pair implementations.
 mod tuples {
 
@@ -316,3 +453,170 @@ mod vector {
         }
     }
 }
+
+pub use self::count_map::CountMap;
+mod count_map {
+
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::{IsZero, Semigroup, Monoid, Abelian, Multiply};
+
+    /// A difference that maps keys to counts, added element-wise.
+    ///
+    /// This type allows a single differential collection to maintain a histogram for each record,
+    /// for example the number of occurrences of each sub-category within each category, by using
+    /// `explode` to produce `CountMap` differences and then `count` to accumulate them.
+    ///
+    /// Entries whose counts accumulate to zero are removed, so that the map is zero exactly when it
+    /// has no entries, and so that its size is bounded by the number of keys with non-zero counts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::difference::CountMap;
+    /// use differential_dataflow::operators::Count;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     // (category, item) pairs, where items 0 and 1 belong to category 0.
+    ///     let expected = scope.new_collection_from(vec![
+    ///         (0u32, CountMap::from_iter(vec![(0u32, 2), (1, 1)])),
+    ///         (1, CountMap::singleton(2)),
+    ///     ]).1;
+    ///
+    ///     scope.new_collection_from(vec![(0u32, 0u32), (0, 0), (0, 1), (1, 2)]).1
+    ///          .explode(|(category, item)| Some((category, CountMap::singleton(item))))
+    ///          .count()
+    ///          .assert_eq(&expected);
+    /// });
+    /// ```
+    #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(bound(serialize = "K: Serialize+Hash+Eq", deserialize = "K: Deserialize<'de>+Hash+Eq"))]
+    pub struct CountMap<K> {
+        counts: HashMap<K, isize>,
+    }
+
+    impl<K: Hash+Eq> CountMap<K> {
+        /// A map with a count of one for `key`.
+        pub fn singleton(key: K) -> Self {
+            let mut counts = HashMap::with_capacity(1);
+            counts.insert(key, 1);
+            CountMap { counts }
+        }
+        /// The count associated with `key`, which is zero if the key is absent.
+        pub fn get(&self, key: &K) -> isize {
+            self.counts.get(key).copied().unwrap_or(0)
+        }
+        /// Iterates over the keys with non-zero counts, in no particular order.
+        pub fn iter(&self) -> impl Iterator<Item=(&K, &isize)> {
+            self.counts.iter()
+        }
+        /// Adds `count` to the count of `key`, removing the entry if it becomes zero.
+        pub fn update(&mut self, key: K, count: isize) {
+            use std::collections::hash_map::Entry;
+            match self.counts.entry(key) {
+                Entry::Occupied(mut entry) => {
+                    *entry.get_mut() += count;
+                    if *entry.get() == 0 { entry.remove(); }
+                },
+                Entry::Vacant(entry) => {
+                    if count != 0 { entry.insert(count); }
+                },
+            }
+        }
+    }
+
+    impl<K: Hash+Eq> FromIterator<(K, isize)> for CountMap<K> {
+        fn from_iter<I: IntoIterator<Item=(K, isize)>>(iter: I) -> Self {
+            let mut result = CountMap { counts: HashMap::new() };
+            for (key, count) in iter {
+                result.update(key, count);
+            }
+            result
+        }
+    }
+
+    impl<K: Ord> CountMap<K> {
+        /// The entries of the map, sorted by key.
+        fn sorted(&self) -> Vec<(&K, &isize)> {
+            let mut entries = self.counts.iter().collect::<Vec<_>>();
+            entries.sort();
+            entries
+        }
+    }
+
+    // Differential collections require their records to be ordered, and so `CountMap` is ordered
+    // by its entries in order of key, as if it were a sorted list of key-count pairs.
+    impl<K: Hash+Ord> PartialOrd for CountMap<K> {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl<K: Hash+Ord> Ord for CountMap<K> {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.sorted().cmp(&other.sorted())
+        }
+    }
+
+    impl<K> IsZero for CountMap<K> {
+        #[inline] fn is_zero(&self) -> bool { self.counts.is_empty() }
+    }
+
+    impl<K: Hash+Eq+Clone> Semigroup for CountMap<K> {
+        fn plus_equals(&mut self, rhs: &Self) {
+            for (key, count) in rhs.counts.iter() {
+                *self.counts.entry(key.clone()).or_insert(0) += count;
+            }
+            self.counts.retain(|_, count| *count != 0);
+        }
+    }
+
+    impl<K: Hash+Eq+Clone> Monoid for CountMap<K> {
+        fn zero() -> Self {
+            CountMap { counts: HashMap::new() }
+        }
+    }
+
+    impl<K: Hash+Eq+Clone> Abelian for CountMap<K> {
+        fn negate(&mut self) {
+            for count in self.counts.values_mut() {
+                *count = -*count;
+            }
+        }
+    }
+
+    impl<K: Hash+Eq+Clone> Multiply<isize> for CountMap<K> {
+        type Output = Self;
+        fn multiply(mut self, rhs: &isize) -> Self::Output {
+            if *rhs == 0 {
+                self.counts.clear();
+            }
+            for count in self.counts.values_mut() {
+                *count *= rhs;
+            }
+            self
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use crate::difference::{CountMap, IsZero, Semigroup, Abelian};
+
+        #[test]
+        fn test_count_map_prunes_zeros() {
+            let mut a = CountMap::from_iter(vec![("a", 1), ("b", 2)]);
+            let mut b = a.clone();
+            b.negate();
+            b.update("c", 1);
+            a.plus_equals(&b);
+            assert_eq!(a, CountMap::singleton("c"));
+            a.update("c", -1);
+            assert!(a.is_zero());
+        }
+    }
+}
@@ -177,6 +177,12 @@ impl<T: Timestamp+Clone, D: Data> InputSession<T, D, isize> {
     pub fn insert(&mut self, element: D) { self.update(element, 1); }
     /// Removes an element from the collection.
     pub fn remove(&mut self, element: D) { self.update(element,-1); }
+    /// Adds each of a vector of elements to the collection, sending them as one consolidated batch.
+    ///
+    /// See `update_batch` for details.
+    pub fn insert_batch(&mut self, elements: Vec<D>) where D: Ord {
+        self.update_batch(elements.into_iter().map(|element| (element, 1)).collect());
+    }
 }
 
 // impl<T: Timestamp+Clone, D: Data> InputSession<T, D, i64> {
@@ -236,6 +242,24 @@ impl<T: Timestamp+Clone, D: Data, R: Semigroup+'static> InputSession<T, D, R> {
         self.buffer.push((element, self.time.clone(), change));
     }
 
+    /// Adds to the weights of a vector of elements in the collection, sending them as one batch.
+    ///
+    /// The updates are consolidated, which sorts them by element, and are introduced at the current time
+    /// of the session as a single shipment rather than through the internal buffer. Downstream operators,
+    /// for example an arrangement, receive the updates in one large message. Updates already buffered by
+    /// the session are sent first.
+    pub fn update_batch(&mut self, mut updates: Vec<(D, R)>) where D: Ord {
+        crate::consolidation::consolidate(&mut updates);
+        if !self.buffer.is_empty() {
+            self.handle.send_batch(&mut self.buffer);
+        }
+        let time = &self.time;
+        let mut batch = updates.into_iter().map(|(element, change)| (element, time.clone(), change)).collect::<Vec<_>>();
+        if !batch.is_empty() {
+            self.handle.send_batch(&mut batch);
+        }
+    }
+
     /// Adds to the weight of an element in the collection at a future time.
     pub fn update_at(&mut self, element: D, time: T, change: R) {
         assert!(self.time.less_equal(&time));
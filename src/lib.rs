@@ -115,7 +115,14 @@ pub struct Config {
     /// cause these operators to reschedule themselves as long as their arrangemnt has not
     /// reached a compact representation, and each scheduling quantum they will perform
     /// compaction work as if `effort` records had been added to the arrangement.
-    pub idle_merge_effort: Option<isize>
+    pub idle_merge_effort: Option<isize>,
+    /// A bound on the merge work batchers perform as they receive updates.
+    ///
+    /// The default value of `None` allows batchers to merge as much as they like as updates
+    /// arrive. Setting the value to `Some(fuel)` bounds the number of chunks a merge batcher
+    /// merges for each container it receives, deferring the remaining merges until it forms
+    /// a batch; see `MergeBatcher::set_fuel`. Assigned with the `batcher_fuel` method.
+    batcher_fuel: Option<usize>,
 }
 
 impl Config {
@@ -124,6 +131,11 @@ impl Config {
         self.idle_merge_effort = effort;
         self
     }
+    /// Assign a bound on the merge work batchers perform as they receive updates.
+    pub fn batcher_fuel(mut self, fuel: Option<usize>) -> Self {
+        self.batcher_fuel = fuel;
+        self
+    }
 }
 
 /// Introduces differential options to a timely configuration.
@@ -143,4 +155,7 @@ pub fn configure(config: &mut timely::WorkerConfig, options: &Config) {
             }),
         );
     }
+    if let Some(fuel) = options.batcher_fuel {
+        config.set("differential/batcher_fuel".to_string(), fuel);
+    }
 }
@@ -37,6 +37,8 @@ pub enum DifferentialEvent {
     TraceShare(TraceShare),
     /// Batcher size event
     Batcher(BatcherEvent),
+    /// Batcher holding pen event
+    HoldingPen(HoldingPenEvent),
 }
 
 /// Either the start or end of a merge event.
@@ -68,6 +70,19 @@ pub struct BatcherEvent {
 
 impl From<BatcherEvent> for DifferentialEvent { fn from(e: BatcherEvent) -> Self { DifferentialEvent::Batcher(e) } }
 
+/// The number of updates held by a batcher, after it accepts or seals updates.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize, Columnar)]
+pub struct HoldingPenEvent {
+    /// Operator identifier.
+    pub operator: usize,
+    /// Number of updates held in sorted chains, awaiting sealing.
+    pub records: usize,
+    /// Number of those updates held back by previous seals, as not yet ready.
+    pub held_back: usize,
+}
+
+impl From<HoldingPenEvent> for DifferentialEvent { fn from(e: HoldingPenEvent) -> Self { DifferentialEvent::HoldingPen(e) } }
+
 /// Either the start or end of a merge event.
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize, Columnar)]
 pub struct DropEvent {
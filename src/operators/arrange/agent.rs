@@ -10,12 +10,14 @@ use timely::progress::Timestamp;
 use timely::progress::{Antichain, frontier::AntichainRef};
 use timely::dataflow::operators::CapabilitySet;
 
-use crate::trace::{Trace, TraceReader, Batch, BatchReader};
+use crate::trace::{Trace, TraceReader, Batch, BatchReader, MergeStats};
 use crate::trace::wrappers::rc::TraceBox;
 
 use timely::scheduling::Activator;
 
 use super::{TraceWriter, TraceAgentQueueWriter, TraceAgentQueueReader, Arranged};
+#[cfg(feature = "checkpoint")]
+use super::checkpoint::Checkpoint;
 use super::TraceReplayInstruction;
 
 use crate::trace::wrappers::frontier::{TraceFrontier, BatchFrontier};
@@ -167,6 +169,123 @@ impl<Tr: TraceReader> TraceAgent<Tr> {
     }
 }
 
+impl<Tr> TraceAgent<Tr>
+where
+    Tr: Trace,
+    Tr::Batch: Batch,
+{
+    /// Statistics about the merges the shared trace has completed.
+    ///
+    /// The statistics are collected in-process, and cover merges driven by the amortized work
+    /// of introduced batches as well as those driven by `exert`. Collection is disabled by default,
+    /// as it times each unit of merge work, and is enabled with `set_merge_stats`. The statistics
+    /// are empty while collection is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::ArrangeBySelf;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let trace = scope.new_collection_from(0 .. 10).1
+    ///                      .arrange_by_self()
+    ///                      .trace;
+    ///
+    ///     trace.set_merge_stats(true);
+    ///     assert_eq!(trace.merge_stats().merges(), 0);
+    /// });
+    /// ```
+    pub fn merge_stats(&self) -> MergeStats {
+        self.trace.borrow().trace.merge_stats()
+    }
+
+    /// Enables or disables the collection of merge statistics for the shared trace.
+    ///
+    /// Disabling collection discards the statistics collected so far.
+    pub fn set_merge_stats(&self, enabled: bool) {
+        self.trace.borrow_mut().trace.set_merge_stats(enabled)
+    }
+
+    /// Captures the batches and compaction frontiers of the shared trace.
+    ///
+    /// This method requires the `checkpoint` feature.
+    ///
+    /// The checkpoint shares the batches of the trace, and can be serialized to persist the trace
+    /// and reconstructed with `restore`. The compaction frontiers are those of the shared trace,
+    /// which may lag those of this agent if other agents hold back compaction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use timely::dataflow::operators::generic::OperatorInfo;
+    /// use timely::progress::Antichain;
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::{ArrangeByKey, TraceAgent};
+    /// use differential_dataflow::trace::Trace;
+    /// use differential_dataflow::trace::implementations::ValSpine;
+    ///
+    /// let checkpoint = ::timely::execute_directly(|worker| {
+    ///     let (mut input, trace) = worker.dataflow::<u32,_,_>(|scope| {
+    ///         let (input, data) = scope.new_collection();
+    ///         (input, data.arrange_by_key().trace)
+    ///     });
+    ///     input.insert((0u32, 1u32));
+    ///     input.advance_to(1);
+    ///     input.flush();
+    ///     worker.step_while(|| trace.checkpoint().upper() == Antichain::from_elem(0));
+    ///     trace.checkpoint()
+    /// });
+    ///
+    /// let info = OperatorInfo::new(0, 0, [].into());
+    /// let trace = ValSpine::<u32, u32, u32, isize>::new(info.clone(), None, None);
+    /// let upper = checkpoint.upper();
+    /// let (_restored, _writer) = TraceAgent::restore(trace, checkpoint, upper.borrow(), info, None).unwrap();
+    /// ```
+    #[cfg(feature = "checkpoint")]
+    pub fn checkpoint(&self) -> Checkpoint<Tr::Batch, Tr::Time> {
+        let mut batches = Vec::new();
+        self.map_batches(|batch| batches.push(batch.clone()));
+        let trace = self.trace.borrow();
+        Checkpoint::new(
+            batches,
+            trace.logical_compaction.frontier().to_owned(),
+            trace.physical_compaction.frontier().to_owned(),
+        )
+    }
+
+    /// Reconstructs a trace from a checkpoint, introducing its batches into the empty `trace`.
+    ///
+    /// This method requires the `checkpoint` feature.
+    ///
+    /// The result is as `new`, except that the writer accepts batches from the upper frontier of
+    /// the checkpoint, and the agent holds the compaction frontiers of the checkpoint. The `upper`
+    /// argument is the frontier from which new batches will be introduced, and the restoration
+    /// fails if the checkpoint does not end there or if its batches are not contiguous.
+    #[cfg(feature = "checkpoint")]
+    pub fn restore(
+        trace: Tr,
+        checkpoint: Checkpoint<Tr::Batch, Tr::Time>,
+        upper: AntichainRef<Tr::Time>,
+        operator: OperatorInfo,
+        logging: Option<crate::logging::Logger>,
+    ) -> Result<(Self, TraceWriter<Tr>), String>
+    {
+        checkpoint.validate(upper)?;
+        let (batches, logical_compaction, physical_compaction) = checkpoint.into_parts();
+
+        let (mut reader, mut writer) = Self::new(trace, operator, logging);
+        for batch in batches {
+            writer.insert(batch, None);
+        }
+        reader.set_logical_compaction(logical_compaction.borrow());
+        reader.set_physical_compaction(physical_compaction.borrow());
+
+        Ok((reader, writer))
+    }
+}
+
 impl<Tr> TraceAgent<Tr>
 where
     Tr: TraceReader+'static,
@@ -414,6 +533,34 @@ where
         self.import_frontier_core(scope, name, since, Antichain::new())
     }
 
+    /// Import a trace with updates advanced to the supplied logical compaction frontier `since`.
+    ///
+    /// As `import_frontier`, but presenting the trace as if compacted to `since`, which is also installed
+    /// as the logical compaction frontier of the imported trace handle. This allows a query that is only
+    /// interested in recent times to share a trace with queries that require finer historical detail,
+    /// without holding back the compaction of the trace on its account.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `since` is not greater or equal to the logical compaction frontier of `self`,
+    /// as the trace cannot present updates at times it may have already compacted away.
+    pub fn import_frontier_since<G>(&mut self, scope: &G, name: &str, since: Antichain<Tr::Time>) -> (Arranged<G, TraceFrontier<TraceAgent<Tr>>>, ShutdownButton<CapabilitySet<Tr::Time>>)
+    where
+        G: Scope<Timestamp=Tr::Time>,
+        Tr: TraceReader,
+    {
+        let frontier = self.get_logical_compaction().to_owned();
+        assert!(
+            timely::PartialOrder::less_equal(&frontier, &since),
+            "import_frontier_since: frontier {:?} is not beyond the logical compaction frontier {:?} of the trace",
+            since,
+            frontier,
+        );
+        let (mut arranged, button) = self.import_frontier_core(scope, name, since.clone(), Antichain::new());
+        arranged.trace.set_logical_compaction(since.borrow());
+        (arranged, button)
+    }
+
     /// Import a trace restricted to a specific time interval `[since, until)`.
     ///
     /// All updates present in the input trace will be first advanced to `since`, and then either emitted,
@@ -17,6 +17,11 @@
 //! see ill-defined data at times for which the trace is not complete. (All current implementations
 //! commit only completed data to the trace).
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+
 use timely::dataflow::operators::{Enter, Map};
 use timely::order::PartialOrder;
 use timely::dataflow::{Scope, Stream, StreamCore};
@@ -24,6 +29,7 @@ use timely::dataflow::operators::generic::Operator;
 use timely::dataflow::channels::pact::{ParallelizationContract, Pipeline, Exchange};
 use timely::progress::Timestamp;
 use timely::progress::Antichain;
+use timely::progress::frontier::AntichainRef;
 use timely::dataflow::operators::Capability;
 
 use crate::{Data, ExchangeData, Collection, AsCollection, Hashable};
@@ -36,8 +42,10 @@ use trace::wrappers::enter::{TraceEnter, BatchEnter,};
 use trace::wrappers::enter_at::TraceEnter as TraceEnterAt;
 use trace::wrappers::enter_at::BatchEnter as BatchEnterAt;
 use trace::wrappers::filter::{TraceFilter, BatchFilter};
+use trace::wrappers::val_project::{TraceValProject, BatchValProject};
+use trace::wrappers::filter_map::{TraceFilterMap, BatchFilterMap};
 
-use super::TraceAgent;
+use super::{TraceAgent, TraceWriter};
 
 /// An arranged collection of `(K,V)` values.
 ///
@@ -111,11 +119,13 @@ where
         }
     }
 
-    /// Brings an arranged collection into a nested scope.
+    /// Brings an arranged collection into a nested scope, at times that may depend on each update.
     ///
-    /// This method produces a proxy trace handle that uses the same backing data, but acts as if the timestamps
-    /// have all been extended with an additional coordinate with the default value. The resulting collection does
-    /// not vary with the new timestamp coordinate.
+    /// This method produces a proxy trace handle that uses the same backing data, but presents each update at
+    /// the time `logic` determines from its key, value, and time. This allows, for example, different keys to be
+    /// delayed by different amounts in the new timestamp coordinate. The time produced must be in advance of the
+    /// entered time, which is checked by debug assertions. The `prior` function maps times in the nested scope
+    /// back to times of the trace, and is applied to compaction frontiers communicated back to the trace.
     pub fn enter_at<'a, TInner, F, P>(&self, child: &Child<'a, G, TInner>, logic: F, prior: P)
         -> Arranged<Child<'a, G, TInner>, TraceEnterAt<Tr, TInner, F, P>>
         where
@@ -169,6 +179,78 @@ where
             stream: self.stream.map(move |bw| BatchFilter::make_from(bw, logic2.clone())),
         }
     }
+    /// Projects the values of an arranged collection.
+    ///
+    /// This method produces a new arrangement backed by the same shared arrangement as `self`,
+    /// whose values are presented through `logic`, a projection that borrows from each value.
+    /// The projection must be monotone in the order of values (e.g. selecting a first component),
+    /// and distinct values may present identically; see `trace::wrappers::val_project`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::ArrangeByKey;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let wide = scope.new_collection_from((0 .. 10).map(|x| (x % 3, (x, x * x)))).1.arrange_by_key();
+    ///     let keys = scope.new_collection_from(0 .. 3).1.map(|x| (x, ())).arrange_by_key();
+    ///
+    ///     wide.val_project(|(a, _b)| a)
+    ///         .join_core(&keys, |k, a, _| Some((*k, *a)))
+    ///         .assert_eq(&scope.new_collection_from((0 .. 10).map(|x| (x % 3, x))).1);
+    /// });
+    /// ```
+    pub fn val_project<F, A>(&self, logic: F)
+        -> Arranged<G, TraceValProject<Tr, F, A>>
+        where
+            A: Ord+?Sized+'static,
+            F: for<'a> Fn(Tr::Val<'a>)->&'a A+Clone+'static,
+    {
+        let logic1 = logic.clone();
+        let logic2 = logic.clone();
+        Arranged {
+            trace: TraceValProject::make_from(self.trace.clone(), logic1),
+            stream: self.stream.map(move |bw| BatchValProject::make_from(bw, logic2.clone())),
+        }
+    }
+    /// Filters and projects the values of an arranged collection.
+    ///
+    /// This method produces a new arrangement backed by the same shared arrangement as `self`,
+    /// whose values are those for which `logic` returns a projection, presented as that projection.
+    /// The function must be order-preserving on the values it retains, and distinct values may
+    /// present identically; see `trace::wrappers::filter_map`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::ArrangeByKey;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let wide = scope.new_collection_from((0 .. 10).map(|x| (x % 3, (x % 2 == 0, x)))).1.arrange_by_key();
+    ///     let keys = scope.new_collection_from(0 .. 3).1.map(|x| (x, ())).arrange_by_key();
+    ///
+    ///     wide.filter_map(|(even, x)| if *even { Some(x) } else { None })
+    ///         .join_core(&keys, |k, x, _| Some((*k, *x)))
+    ///         .assert_eq(&scope.new_collection_from((0 .. 10).filter(|x| x % 2 == 0).map(|x| (x % 3, x))).1);
+    /// });
+    /// ```
+    pub fn filter_map<F, A>(&self, logic: F)
+        -> Arranged<G, TraceFilterMap<Tr, F, A>>
+        where
+            A: Ord+?Sized+'static,
+            F: for<'a> Fn(Tr::Val<'a>)->Option<&'a A>+Clone+'static,
+    {
+        let logic1 = logic.clone();
+        let logic2 = logic.clone();
+        Arranged {
+            trace: TraceFilterMap::make_from(self.trace.clone(), logic1),
+            stream: self.stream.map(move |bw| BatchFilterMap::make_from(bw, logic2.clone())),
+        }
+    }
     /// Flattens the stream into a `Collection`.
     ///
     /// The underlying `Stream<G, BatchWrapper<T::Batch>>` is a much more efficient way to access the data,
@@ -240,7 +322,7 @@ where
     T1: TraceReader + Clone + 'static,
 {
     /// A direct implementation of the `JoinCore::join_core` method.
-    pub fn join_core<T2,I,L>(&self, other: &Arranged<G,T2>, mut result: L) -> Collection<G,I::Item,<T1::Diff as Multiply<T2::Diff>>::Output>
+    pub fn join_core<T2,I,L>(&self, other: &Arranged<G,T2>, result: L) -> Collection<G,I::Item,<T1::Diff as Multiply<T2::Diff>>::Output>
     where
         T2: for<'a> TraceReader<Key<'a>=T1::Key<'a>,Time=T1::Time>+Clone+'static,
         T1::Diff: Multiply<T2::Diff>,
@@ -249,12 +331,31 @@ where
         I::Item: Data,
         L: FnMut(T1::Key<'_>,T1::Val<'_>,T2::Val<'_>)->I+'static
     {
-        let result = move |k: T1::Key<'_>, v1: T1::Val<'_>, v2: T2::Val<'_>, t: &G::Timestamp, r1: &T1::Diff, r2: &T2::Diff| {
-            let t = t.clone();
-            let r = (r1.clone()).multiply(r2);
-            result(k, v1, v2).into_iter().map(move |d| (d, t.clone(), r.clone()))
-        };
-        self.join_core_internal_unsafe(other, result)
+        self.join_core_named("Join", other, result)
+    }
+    /// A direct implementation of the `JoinCore::join_core_named` method.
+    pub fn join_core_named<T2,I,L>(&self, name: &str, other: &Arranged<G,T2>, mut result: L) -> Collection<G,I::Item,<T1::Diff as Multiply<T2::Diff>>::Output>
+    where
+        T2: for<'a> TraceReader<Key<'a>=T1::Key<'a>,Time=T1::Time>+Clone+'static,
+        T1::Diff: Multiply<T2::Diff>,
+        <T1::Diff as Multiply<T2::Diff>>::Output: Semigroup+'static,
+        I: IntoIterator,
+        I::Item: Data,
+        L: FnMut(T1::Key<'_>,T1::Val<'_>,T2::Val<'_>)->I+'static
+    {
+        use crate::operators::join::join_traces_named;
+        join_traces_named::<_, _, _, _, crate::consolidation::ConsolidatingContainerBuilder<_>>(
+            name,
+            self,
+            other,
+            move |k, v1, v2, t, r1, r2, c| {
+                let r = (r1.clone()).multiply(r2);
+                for datum in result(k, v1, v2) {
+                    c.give((datum, t.clone(), r.clone()));
+                }
+            }
+        )
+            .as_collection()
     }
     /// A direct implementation of the `JoinCore::join_core_internal_unsafe` method.
     pub fn join_core_internal_unsafe<T2,I,L,D,ROut> (&self, other: &Arranged<G,T2>, mut result: L) -> Collection<G,D,ROut>
@@ -277,6 +378,142 @@ where
         )
             .as_collection()
     }
+
+    /// Joins two arrangements, and presents each output to `sink` rather than producing a collection.
+    ///
+    /// The `result` closure is as in `join_core`, and each record it produces is presented to `sink`
+    /// along with its time and multiplied difference, as soon as the join produces it from the batches
+    /// of the arranged inputs. The outputs are not consolidated, and a record may be presented several
+    /// times at the same time, with differences that should be accumulated. Outputs may be presented
+    /// for a time before all of its outputs are; see `join_core_for_each_completed` to wait for times
+    /// to complete. The returned stream carries no data, and can be probed to learn which times have
+    /// been presented to `sink`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::ArrangeByKey;
+    ///
+    /// let total = Arc::new(Mutex::new(0isize));
+    /// let total2 = Arc::clone(&total);
+    ///
+    /// ::timely::example(move |scope| {
+    ///
+    ///     let x = scope.new_collection_from(vec![(0u32, 1u32), (1, 3)]).1.arrange_by_key();
+    ///     let y = scope.new_collection_from(vec![(0u32, 'a'), (1, 'b'), (1, 'c')]).1.arrange_by_key();
+    ///
+    ///     x.join_core_for_each(&y, |_k, &a, &_b| Some(a), move |a, _t, r| {
+    ///         *total2.lock().unwrap() += (*a as isize) * r;
+    ///     });
+    /// });
+    ///
+    /// assert_eq!(*total.lock().unwrap(), 7);
+    /// ```
+    pub fn join_core_for_each<T2,I,L,S>(&self, other: &Arranged<G,T2>, mut result: L, mut sink: S) -> Stream<G, ()>
+    where
+        T2: for<'a> TraceReader<Key<'a>=T1::Key<'a>,Time=T1::Time>+Clone+'static,
+        T1::Diff: Multiply<T2::Diff>,
+        <T1::Diff as Multiply<T2::Diff>>::Output: Semigroup+'static,
+        I: IntoIterator,
+        I::Item: Data,
+        L: FnMut(T1::Key<'_>,T1::Val<'_>,T2::Val<'_>)->I+'static,
+        S: FnMut(&I::Item, &G::Timestamp, &<T1::Diff as Multiply<T2::Diff>>::Output)+'static,
+    {
+        use timely::container::CapacityContainerBuilder;
+        use crate::operators::join::join_traces;
+
+        join_traces::<_, _, _, _, CapacityContainerBuilder<Vec<()>>>(
+            self,
+            other,
+            move |k, v1, v2, t, d1, d2, _c| {
+                let r = (d1.clone()).multiply(d2);
+                for datum in result(k, v1, v2) {
+                    sink(&datum, t, &r);
+                }
+            }
+        )
+    }
+
+    /// As `join_core_for_each`, but presenting the outputs of each time only once the time is complete.
+    ///
+    /// Outputs are held back until the joint frontier of the arranged inputs no longer includes their
+    /// time, so that `sink` sees only the updates of completed times. The outputs are consolidated as
+    /// they are produced, but not across the batches the join produces them from, and so a record may
+    /// still be presented several times at the same time.
+    ///
+    /// The held back outputs are stored in memory until their times complete. When the inputs advance
+    /// slowly relative to the rate of join outputs, this may be as much as all outputs of the incomplete
+    /// times, which `join_core_for_each` never holds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::ArrangeByKey;
+    ///
+    /// let total = Arc::new(Mutex::new(0isize));
+    /// let total2 = Arc::clone(&total);
+    ///
+    /// ::timely::example(move |scope| {
+    ///
+    ///     let x = scope.new_collection_from(vec![(0u32, 1u32), (1, 3)]).1.arrange_by_key();
+    ///     let y = scope.new_collection_from(vec![(0u32, 'a'), (1, 'b'), (1, 'c')]).1.arrange_by_key();
+    ///
+    ///     x.join_core_for_each_completed(&y, |_k, &a, &_b| Some(a), move |a, _t, r| {
+    ///         *total2.lock().unwrap() += (*a as isize) * r;
+    ///     });
+    /// });
+    ///
+    /// assert_eq!(*total.lock().unwrap(), 7);
+    /// ```
+    pub fn join_core_for_each_completed<T2,I,L,S>(&self, other: &Arranged<G,T2>, mut result: L, mut sink: S) -> Stream<G, ()>
+    where
+        T2: for<'a> TraceReader<Key<'a>=T1::Key<'a>,Time=T1::Time>+Clone+'static,
+        T1::Diff: Multiply<T2::Diff>,
+        <T1::Diff as Multiply<T2::Diff>>::Output: Semigroup+'static,
+        I: IntoIterator,
+        I::Item: Data,
+        L: FnMut(T1::Key<'_>,T1::Val<'_>,T2::Val<'_>)->I+'static,
+        S: FnMut(&I::Item, &G::Timestamp, &<T1::Diff as Multiply<T2::Diff>>::Output)+'static,
+    {
+        use crate::operators::join::join_traces;
+
+        // Outputs are staged through a stream, whose frontier reflects both arranged inputs.
+        let outputs = join_traces::<_, _, _, _, crate::consolidation::ConsolidatingContainerBuilder<_>>(
+            self,
+            other,
+            move |k, v1, v2, t, d1, d2, c| {
+                let r = (d1.clone()).multiply(d2);
+                for datum in result(k, v1, v2) {
+                    c.give((datum, t.clone(), r.clone()));
+                }
+            }
+        );
+
+        outputs.unary_frontier(Pipeline, "JoinForEach", move |_cap, _info| {
+
+            // Outputs whose times are not yet complete.
+            let mut pending = Vec::new();
+
+            move |input, _output| {
+                input.for_each(|_time, data| { pending.append(data); });
+
+                let frontier = input.frontier();
+                pending.retain(|(datum, time, diff)| {
+                    if frontier.less_equal(time) {
+                        true
+                    }
+                    else {
+                        sink(datum, time, diff);
+                        false
+                    }
+                });
+            }
+        })
+    }
 }
 
 use crate::trace::cursor::IntoOwned;
@@ -327,6 +564,34 @@ where
         use crate::operators::reduce::reduce_trace;
         reduce_trace::<_,_,Bu,_,_,V,_>(self, name, logic)
     }
+
+    /// As `reduce_core`, but presenting to `logic` at most the `limit` least values of each key.
+    ///
+    /// The logic receives the key, its least values, the number of its values, its prior output, and
+    /// a vector to populate with changes to that output. The number of values is exact if at most `limit`,
+    /// and otherwise an upper bound, as values beyond those loaded are counted by the distinct values that
+    /// have updates, whether or not those accumulate to zero. The logic is called for each key at each
+    /// time it is evaluated, even if its input and output are both empty.
+    ///
+    /// The values of a key are loaded in order only until `limit` of them are found that are certainly
+    /// present at each time to evaluate: those whose updates have been compacted to a single update and
+    /// that have no new updates. Later values are counted but not loaded, which bounds the memory used for
+    /// a key with many values by `limit` and the number of its values with recent updates.
+    pub fn reduce_core_capped<L, K, V, Bu, T2>(&self, name: &str, limit: usize, logic: L) -> Arranged<G, TraceAgent<T2>>
+    where
+        for<'a> T1::Key<'a>: IntoOwned<'a, Owned = K>,
+        T2: for<'a> Trace<Key<'a>=T1::Key<'a>, Time=T1::Time>+'static,
+        K: Ord + 'static,
+        V: Data,
+        for<'a> T2::Val<'a> : IntoOwned<'a, Owned = V>,
+        T2::Batch: Batch,
+        Bu: Builder<Time=G::Timestamp, Output = T2::Batch>,
+        Bu::Input: Container + PushInto<((K, V), T2::Time, T2::Diff)>,
+        L: FnMut(T1::Key<'_>, &[(T1::Val<'_>, T1::Diff)], usize, &mut Vec<(V, T2::Diff)>, &mut Vec<(V, T2::Diff)>)+'static,
+    {
+        use crate::operators::reduce::reduce_trace_capped;
+        reduce_trace_capped::<_,_,Bu,_,_,V,_>(self, name, Some(limit), logic)
+    }
 }
 
 
@@ -373,8 +638,51 @@ where
         Tr: Trace<Time=G::Timestamp> + 'static,
         Tr::Batch: Batch,
     ;
+
+    /// Arranges updates into a shared trace, or reuses the arrangement registered under `token`.
+    ///
+    /// If `registry` contains an arrangement for `token` it is returned, and otherwise the updates are
+    /// arranged with the supplied name and the arrangement is registered under `token`. The token should
+    /// identify both the collection and its arrangement, as any arrangement registered under the token
+    /// is returned regardless of the collection on which the method is called. This allows several
+    /// operators in a scope to share one arrangement of a collection without coordinating directly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::{Arrange, ArrangementRegistry};
+    /// use differential_dataflow::trace::implementations::{ValBatcher, ValBuilder, ValSpine};
+    ///
+    /// ::timely::example(|scope| {
+    ///     let registry = ArrangementRegistry::default();
+    ///     let data = scope.new_collection_from((0 .. 10).map(|x| (x % 3, x))).1;
+    ///     // both arrangements are served by one trace.
+    ///     let arranged1 = data.arrange_or_reuse::<ValBatcher<_,_,_,_>, ValBuilder<_,_,_,_>, ValSpine<_,_,_,_>, _>(&registry, "data by key", "Arrange");
+    ///     let arranged2 = data.arrange_or_reuse::<ValBatcher<_,_,_,_>, ValBuilder<_,_,_,_>, ValSpine<_,_,_,_>, _>(&registry, "data by key", "Arrange");
+    ///     assert_eq!(registry.borrow().len(), 1);
+    /// });
+    /// ```
+    fn arrange_or_reuse<Ba, Bu, Tr, K>(&self, registry: &ArrangementRegistry<G, K, Tr>, token: K, name: &str) -> Arranged<G, TraceAgent<Tr>>
+    where
+        Ba: Batcher<Input=C, Time=G::Timestamp> + 'static,
+        Bu: Builder<Time=G::Timestamp, Input=Ba::Output, Output = Tr::Batch>,
+        Tr: Trace<Time=G::Timestamp> + 'static,
+        Tr::Batch: Batch,
+        K: Hash + Eq,
+    {
+        if let Some(arranged) = registry.borrow().get(&token) {
+            return arranged.clone();
+        }
+        let arranged = self.arrange_named::<Ba, Bu, Tr>(name);
+        registry.borrow_mut().insert(token, arranged.clone());
+        arranged
+    }
 }
 
+/// A registry of arrangements in a scope, by a caller-supplied token, for use with `Arrange::arrange_or_reuse`.
+pub type ArrangementRegistry<G, K, Tr> = Rc<RefCell<HashMap<K, Arranged<G, TraceAgent<Tr>>>>>;
+
 impl<G, K, V, R> Arrange<G, Vec<((K, V), G::Timestamp, R)>> for Collection<G, (K, V), R>
 where
     G: Scope,
@@ -401,6 +709,65 @@ where
 /// It uses the supplied parallelization contract to distribute the data, which does not need to
 /// be consistently by key (though this is the most common).
 pub fn arrange_core<G, P, Ba, Bu, Tr>(stream: &StreamCore<G, Ba::Input>, pact: P, name: &str) -> Arranged<G, TraceAgent<Tr>>
+where
+    G: Scope,
+    G::Timestamp: Lattice,
+    P: ParallelizationContract<G::Timestamp, Ba::Input>,
+    Ba: Batcher<Time=G::Timestamp> + 'static,
+    Ba::Input: Container + Clone + 'static,
+    Bu: Builder<Time=G::Timestamp, Input=Ba::Output, Output = Tr::Batch>,
+    Tr: Trace<Time=G::Timestamp>+'static,
+    Tr::Batch: Batch,
+{
+    arrange_core_exert::<_, _, Ba, Bu, _>(stream, pact, name, None)
+}
+
+/// As `arrange_core`, but installing `exert_logic` on the trace before it receives any batches.
+///
+/// If `exert_logic` is `None`, the default exertion logic of the worker configuration is used, if any.
+pub fn arrange_core_exert<G, P, Ba, Bu, Tr>(stream: &StreamCore<G, Ba::Input>, pact: P, name: &str, exert_logic: Option<trace::ExertionLogic>) -> Arranged<G, TraceAgent<Tr>>
+where
+    G: Scope,
+    G::Timestamp: Lattice,
+    P: ParallelizationContract<G::Timestamp, Ba::Input>,
+    Ba: Batcher<Time=G::Timestamp> + 'static,
+    Ba::Input: Container + Clone + 'static,
+    Bu: Builder<Time=G::Timestamp, Input=Ba::Output, Output = Tr::Batch>,
+    Tr: Trace<Time=G::Timestamp>+'static,
+    Tr::Batch: Batch,
+{
+    arrange_core_inner::<_, _, Ba, Bu, _>(stream, pact, name, exert_logic, 0, 0)
+}
+
+/// As `arrange_core`, but forming batches only once at least `min_batch` records have been received.
+///
+/// Each advance of the input frontier ordinarily seals a batch of the updates it passes, and a frontier
+/// that advances with each few records results in many small batches. Here the updates are instead held
+/// back until at least `min_batch` records have been received since the last batch was formed, until
+/// the input frontier has advanced `max_deferred` times without a batch being formed, or until the input
+/// is complete, at which point one batch is formed through the current input frontier. While updates are
+/// held back the operator retains capabilities for them, and the output frontier does not advance past
+/// them; results are delayed, but never incomplete.
+///
+/// The `max_deferred` bound is what ensures liveness: without it, an input that goes quiet after fewer than
+/// `min_batch` records would have its updates held back, and its output frontier stalled, until the input
+/// completes. Smaller values of `max_deferred` bound the delay more tightly, at the cost of smaller batches.
+pub fn arrange_core_coalesced<G, P, Ba, Bu, Tr>(stream: &StreamCore<G, Ba::Input>, pact: P, name: &str, min_batch: usize, max_deferred: usize) -> Arranged<G, TraceAgent<Tr>>
+where
+    G: Scope,
+    G::Timestamp: Lattice,
+    P: ParallelizationContract<G::Timestamp, Ba::Input>,
+    Ba: Batcher<Time=G::Timestamp> + 'static,
+    Ba::Input: Container + Clone + 'static,
+    Bu: Builder<Time=G::Timestamp, Input=Ba::Output, Output = Tr::Batch>,
+    Tr: Trace<Time=G::Timestamp>+'static,
+    Tr::Batch: Batch,
+{
+    arrange_core_inner::<_, _, Ba, Bu, _>(stream, pact, name, None, min_batch, max_deferred)
+}
+
+/// The implementation of `arrange_core_exert` and `arrange_core_coalesced`.
+fn arrange_core_inner<G, P, Ba, Bu, Tr>(stream: &StreamCore<G, Ba::Input>, pact: P, name: &str, exert_logic: Option<trace::ExertionLogic>, min_batch: usize, max_deferred: usize) -> Arranged<G, TraceAgent<Tr>>
 where
     G: Scope,
     G::Timestamp: Lattice,
@@ -442,14 +809,19 @@ where
 
         // Where we will deposit received updates, and from which we extract batches.
         let mut batcher = Ba::new(logger.clone(), info.global_id);
+        // Install the configured bound on batcher work, if it is set.
+        if let Some(fuel) = scope.config().get::<usize>("differential/batcher_fuel") {
+            batcher.set_fuel(Some(*fuel));
+        }
 
         // Capabilities for the lower envelope of updates in `batcher`.
         let mut capabilities = Antichain::<Capability<G::Timestamp>>::new();
 
         let activator = Some(scope.activator_for(info.address.clone()));
         let mut empty_trace = Tr::new(info.clone(), logger.clone(), activator);
-        // If there is default exertion logic set, install it.
-        if let Some(exert_logic) = scope.config().get::<trace::ExertionLogic>("differential/default_exert_logic").cloned() {
+        // Install the supplied exertion logic, or the default exertion logic if it is set.
+        let exert_logic = exert_logic.or_else(|| scope.config().get::<trace::ExertionLogic>("differential/default_exert_logic").cloned());
+        if let Some(exert_logic) = exert_logic {
             empty_trace.set_exert_logic(exert_logic);
         }
 
@@ -460,6 +832,12 @@ where
         // Initialize to the minimal input frontier.
         let mut prev_frontier = Antichain::from_elem(<G::Timestamp as Timestamp>::minimum());
 
+        // The number of records received since the last batch was formed.
+        let mut received = 0;
+        // The number of input frontier advances since the last batch was formed, and the most recent frontier.
+        let mut deferrals = 0;
+        let mut last_frontier = prev_frontier.clone();
+
         move |input, output| {
 
             // As we receive data, we need to (i) stash the data and (ii) keep *enough* capabilities.
@@ -468,6 +846,7 @@ where
 
             input.for_each(|cap, data| {
                 capabilities.insert(cap.retain());
+                received += data.len();
                 batcher.push_container(data);
             });
 
@@ -482,7 +861,20 @@ where
             // Test to see if strict progress has occurred, which happens whenever the new
             // frontier isn't equal to the previous. It is only in this case that we have any
             // data processing to do.
-            if prev_frontier.borrow() != input.frontier().frontier() {
+            // Updates not in advance of the input frontier may be held back until enough records have
+            // been received, as long as the input is not complete and the frontier has not advanced
+            // `max_deferred` times already. The held capabilities prevent the output frontier from
+            // advancing past them in the meantime.
+            let pending = capabilities.elements().iter().any(|c| !input.frontier().less_equal(c.time()));
+            let deferred = pending && received < min_batch && deferrals < max_deferred && !input.frontier().is_empty();
+
+            if last_frontier.borrow() != input.frontier().frontier() {
+                last_frontier.clear();
+                last_frontier.extend(input.frontier().frontier().iter().cloned());
+                if deferred { deferrals += 1; }
+            }
+
+            if prev_frontier.borrow() != input.frontier().frontier() && !deferred {
                 // There are two cases to handle with some care:
                 //
                 // 1. If any held capabilities are not in advance of the new input frontier,
@@ -495,36 +887,17 @@ where
                 //    we can simply create an empty input batch with the new upper frontier
                 //    and feed this to the trace agent (but not along the timely output).
 
-                // If there is at least one capability not in advance of the input frontier ...
-                if capabilities.elements().iter().any(|c| !input.frontier().less_equal(c.time())) {
+                // Send each batch to downstream consumers, empty or not, with the capability of its held time.
+                let held = capabilities.elements();
+                let sealed = seal_held::<_, _, Ba, Bu, _>(&mut batcher, &mut writer, held, Capability::time, input.frontier().frontier(), |index, batch| {
+                    output.session(&held[index]).give(batch);
+                });
 
-                    let mut upper = Antichain::new();   // re-used allocation for sealing batches.
+                // If there was at least one capability not in advance of the input frontier ...
+                if sealed {
 
-                    // For each capability not in advance of the input frontier ...
-                    for (index, capability) in capabilities.elements().iter().enumerate() {
-
-                        if !input.frontier().less_equal(capability.time()) {
-
-                            // Assemble the upper bound on times we can commit with this capabilities.
-                            // We must respect the input frontier, and *subsequent* capabilities, as
-                            // we are pretending to retire the capability changes one by one.
-                            upper.clear();
-                            for time in input.frontier().frontier().iter() {
-                                upper.insert(time.clone());
-                            }
-                            for other_capability in &capabilities.elements()[(index + 1) .. ] {
-                                upper.insert(other_capability.time().clone());
-                            }
-
-                            // Extract updates not in advance of `upper`.
-                            let batch = batcher.seal::<Bu>(upper.clone());
-
-                            writer.insert(batch.clone(), Some(capability.time().clone()));
-
-                            // send the batch to downstream consumers, empty or not.
-                            output.session(&capabilities.elements()[index]).give(batch);
-                        }
-                    }
+                    received = 0;
+                    deferrals = 0;
 
                     // Having extracted and sent batches between each capability and the input frontier,
                     // we should downgrade all capabilities to match the batcher's lower update frontier.
@@ -543,11 +916,6 @@ where
 
                     capabilities = new_capabilities;
                 }
-                else {
-                    // Announce progress updates, even without data.
-                    let _batch = batcher.seal::<Bu>(input.frontier().frontier().to_owned());
-                    writer.seal(input.frontier().frontier().to_owned());
-                }
 
                 prev_frontier.clear();
                 prev_frontier.extend(input.frontier().frontier().iter().cloned());
@@ -560,6 +928,180 @@ where
     Arranged { stream, trace: reader.unwrap() }
 }
 
+/// Seals batches from `batcher` through `frontier`, inserting each into `writer`.
+///
+/// The `held` elements are those whose times (as read by `time`) lower bound the updates in `batcher`.
+/// Each held time not in advance of `frontier` is retired in turn, with a batch bounded above by `frontier`
+/// and by the *subsequent* held times, so that the retired time is a valid hint for the batch. The batch
+/// is presented to `deliver` along with the index of its held element. If no held time is retired, the
+/// writer is instead sealed through `frontier` without data.
+///
+/// Returns `true` if any held time was retired, in which case the caller should downgrade its held times
+/// to the batcher's frontier.
+fn seal_held<T, H, Ba, Bu, Tr>(batcher: &mut Ba, writer: &mut TraceWriter<Tr>, held: &[H], time: fn(&H)->&T, frontier: AntichainRef<T>, mut deliver: impl FnMut(usize, Tr::Batch)) -> bool
+where
+    T: Timestamp+Lattice,
+    Ba: Batcher<Time=T>,
+    Bu: Builder<Time=T, Input=Ba::Output, Output=Tr::Batch>,
+    Tr: Trace<Time=T>,
+    Tr::Batch: Batch,
+{
+    if held.iter().any(|h| !frontier.less_equal(time(h))) {
+
+        let mut upper = Antichain::new();   // re-used allocation for sealing batches.
+
+        for (index, element) in held.iter().enumerate() {
+            if !frontier.less_equal(time(element)) {
+
+                // Assemble the upper bound on times we can commit with this held time.
+                // We must respect the frontier, and *subsequent* held times, as we are
+                // pretending to retire the held times one by one.
+                upper.clear();
+                for t in frontier.iter() {
+                    upper.insert(t.clone());
+                }
+                for other in &held[(index + 1) .. ] {
+                    upper.insert(time(other).clone());
+                }
+
+                // Extract updates not in advance of `upper`.
+                let batch = batcher.seal::<Bu>(upper.clone());
+                writer.insert(batch.clone(), Some(time(element).clone()));
+                deliver(index, batch);
+            }
+        }
+        true
+    }
+    else {
+        // Announce progress updates, even without data.
+        let _batch = batcher.seal::<Bu>(frontier.to_owned());
+        writer.seal(frontier.to_owned());
+        false
+    }
+}
+
+impl<G, K, V, R> Collection<G, (K, V), R>
+where
+    G: Scope,
+    G::Timestamp: Lattice,
+    K: ExchangeData+Hashable,
+    V: ExchangeData,
+    R: ExchangeData+Semigroup,
+{
+    /// Batches the updates of the collection and inserts them into an externally managed trace.
+    ///
+    /// This is the writing half of `arrange`, without the trace construction and the stream of batches.
+    /// Updates are exchanged by key, as in `arrange_by_key`, and each worker inserts its batches into the
+    /// `writer` it supplies. Batches are inserted in order of their frontiers, each accompanied by a hint
+    /// suitable as a capability, and so readers of the trace (e.g. through `TraceAgent::import`) observe
+    /// the same sequence of replay instructions they would from an arrangement.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use timely::dataflow::operators::generic::OperatorInfo;
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::TraceAgent;
+    /// use differential_dataflow::trace::Trace;
+    /// use differential_dataflow::trace::implementations::{ValBatcher, ValBuilder, ValSpine};
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let info = OperatorInfo::new(0, 0, [].into());
+    ///     let trace = ValSpine::<u32, u32, usize, isize>::new(info.clone(), None, None);
+    ///     let (mut reader, writer) = TraceAgent::new(trace, info, None);
+    ///
+    ///     let data = scope.new_collection_from((0 .. 10u32).map(|x| (x, x + 1))).1;
+    ///     data.write_to_trace::<ValBatcher<_,_,_,_>, ValBuilder<_,_,_,_>, _>(writer);
+    ///
+    ///     reader.import(scope)
+    ///           .as_collection(|k, v| (*k, *v))
+    ///           .assert_eq(&data);
+    /// });
+    /// ```
+    pub fn write_to_trace<Ba, Bu, Tr>(&self, mut writer: TraceWriter<Tr>)
+    where
+        Ba: Batcher<Input=Vec<((K, V), G::Timestamp, R)>, Time=G::Timestamp> + 'static,
+        Bu: Builder<Time=G::Timestamp, Input=Ba::Output, Output = Tr::Batch>,
+        Tr: Trace<Time=G::Timestamp> + 'static,
+        Tr::Batch: Batch,
+    {
+        let exchange = Exchange::new(move |update: &((K,V),G::Timestamp,R)| (update.0).0.hashed().into());
+        let scope = self.scope();
+
+        // The operator follows `arrange_core`, but holds times rather than capabilities as it has no output.
+        let _: Stream<G, ()> = self.inner.unary_frontier(exchange, "WriteToTrace", move |_capability, info| {
+
+            let logger = {
+                let register = scope.log_register();
+                register.get::<crate::logging::DifferentialEventBuilder>("differential/arrange").map(Into::into)
+            };
+
+            let mut batcher = Ba::new(logger, info.global_id);
+            if let Some(fuel) = scope.config().get::<usize>("differential/batcher_fuel") {
+                batcher.set_fuel(Some(*fuel));
+            }
+
+            // Times for the lower envelope of updates in `batcher`.
+            let mut times = Antichain::<G::Timestamp>::new();
+            let mut prev_frontier = Antichain::from_elem(<G::Timestamp as Timestamp>::minimum());
+
+            move |input, _output| {
+
+                input.for_each(|cap, data| {
+                    times.insert(cap.time().clone());
+                    batcher.push_container(data);
+                });
+
+                assert!(PartialOrder::less_equal(&prev_frontier.borrow(), &input.frontier().frontier()));
+
+                if prev_frontier.borrow() != input.frontier().frontier() {
+
+                    if seal_held::<_, _, Ba, Bu, _>(&mut batcher, &mut writer, times.elements(), |t| t, input.frontier().frontier(), |_, _| ()) {
+                        times = batcher.frontier().to_owned();
+                    }
+
+                    prev_frontier.clear();
+                    prev_frontier.extend(input.frontier().frontier().iter().cloned());
+                }
+
+                writer.exert();
+            }
+        });
+    }
+
+    /// Arranges the collection by key, forming batches only once at least `min_batch` records have been received.
+    ///
+    /// When the input frontier advances with each few records, `arrange_by_key` forms a batch for each advance,
+    /// and the trace and its readers must contend with many small batches. This method instead holds updates
+    /// back until at least `min_batch` records have been received since the last batch, or until the input
+    /// frontier has advanced `max_deferred` times, as described for `arrange_core_coalesced`. The arranged
+    /// contents are unchanged, but are reported later than they might be.
+    ///
+    /// The `max_deferred` bound trades batch size for liveness: it limits how long a quiet input can hold
+    /// back the output frontier, which `min_batch` alone does not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::ArrangeByKey;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let data = scope.new_collection_from((0 .. 10u32).map(|x| (x, x + 1))).1;
+    ///
+    ///     data.arrange_coalesced(100, 10)
+    ///         .as_collection(|k, v| (*k, *v))
+    ///         .assert_eq(&data.arrange_by_key().as_collection(|k, v| (*k, *v)));
+    /// });
+    /// ```
+    pub fn arrange_coalesced(&self, min_batch: usize, max_deferred: usize) -> Arranged<G, TraceAgent<ValSpine<K, V, G::Timestamp, R>>> {
+        let exchange = Exchange::new(move |update: &((K,V),G::Timestamp,R)| (update.0).0.hashed().into());
+        arrange_core_coalesced::<_,_,ValBatcher<_,_,_,_>,ValBuilder<_,_,_,_>,_>(&self.inner, exchange, "ArrangeCoalesced", min_batch, max_deferred)
+    }
+}
+
 impl<G: Scope, K: ExchangeData+Hashable, R: ExchangeData+Semigroup> Arrange<G, Vec<((K, ()), G::Timestamp, R)>> for Collection<G, K, R>
 where
     G::Timestamp: Lattice+Ord,
@@ -592,6 +1134,58 @@ where G::Timestamp: Lattice+Ord {
 
     /// As `arrange_by_key` but with the ability to name the arrangement.
     fn arrange_by_key_named(&self, name: &str) -> Arranged<G, TraceAgent<ValSpine<K, V, G::Timestamp, R>>>;
+
+    /// As `arrange_by_key_named` but installing `exert_logic` on the trace, in place of any default.
+    ///
+    /// The exertion logic determines how much merge effort the trace applies when no updates arrive,
+    /// for example to merge batches down promptly once the input is idle. It is installed before the
+    /// trace receives its first batch. See `Trace::set_exert_logic` for the arguments it receives.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::ArrangeByKey;
+    ///
+    /// ::timely::example(|scope| {
+    ///     // merge batches whenever there is more than one layer of them.
+    ///     scope.new_collection_from(vec![(0, 1), (1, 2)]).1
+    ///          .arrange_by_key_named_exert("Exerted", Arc::new(|layers: &[(usize, usize, usize)]| {
+    ///              let updates: usize = layers.iter().map(|(_, _, length)| length).sum();
+    ///              if layers.iter().filter(|(_, count, _)| *count > 0).count() > 1 { Some(updates) } else { None }
+    ///          }));
+    /// });
+    /// ```
+    fn arrange_by_key_named_exert(&self, name: &str, exert_logic: trace::ExertionLogic) -> Arranged<G, TraceAgent<ValSpine<K, V, G::Timestamp, R>>>;
+
+    /// As `arrange_by_key` but distributing keys among workers by `hash` rather than by `Hashable::hashed`.
+    ///
+    /// The hash function only determines which worker maintains each key, for example to spread hot keys
+    /// with a salted hash; the arrangement is ordered by key as usual. Operators that combine arrangements
+    /// without exchanging data, like `join_core` between two arrangements, require their inputs to place
+    /// keys identically, and so an arrangement distributed by a custom hash should only be combined with
+    /// arrangements distributed by the same hash. Collections are exchanged by `Hashable::hashed` when
+    /// they are arranged implicitly, and so should not be combined directly with such an arrangement.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::Reduce;
+    /// use differential_dataflow::operators::arrange::ArrangeByKey;
+    ///
+    /// ::timely::example(|scope| {
+    ///     // count the values of each key, distributing keys by a salted hash.
+    ///     scope.new_collection_from((0 .. 10u64).map(|x| (x % 3, x))).1
+    ///          .arrange_by_key_hashed(|key: &u64| (key ^ 0x5555).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    ///          .reduce(|_key, input, output| output.push((input.len(), 1)))
+    ///          .assert_eq(&scope.new_collection_from(vec![(0, 4), (1, 3), (2, 3)]).1);
+    /// });
+    /// ```
+    fn arrange_by_key_hashed<H>(&self, hash: H) -> Arranged<G, TraceAgent<ValSpine<K, V, G::Timestamp, R>>>
+    where
+        H: Fn(&K) -> u64 + 'static;
 }
 
 impl<G: Scope, K: ExchangeData+Hashable, V: ExchangeData, R: ExchangeData+Semigroup> ArrangeByKey<G, K, V, R> for Collection<G, (K,V), R>
@@ -605,6 +1199,19 @@ where
     fn arrange_by_key_named(&self, name: &str) -> Arranged<G, TraceAgent<ValSpine<K, V, G::Timestamp, R>>> {
         self.arrange_named::<ValBatcher<_,_,_,_>,ValBuilder<_,_,_,_>,_>(name)
     }
+
+    fn arrange_by_key_named_exert(&self, name: &str, exert_logic: trace::ExertionLogic) -> Arranged<G, TraceAgent<ValSpine<K, V, G::Timestamp, R>>> {
+        let exchange = Exchange::new(move |update: &((K,V),G::Timestamp,R)| (update.0).0.hashed().into());
+        arrange_core_exert::<_,_,ValBatcher<_,_,_,_>,ValBuilder<_,_,_,_>,_>(&self.inner, exchange, name, Some(exert_logic))
+    }
+
+    fn arrange_by_key_hashed<H>(&self, hash: H) -> Arranged<G, TraceAgent<ValSpine<K, V, G::Timestamp, R>>>
+    where
+        H: Fn(&K) -> u64 + 'static,
+    {
+        let exchange = Exchange::new(move |update: &((K,V),G::Timestamp,R)| hash(&(update.0).0));
+        arrange_core::<_,_,ValBatcher<_,_,_,_>,ValBuilder<_,_,_,_>,_>(&self.inner, exchange, "ArrangeByKeyHashed")
+    }
 }
 
 /// Arranges something as `(Key, ())` pairs according to a type `T` of trace.
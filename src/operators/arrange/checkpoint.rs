@@ -0,0 +1,164 @@
+//! Checkpoints of the contents of a trace.
+//!
+//! A checkpoint captures the batches of a trace and its compaction frontiers, and can be used to
+//! reconstruct an equivalent trace, for example in a later process, rather than replaying all of
+//! the input that produced the trace. Checkpoints implement `Serialize` and `Deserialize`, and
+//! can be written with any `serde` format. This module requires the `checkpoint` feature, which
+//! enables the `rc` feature of `serde` for the batches held behind `Rc` in the default spines.
+//!
+//! A checkpoint may hold the same batch several times, for example when assembled from traces
+//! that share batches. Batches are serialized by identity, each shared batch only once, and are
+//! shared again once deserialized.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+
+use timely::progress::{Antichain, Timestamp};
+use timely::progress::frontier::AntichainRef;
+use timely::order::PartialOrder;
+
+use crate::trace::BatchReader;
+
+/// The batches of a trace, and its logical and physical compaction frontiers.
+///
+/// The batches are contiguous, in the order they were introduced, starting from the minimal time.
+#[derive(Clone, Debug)]
+pub struct Checkpoint<B, T> {
+    batches: Vec<B>,
+    logical_compaction: Antichain<T>,
+    physical_compaction: Antichain<T>,
+}
+
+impl<B, T> Checkpoint<B, T>
+where
+    B: BatchReader<Time = T>,
+    T: Timestamp,
+{
+    /// Assembles a checkpoint from batches and compaction frontiers.
+    pub fn new(batches: Vec<B>, logical_compaction: Antichain<T>, physical_compaction: Antichain<T>) -> Self {
+        Checkpoint { batches, logical_compaction, physical_compaction }
+    }
+
+    /// The batches of the checkpoint, in the order they should be introduced to a trace.
+    pub fn batches(&self) -> &[B] { &self.batches[..] }
+    /// The logical compaction frontier of the checkpointed trace.
+    pub fn logical_compaction(&self) -> AntichainRef<T> { self.logical_compaction.borrow() }
+    /// The physical compaction frontier of the checkpointed trace.
+    pub fn physical_compaction(&self) -> AntichainRef<T> { self.physical_compaction.borrow() }
+
+    /// The upper frontier of the checkpointed batches.
+    ///
+    /// This is the frontier from which a restored trace accepts new batches.
+    pub fn upper(&self) -> Antichain<T> {
+        self.batches
+            .last()
+            .map(|batch| batch.upper().clone())
+            .unwrap_or_else(|| Antichain::from_elem(T::minimum()))
+    }
+
+    /// Checks that the batches are contiguous from the minimal time and compacted no further than
+    /// the compaction frontiers, and that the batches end at `upper`.
+    ///
+    /// Restoring a checkpoint that fails validation would produce a trace whose batch descriptions
+    /// are inconsistent with each other, or with the batches subsequently introduced from `upper`.
+    pub fn validate(&self, upper: AntichainRef<T>) -> Result<(), String> {
+        let mut frontier = Antichain::from_elem(T::minimum());
+        for batch in self.batches.iter() {
+            let description = batch.description();
+            if description.lower() != &frontier {
+                return Err(format!("Checkpoint: batch lower {:?} does not follow upper {:?}", description.lower(), frontier));
+            }
+            if description.lower() == description.upper() {
+                return Err(format!("Checkpoint: batch with empty interval at {:?}", description.lower()));
+            }
+            if !PartialOrder::less_equal(&description.since().borrow(), &self.logical_compaction.borrow()) {
+                return Err(format!("Checkpoint: batch since {:?} in advance of logical compaction {:?}", description.since(), self.logical_compaction));
+            }
+            frontier.clone_from(description.upper());
+        }
+        if frontier != upper.to_owned() {
+            return Err(format!("Checkpoint: batches end at {:?} rather than {:?}", frontier, upper));
+        }
+        Ok(())
+    }
+
+    /// Consumes the checkpoint, returning its batches and compaction frontiers.
+    pub fn into_parts(self) -> (Vec<B>, Antichain<T>, Antichain<T>) {
+        (self.batches, self.logical_compaction, self.physical_compaction)
+    }
+}
+
+/// Batches whose identity can be observed, so that a batch held several times is serialized once.
+pub trait SharedBatch {
+    /// A value identifying the batch, equal for handles to the same batch.
+    fn identity(&self) -> usize;
+}
+
+impl<B> SharedBatch for Rc<B> {
+    fn identity(&self) -> usize { Rc::as_ptr(self) as *const () as usize }
+}
+
+/// The serialized form of a checkpoint: its distinct batches, and for each batch the index of its copy.
+#[derive(Serialize)]
+struct SerializeCheckpoint<'a, B, T> {
+    batches: Vec<&'a B>,
+    order: Vec<usize>,
+    logical_compaction: &'a Antichain<T>,
+    physical_compaction: &'a Antichain<T>,
+}
+
+/// The deserialized form of a checkpoint, as `SerializeCheckpoint`.
+#[derive(Deserialize)]
+struct DeserializeCheckpoint<B, T> {
+    batches: Vec<B>,
+    order: Vec<usize>,
+    logical_compaction: Antichain<T>,
+    physical_compaction: Antichain<T>,
+}
+
+impl<B, T> Serialize for Checkpoint<B, T>
+where
+    B: SharedBatch+Serialize,
+    T: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut batches = Vec::new();
+        let mut indices = HashMap::new();
+        let order =
+        self.batches
+            .iter()
+            .map(|batch| *indices.entry(batch.identity()).or_insert_with(|| { batches.push(batch); batches.len() - 1 }))
+            .collect();
+
+        SerializeCheckpoint {
+            batches,
+            order,
+            logical_compaction: &self.logical_compaction,
+            physical_compaction: &self.physical_compaction,
+        }.serialize(serializer)
+    }
+}
+
+impl<'de, B, T> Deserialize<'de> for Checkpoint<B, T>
+where
+    B: Clone+Deserialize<'de>,
+    T: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let checkpoint = DeserializeCheckpoint::<B, T>::deserialize(deserializer)?;
+        let batches =
+        checkpoint.order
+            .iter()
+            .map(|index| checkpoint.batches.get(*index).cloned().ok_or_else(|| D::Error::custom(format!("Checkpoint: batch index {} out of bounds", index))))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Checkpoint {
+            batches,
+            logical_compaction: checkpoint.logical_compaction,
+            physical_compaction: checkpoint.physical_compaction,
+        })
+    }
+}
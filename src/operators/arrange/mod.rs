@@ -66,10 +66,16 @@ type TraceAgentQueueWriter<Tr> = Weak<(Activator, RefCell<BatchQueue<Tr>>)>;
 pub mod writer;
 pub mod agent;
 pub mod arrangement;
+pub mod publish;
+#[cfg(feature = "checkpoint")]
+pub mod checkpoint;
 
 pub mod upsert;
 
 pub use self::writer::TraceWriter;
 pub use self::agent::{TraceAgent, ShutdownButton};
+pub use self::publish::{PublishedTrace, TracePublisher};
+#[cfg(feature = "checkpoint")]
+pub use self::checkpoint::Checkpoint;
 
-pub use self::arrangement::{Arranged, Arrange, ArrangeByKey, ArrangeBySelf};
\ No newline at end of file
+pub use self::arrangement::{Arranged, Arrange, ArrangeByKey, ArrangeBySelf, ArrangementRegistry};
\ No newline at end of file
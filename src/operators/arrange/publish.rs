@@ -0,0 +1,164 @@
+//! Publishing traces for use outside the dataflows that maintain them.
+//!
+//! A published trace is a shared handle to a `TraceAgent`, which can be registered by name with
+//! a `TracePublisher` and imported into other dataflows. The handle manages the bookkeeping that
+//! would otherwise be repeated by each user: it holds its own compaction frontiers, it can be
+//! weakly referenced by operators that should not keep the trace alive, and the trace is released
+//! when the last handle is dropped.
+
+use std::rc::{Rc, Weak};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use timely::dataflow::Scope;
+use timely::progress::frontier::AntichainRef;
+
+use crate::{ExchangeData, Collection, Hashable};
+use crate::difference::Semigroup;
+use crate::lattice::Lattice;
+use crate::trace::TraceReader;
+use crate::trace::implementations::ValSpine;
+
+use super::{TraceAgent, Arranged, ArrangeByKey};
+
+/// A shared handle to a published trace.
+///
+/// Clones of the handle share one `TraceAgent`, whose compaction frontiers are independent of
+/// those of other agents for the same trace. The agent is dropped, releasing its hold on the
+/// compaction of the trace, when the last clone is dropped or when `release` is called.
+pub struct PublishedTrace<Tr: TraceReader> {
+    trace: Rc<RefCell<Option<TraceAgent<Tr>>>>,
+}
+
+impl<Tr: TraceReader> PublishedTrace<Tr> {
+    /// A handle for `trace`.
+    pub fn new(trace: TraceAgent<Tr>) -> Self {
+        PublishedTrace { trace: Rc::new(RefCell::new(Some(trace))) }
+    }
+    /// A handle without a trace, which can be supplied later with `set`.
+    ///
+    /// This allows operators that will maintain the trace to hold a reference to it before it exists.
+    pub fn empty() -> Self {
+        PublishedTrace { trace: Rc::new(RefCell::new(None)) }
+    }
+    /// Supplies the trace for the handle, replacing and releasing any previous trace.
+    pub fn set(&self, trace: TraceAgent<Tr>) {
+        *self.trace.borrow_mut() = Some(trace);
+    }
+    /// Removes the trace from the handle and all of its clones.
+    ///
+    /// Dropping the result releases the trace, even if clones of the handle remain.
+    pub fn release(&self) -> Option<TraceAgent<Tr>> {
+        self.trace.borrow_mut().take()
+    }
+    /// True if the handle has no trace, because it was not yet set or has been released.
+    pub fn is_released(&self) -> bool {
+        self.trace.borrow().is_none()
+    }
+    /// Allows the trace to compact times not in advance of `frontier`, both logically and physically.
+    pub fn set_compaction(&self, frontier: AntichainRef<Tr::Time>) {
+        if let Some(trace) = self.trace.borrow_mut().as_mut() {
+            trace.set_logical_compaction(frontier);
+            trace.set_physical_compaction(frontier);
+        }
+    }
+    /// Applies `logic` to the trace, if it has not been released.
+    pub fn with_trace<X, F: FnOnce(&mut TraceAgent<Tr>)->X>(&self, logic: F) -> Option<X> {
+        self.trace.borrow_mut().as_mut().map(logic)
+    }
+    /// A weak reference to the trace, which does not keep it from being released.
+    pub fn downgrade(&self) -> Weak<RefCell<Option<TraceAgent<Tr>>>> {
+        Rc::downgrade(&self.trace)
+    }
+}
+
+impl<Tr: TraceReader+'static> PublishedTrace<Tr> {
+    /// Imports the trace into `scope`, if it has not been released.
+    pub fn import<G>(&self, scope: &G) -> Option<Arranged<G, TraceAgent<Tr>>>
+    where
+        G: Scope<Timestamp=Tr::Time>,
+    {
+        self.with_trace(|trace| trace.import(scope))
+    }
+}
+
+impl<Tr: TraceReader> Clone for PublishedTrace<Tr> {
+    fn clone(&self) -> Self {
+        PublishedTrace { trace: Rc::clone(&self.trace) }
+    }
+}
+
+/// A registry in which traces can be published by name.
+pub trait TracePublisher<Tr: TraceReader> {
+    /// Registers `trace` under `name`, replacing any trace previously registered under that name.
+    fn publish(&mut self, name: &str, trace: PublishedTrace<Tr>);
+}
+
+impl<Tr: TraceReader> TracePublisher<Tr> for HashMap<String, PublishedTrace<Tr>> {
+    fn publish(&mut self, name: &str, trace: PublishedTrace<Tr>) {
+        self.insert(name.to_owned(), trace);
+    }
+}
+
+impl<G, K, V, R> Collection<G, (K, V), R>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    K: ExchangeData+Hashable,
+    V: ExchangeData,
+    R: ExchangeData+Semigroup,
+{
+    /// Arranges the collection by key, and publishes the trace under `name` with `publisher`.
+    ///
+    /// The returned handle shares its trace with the handle registered with `publisher`. The trace
+    /// initially retains its full history; use `set_compaction` to allow it to compact. Once both
+    /// the returned handle and the registered handle are dropped, the trace is released.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use timely::Config;
+    /// use timely::progress::Antichain;
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::execute(Config::thread(), |worker| {
+    ///
+    ///     // publish an arrangement from a first dataflow.
+    ///     let mut published: HashMap<String, _> = HashMap::new();
+    ///     let mut input = worker.dataflow::<u32,_,_>(|scope| {
+    ///         let (input, data) = scope.new_collection();
+    ///         data.map(|x: u32| (x, x + 1))
+    ///             .publish_trace("data", &mut published);
+    ///         input
+    ///     });
+    ///
+    ///     input.insert(0);
+    ///     input.advance_to(1);
+    ///     input.flush();
+    ///     worker.step();
+    ///
+    ///     // import the published trace into a second dataflow.
+    ///     let trace = published["data"].clone();
+    ///     worker.dataflow(|scope| {
+    ///         trace.import(scope)
+    ///              .expect("trace not released")
+    ///              .as_collection(|k, v| (*k, *v));
+    ///     });
+    ///
+    ///     // allow the trace to compact, and then release it.
+    ///     trace.set_compaction(Antichain::from_elem(1).borrow());
+    ///     published.clear();
+    ///     drop(trace);
+    ///
+    /// }).unwrap();
+    /// ```
+    pub fn publish_trace<P>(&self, name: &str, publisher: &mut P) -> PublishedTrace<ValSpine<K, V, G::Timestamp, R>>
+    where
+        P: TracePublisher<ValSpine<K, V, G::Timestamp, R>>,
+    {
+        let published = PublishedTrace::new(self.arrange_by_key_named(name).trace);
+        publisher.publish(name, published.clone());
+        published
+    }
+}
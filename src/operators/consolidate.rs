@@ -72,7 +72,8 @@ where
     /// ensure that at most one copy of each `(data, time)` pair exists in the
     /// results. Instead, it acts on each batch of data and collapses equivalent
     /// `(data, time)` pairs found therein, suppressing any that accumulate to
-    /// zero.
+    /// zero. Updates to the same data at different times are not merged, even
+    /// when they arrive in the same batch.
     ///
     /// # Examples
     ///
@@ -106,4 +107,67 @@ where
             })
             .as_collection()
     }
+
+    /// Aggregates the weights of equal records within each worker, ahead of an exchange.
+    ///
+    /// Unlike `consolidate`, this method does not exchange data, and unlike `consolidate_stream`
+    /// it is not limited to individual batches. Updates are held back until their capability's
+    /// time is complete, at which point all updates received by the worker for that time are
+    /// consolidated together, and those that accumulate to zero are suppressed. The accumulated
+    /// collection is unchanged, but updates that cancel locally are never sent to other workers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let x = scope.new_collection_from(1 .. 10u32).1;
+    ///
+    ///     x.negate()
+    ///      .concat(&x)
+    ///      .coalesce_updates_in_flight()
+    ///      .assert_empty();
+    /// });
+    /// ```
+    pub fn coalesce_updates_in_flight(&self) -> Self {
+
+        use timely::dataflow::channels::pact::Pipeline;
+        use timely::dataflow::operators::{Capability, Operator};
+        use crate::collection::AsCollection;
+
+        self.inner
+            .unary_frontier(Pipeline, "CoalesceUpdates", |_cap, _info| {
+
+                // Updates received for each capability whose time has not yet completed.
+                let mut stash: Vec<(Capability<G::Timestamp>, Vec<(D, G::Timestamp, R)>)> = Vec::new();
+
+                move |input, output| {
+                    input.for_each(|time, data| {
+                        if let Some(index) = stash.iter().position(|(cap, _)| cap.time() == time.time()) {
+                            stash[index].1.append(data);
+                        }
+                        else {
+                            stash.push((time.retain(), std::mem::take(data)));
+                        }
+                    });
+
+                    let frontier = input.frontier();
+                    stash.retain_mut(|(cap, updates)| {
+                        if frontier.less_equal(cap.time()) {
+                            true
+                        }
+                        else {
+                            crate::consolidation::consolidate_updates(updates);
+                            if !updates.is_empty() {
+                                output.session(cap).give_container(updates);
+                            }
+                            false
+                        }
+                    });
+                }
+            })
+            .as_collection()
+    }
 }
@@ -1,4 +1,4 @@
-//! Count the number of occurrences of each element.
+//! Count the number of occurrences of each element, or sum their values.
 
 use timely::order::TotalOrder;
 use timely::dataflow::*;
@@ -12,13 +12,19 @@ use crate::{ExchangeData, Collection};
 use crate::difference::{IsZero, Semigroup};
 use crate::hashable::Hashable;
 use crate::collection::AsCollection;
-use crate::operators::arrange::{Arranged, ArrangeBySelf};
+use crate::operators::arrange::{Arranged, ArrangeByKey, ArrangeBySelf};
 use crate::trace::{BatchReader, Cursor, TraceReader};
 
 /// Extension trait for the `count` differential dataflow method.
 pub trait CountTotal<G: Scope, K: ExchangeData, R: Semigroup> where G::Timestamp: TotalOrder+Lattice+Ord {
     /// Counts the number of occurrences of each element.
     ///
+    /// The output reports running counts: for each key and each distinct time at which its updates
+    /// do not cancel, the output retracts the previous count and asserts the count accumulated through
+    /// that time, in time order. Counts may go negative in response to negative differences, and keys
+    /// whose records are fully retracted have their counts retracted, so the output consolidates to
+    /// empty with the input.
+    ///
     /// # Examples
     ///
     /// ```
@@ -29,7 +35,8 @@ pub trait CountTotal<G: Scope, K: ExchangeData, R: Semigroup> where G::Timestamp
     ///     // report the number of occurrences of each key
     ///     scope.new_collection_from(1 .. 10).1
     ///          .map(|x| x / 3)
-    ///          .count_total();
+    ///          .count_total()
+    ///          .assert_eq(&scope.new_collection_from(vec![(0, 2), (1, 3), (2, 3), (3, 1)]).1);
     /// });
     /// ```
     fn count_total(&self) -> Collection<G, (K, R), isize> {
@@ -141,3 +148,156 @@ where
         .as_collection()
     }
 }
+
+/// Extension trait for the `sum_total` differential dataflow method.
+pub trait SumTotal<G: Scope, K: ExchangeData, V: ExchangeData, R: Semigroup> where G::Timestamp: TotalOrder+Lattice+Ord {
+    /// Sums a numeric projection of the values associated with each key.
+    ///
+    /// Each `(key, val)` record contributes `value(val)` scaled by its multiplicity, and the
+    /// output contains `(key, sum)` for each key with a non-zero number of records. Retractions
+    /// subtract their contribution from the sum.
+    ///
+    /// Like `count_total`, this method relies on totally ordered timestamps to maintain only
+    /// the current accumulation for each key, rather than the general history `reduce` requires.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::{Reduce, SumTotal};
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let data = scope.new_collection_from(1 .. 10u64).1
+    ///                     .map(|x| (x % 3, x));
+    ///
+    ///     let sums = data.reduce(|_key, input, output| {
+    ///         let sum: i128 = input.iter().map(|(v, r)| (**v as i128) * (*r as i128)).sum();
+    ///         output.push((sum, 1));
+    ///     });
+    ///
+    ///     data.sum_total(|v| *v as i128)
+    ///         .assert_eq(&sums);
+    /// });
+    /// ```
+    fn sum_total<F: FnMut(&V)->i128+'static>(&self, value: F) -> Collection<G, (K, i128), isize>;
+}
+
+impl<G: Scope, K: ExchangeData+Hashable, V: ExchangeData, R: ExchangeData+Semigroup+TryInto<i128>> SumTotal<G, K, V, R> for Collection<G, (K, V), R>
+where G::Timestamp: TotalOrder+Lattice+Ord {
+    fn sum_total<F: FnMut(&V)->i128+'static>(&self, value: F) -> Collection<G, (K, i128), isize> {
+        self.arrange_by_key_named("Arrange: SumTotal")
+            .sum_total(value)
+    }
+}
+
+impl<G, K, V, T1> SumTotal<G, K, V, T1::Diff> for Arranged<G, T1>
+where
+    G: Scope<Timestamp=T1::Time>,
+    T1: for<'a> TraceReader<Key<'a>=&'a K, Val<'a>=&'a V>+Clone+'static,
+    K: ExchangeData,
+    V: ExchangeData,
+    T1::Time: TotalOrder,
+    T1::Diff: ExchangeData+TryInto<i128>,
+{
+    fn sum_total<F: FnMut(&V)->i128+'static>(&self, mut logic: F) -> Collection<G, (K, i128), isize> {
+
+        let mut trace = self.trace.clone();
+
+        self.stream.unary_frontier(Pipeline, "SumTotal", move |_,_| {
+
+            // tracks the lower and upper limit of received batches.
+            let mut lower_limit = timely::progress::frontier::Antichain::from_elem(<G::Timestamp as timely::progress::Timestamp>::minimum());
+            let mut upper_limit = timely::progress::frontier::Antichain::from_elem(<G::Timestamp as timely::progress::Timestamp>::minimum());
+
+            // per-key changes to `(count, sum)`, ordered by time.
+            let mut changes = Vec::<(G::Timestamp, (i128, i128))>::new();
+
+            move |input, output| {
+
+                let mut batch_cursors = Vec::new();
+                let mut batch_storage = Vec::new();
+
+                // Downgrade previous upper limit to be current lower limit.
+                lower_limit.clear();
+                lower_limit.extend(upper_limit.borrow().iter().cloned());
+
+                let mut cap = None;
+                input.for_each(|capability, batches| {
+                    if cap.is_none() {                          // NB: Assumes batches are in-order
+                        cap = Some(capability.retain());
+                    }
+                    for batch in batches.drain(..) {
+                        upper_limit.clone_from(batch.upper());  // NB: Assumes batches are in-order
+                        batch_cursors.push(batch.cursor());
+                        batch_storage.push(batch);
+                    }
+                });
+
+                if let Some(capability) = cap {
+
+                    let mut session = output.session(&capability);
+
+                    use crate::trace::cursor::CursorList;
+                    let mut batch_cursor = CursorList::new(batch_cursors, &batch_storage);
+                    let (mut trace_cursor, trace_storage) = trace.cursor_through(lower_limit.borrow()).unwrap();
+
+                    while let Some(key) = batch_cursor.get_key(&batch_storage) {
+
+                        // The number of records and the sum of their values, prior to the batch.
+                        let mut count = 0i128;
+                        let mut sum = 0i128;
+
+                        trace_cursor.seek_key(&trace_storage, key);
+                        if trace_cursor.get_key(&trace_storage) == Some(key) {
+                            while let Some(val) = trace_cursor.get_val(&trace_storage) {
+                                let value = logic(val);
+                                trace_cursor.map_times(&trace_storage, |_, diff| {
+                                    let diff = diff_to_i128(diff.into_owned());
+                                    count += diff;
+                                    sum += value * diff;
+                                });
+                                trace_cursor.step_val(&trace_storage);
+                            }
+                        }
+
+                        // Collect the changes across all values, and order them by time.
+                        while let Some(val) = batch_cursor.get_val(&batch_storage) {
+                            let value = logic(val);
+                            batch_cursor.map_times(&batch_storage, |time, diff| {
+                                let diff = diff_to_i128(diff.into_owned());
+                                changes.push((time.into_owned(), (diff, value * diff)));
+                            });
+                            batch_cursor.step_val(&batch_storage);
+                        }
+                        crate::consolidation::consolidate(&mut changes);
+
+                        for (time, (count_diff, sum_diff)) in changes.drain(..) {
+                            if count != 0 {
+                                session.give(((key.clone(), sum), time.clone(), -1));
+                            }
+                            count += count_diff;
+                            sum += sum_diff;
+                            if count != 0 {
+                                session.give(((key.clone(), sum), time, 1));
+                            }
+                        }
+
+                        batch_cursor.step_key(&batch_storage);
+                    }
+                }
+
+                // tidy up the shared input trace.
+                trace.advance_upper(&mut upper_limit);
+                trace.set_logical_compaction(upper_limit.borrow());
+                trace.set_physical_compaction(upper_limit.borrow());
+            }
+        })
+        .as_collection()
+    }
+}
+
+/// Converts a difference to an `i128`, panicking if it does not fit.
+fn diff_to_i128<R: TryInto<i128>>(diff: R) -> i128 {
+    diff.try_into().unwrap_or_else(|_| panic!("SumTotal: difference does not fit in an i128"))
+}
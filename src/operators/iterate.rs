@@ -33,15 +33,16 @@
 use std::fmt::Debug;
 use std::ops::Deref;
 
-use timely::progress::{Timestamp, PathSummary};
+use timely::progress::{Antichain, Timestamp, PathSummary};
 use timely::order::Product;
 
 use timely::dataflow::*;
 use timely::dataflow::scopes::child::Iterative;
-use timely::dataflow::operators::{Feedback, ConnectLoop, Map};
+use timely::dataflow::operators::{Feedback, ConnectLoop, Map, Operator, Capability};
 use timely::dataflow::operators::feedback::Handle;
+use timely::dataflow::channels::pact::Pipeline;
 
-use crate::{Data, Collection};
+use crate::{Data, Collection, AsCollection};
 use crate::difference::{Semigroup, Abelian};
 use crate::lattice::Lattice;
 
@@ -120,6 +121,211 @@ impl<G: Scope, D: Ord+Data+Debug, R: Semigroup+'static> Iterate<G, D, R> for G {
     }
 }
 
+impl<G: Scope, D: Ord+Data+Debug, R: Abelian+'static> Collection<G, D, R> {
+    /// Iteratively apply `logic` to the source collection, reporting the volume of change in each round.
+    ///
+    /// The first returned collection is the same as the result of `iterate`. The second collection
+    /// contains a record `(round, count)` for each round of each input time, where `count` is the
+    /// number of consolidated updates `logic` produced in that round. The round at which the
+    /// iteration reaches its fixed point is reported with a count of zero. Monitoring the counts
+    /// reveals iterations that converge slowly, or not at all.
+    ///
+    /// The reports are produced as each round completes, and are not part of the loop, so they
+    /// do not influence the computation of the fixed point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let (result, rounds) =
+    ///     scope.new_collection_from(vec![0u32]).1
+    ///          .iterate_instrumented(|values| {
+    ///              values.map(|x| std::cmp::min(x + 1, 3))
+    ///                    .consolidate()
+    ///          });
+    ///
+    ///     result.assert_eq(&scope.new_collection_from(vec![3]).1);
+    ///     rounds.assert_eq(&scope.new_collection_from(vec![(0, 1), (1, 2), (2, 2), (3, 0)]).1);
+    /// });
+    /// ```
+    pub fn iterate_instrumented<F>(&self, logic: F) -> (Collection<G, D, R>, Collection<G, (u64, usize), isize>)
+        where G::Timestamp: Lattice,
+              for<'a> F: FnOnce(&Collection<Iterative<'a, G, u64>, D, R>)->Collection<Iterative<'a, G, u64>, D, R> {
+
+        self.inner.scope().scoped("IterateInstrumented", |subgraph| {
+
+            let variable = Variable::new_from(self.enter(subgraph), Product::new(Default::default(), 1));
+            let result = logic(&variable);
+            variable.set(&result);
+
+            let rounds =
+            result
+                .inner
+                .unary_frontier(Pipeline, "IterateRounds", |_,_| {
+
+                    // changes to `result` at each round, with a capability to report them.
+                    let mut stash: Vec<(Capability<Product<G::Timestamp, u64>>, Vec<(D, R)>)> = Vec::new();
+
+                    move |input, output| {
+
+                        input.for_each(|capability, data| {
+                            for (datum, time, diff) in data.drain(..) {
+                                if let Some((_, changes)) = stash.iter_mut().find(|(cap, _)| cap.time() == &time) {
+                                    changes.push((datum, diff));
+                                }
+                                else {
+                                    stash.push((capability.delayed(&time), vec![(datum, diff)]));
+                                }
+                            }
+                        });
+
+                        // Report completed rounds in time order, so that a round with changes can
+                        // schedule a report for the following round, which may be the fixed point.
+                        stash.sort_by(|x, y| x.0.time().cmp(y.0.time()));
+                        let mut index = 0;
+                        while index < stash.len() {
+                            if !input.frontier().less_equal(stash[index].0.time()) {
+                                let (capability, mut changes) = stash.remove(index);
+                                crate::consolidation::consolidate(&mut changes);
+                                let round = capability.time().inner;
+                                output.session(&capability).give(((round, changes.len()), capability.time().clone(), 1));
+                                if !changes.is_empty() {
+                                    let next = Product::new(capability.time().outer.clone(), round + 1);
+                                    if !stash.iter().any(|(cap, _)| cap.time() == &next) {
+                                        let position = stash.partition_point(|(cap, _)| cap.time() < &next);
+                                        stash.insert(position, (capability.delayed(&next), Vec::new()));
+                                    }
+                                }
+                            }
+                            else {
+                                index += 1;
+                            }
+                        }
+                    }
+                })
+                .as_collection();
+
+            (result.leave(), rounds.leave())
+        })
+    }
+
+    /// Iteratively apply `logic` to the source collection until convergence, or until `stop` holds.
+    ///
+    /// The `stop` function is applied to the result of `logic` in each round, and the iteration ceases
+    /// at the first round whose result leads `stop` to produce a `true` record: that round's result is
+    /// returned, and its changes are not fed back into the loop. Because `stop` observes the result of
+    /// the round, rather than the input to the round, it does not lag the iteration by a round. If
+    /// `stop` never produces `true`, this is the same as `iterate`.
+    ///
+    /// The condition is consulted once each round completes, which requires the changes of the round
+    /// to be held back until then. As each time is considered independently, input changes at later
+    /// times may resume an iteration that the condition had stopped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     // increments values without end, unless stopped.
+    ///     scope.new_collection_from(vec![0u32]).1
+    ///          .iterate_until(
+    ///              |values| values.map(|x| x + 1),
+    ///              |values| values.map(|x| x >= 5),
+    ///          )
+    ///          .assert_eq(&scope.new_collection_from(vec![5]).1);
+    /// });
+    /// ```
+    pub fn iterate_until<F, S>(&self, logic: F, stop: S) -> Collection<G, D, R>
+        where G::Timestamp: Lattice,
+              for<'a> F: FnOnce(&Collection<Iterative<'a, G, u64>, D, R>)->Collection<Iterative<'a, G, u64>, D, R>,
+              for<'a> S: FnOnce(&Collection<Iterative<'a, G, u64>, D, R>)->Collection<Iterative<'a, G, u64>, bool> {
+
+        use timely::order::PartialOrder;
+
+        self.inner.scope().scoped("IterateUntil", |subgraph| {
+
+            let source = self.enter(subgraph);
+            let variable = Variable::new_from(source.clone(), Product::new(Default::default(), 1));
+            let result = logic(&variable);
+            let stopped = stop(&result);
+
+            // changes to `result` are fed back only for rounds at which `stopped` does not hold.
+            // the retraction of `source` is gated along with them, so that a stopped round
+            // leaves the variable unchanged rather than empty.
+            let gated =
+            result
+                .concat(&source.negate())
+                .inner
+                .binary_frontier(&stopped.inner, Pipeline, Pipeline, "IterateUntil", |_,_| {
+
+                    // changes to `result` at each time, awaiting the completion of that time.
+                    let mut stash: Vec<(Capability<Product<G::Timestamp, u64>>, Vec<(D, R)>)> = Vec::new();
+                    // updates to the number of `true` records produced by `stop`.
+                    let mut stops: Vec<(Product<G::Timestamp, u64>, isize)> = Vec::new();
+
+                    move |input1, input2, output| {
+
+                        input1.for_each(|capability, data| {
+                            for (datum, time, diff) in data.drain(..) {
+                                if let Some((_, changes)) = stash.iter_mut().find(|(cap, _)| cap.time() == &time) {
+                                    changes.push((datum, diff));
+                                }
+                                else {
+                                    stash.push((capability.delayed(&time), vec![(datum, diff)]));
+                                }
+                            }
+                        });
+                        input2.for_each(|_capability, data| {
+                            stops.extend(data.drain(..).filter(|(stop, _, _)| *stop).map(|(_, time, diff)| (time, diff)));
+                        });
+
+                        // Changes at times complete for both inputs are either released or discarded.
+                        let (ready, pending): (Vec<_>, Vec<_>) = stash.drain(..).partition(|(cap, _)| {
+                            !input1.frontier().less_equal(cap.time()) && !input2.frontier().less_equal(cap.time())
+                        });
+                        stash = pending;
+
+                        for (capability, changes) in ready {
+                            let time = capability.time().clone();
+                            let count: isize = stops.iter().filter(|(t, _)| t.less_equal(&time)).map(|(_, diff)| *diff).sum();
+                            if count <= 0 {
+                                output.session(&capability).give_iterator(changes.into_iter().map(|(datum, diff)| (datum, time.clone(), diff)));
+                            }
+                        }
+
+                        // `stops` is only consulted at the times of held and future changes, and may be
+                        // advanced to their lower bound and consolidated, which discards cancelled entries.
+                        let mut lower = Antichain::new();
+                        for time in input1.frontier().frontier().iter() {
+                            lower.insert(time.clone());
+                        }
+                        for (capability, _) in stash.iter() {
+                            lower.insert(capability.time().clone());
+                        }
+                        if lower.is_empty() {
+                            stops.clear();
+                        }
+                        else {
+                            for (time, _) in stops.iter_mut() {
+                                time.advance_by(lower.borrow());
+                            }
+                            crate::consolidation::consolidate(&mut stops);
+                        }
+                    }
+                })
+                .as_collection();
+
+            variable.set_concat(&gated);
+            result.leave()
+        })
+    }
+}
+
 /// A recursively defined collection.
 ///
 /// The `Variable` struct allows differential dataflow programs requiring more sophisticated
@@ -82,6 +82,32 @@ pub trait Join<G: Scope, K: Data, V: Data, R: Semigroup> {
     fn join_map<V2, R2, D, L>(&self, other: &Collection<G, (K,V2), R2>, logic: L) -> Collection<G, D, <R as Multiply<R2>>::Output>
     where K: ExchangeData, V2: ExchangeData, R2: ExchangeData+Semigroup, R: Multiply<R2>, <R as Multiply<R2>>::Output: Semigroup+'static, D: Data, L: FnMut(&K, &V, &V2)->D+'static;
 
+    /// Matches pairs `(key,val1)` and `(key,val2)` based on `key` and then applies a function, retaining only `Some` results.
+    ///
+    /// This is equivalent to `join_map` followed by `filter` and `map`, except that matches for which
+    /// `logic` returns `None` are never produced, and so are not allocated, sent, or consolidated. This
+    /// can substantially reduce the volume of output when keys match broadly but few pairs of values
+    /// are of interest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::Join;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let x = scope.new_collection_from(vec![(0, 1), (0, 2), (1, 3)]).1;
+    ///     let y = scope.new_collection_from(vec![(0, 2), (1, 4)]).1;
+    ///     let z = scope.new_collection_from(vec![(0, 2)]).1;
+    ///
+    ///     x.join_map_opt(&y, |&key, &a, &b| if a == b { Some((key, a)) } else { None })
+    ///      .assert_eq(&z);
+    /// });
+    /// ```
+    fn join_map_opt<V2, R2, D, L>(&self, other: &Collection<G, (K,V2), R2>, logic: L) -> Collection<G, D, <R as Multiply<R2>>::Output>
+    where K: ExchangeData, V2: ExchangeData, R2: ExchangeData+Semigroup, R: Multiply<R2>, <R as Multiply<R2>>::Output: Semigroup+'static, D: Data, L: FnMut(&K, &V, &V2)->Option<D>+'static;
+
     /// Matches pairs `(key, val)` and `key` based on `key`, producing the former with frequencies multiplied.
     ///
     /// When the second collection contains frequencies that are either zero or one this is the more traditional
@@ -135,6 +161,38 @@ pub trait Join<G: Scope, K: Data, V: Data, R: Semigroup> {
     /// ```
     fn antijoin<R2>(&self, other: &Collection<G, K, R2>) -> Collection<G, (K, V), R>
     where K: ExchangeData, R2: ExchangeData+Semigroup, R: Multiply<R2, Output = R>, R: Abelian+'static;
+
+    /// Subtracts the semijoin with the arranged keys `keys` from `self`.
+    ///
+    /// This is `antijoin` for keys that are already arranged, for example by `arrange_by_self`, and
+    /// the arrangement is used as provided. As the keys of `keys` come and go, the records of `self`
+    /// with those keys are retracted and re-introduced. As with `antijoin`, the keys should have
+    /// multiplicities zero or one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::Join;
+    /// use differential_dataflow::operators::arrange::ArrangeBySelf;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let x = scope.new_collection_from(vec![(0u32, 1), (1, 3)]).1;
+    ///     let y = scope.new_collection_from(vec![0u32, 2]).1
+    ///                  .arrange_by_self();
+    ///     let z = scope.new_collection_from(vec![(1, 3)]).1;
+    ///
+    ///     x.antijoin_core(&y)
+    ///      .assert_eq(&z);
+    /// });
+    /// ```
+    fn antijoin_core<Tr2>(&self, keys: &Arranged<G, Tr2>) -> Collection<G, (K, V), R>
+    where
+        G::Timestamp: Lattice+Ord,
+        Tr2: for<'a> TraceReader<Key<'a>=&'a K, Val<'a>=&'a (), Time=G::Timestamp>+Clone+'static,
+        R: Multiply<Tr2::Diff, Output = R>,
+        R: Abelian+'static;
 }
 
 impl<G, K, V, R> Join<G, K, V, R> for Collection<G, (K, V), R>
@@ -152,6 +210,13 @@ where
         arranged1.join_core(&arranged2, move |k,v1,v2| Some(logic(k,v1,v2)))
     }
 
+    fn join_map_opt<V2: ExchangeData, R2: ExchangeData+Semigroup, D: Data, L>(&self, other: &Collection<G, (K, V2), R2>, mut logic: L) -> Collection<G, D, <R as Multiply<R2>>::Output>
+    where R: Multiply<R2>, <R as Multiply<R2>>::Output: Semigroup+'static, L: FnMut(&K, &V, &V2)->Option<D>+'static {
+        let arranged1 = self.arrange_by_key();
+        let arranged2 = other.arrange_by_key();
+        arranged1.join_core(&arranged2, move |k,v1,v2| logic(k,v1,v2))
+    }
+
     fn semijoin<R2: ExchangeData+Semigroup>(&self, other: &Collection<G, K, R2>) -> Collection<G, (K, V), <R as Multiply<R2>>::Output>
     where R: Multiply<R2>, <R as Multiply<R2>>::Output: Semigroup+'static {
         let arranged1 = self.arrange_by_key();
@@ -163,6 +228,16 @@ where
     where R: Multiply<R2, Output=R>, R: Abelian+'static {
         self.concat(&self.semijoin(other).negate())
     }
+
+    fn antijoin_core<Tr2>(&self, keys: &Arranged<G, Tr2>) -> Collection<G, (K, V), R>
+    where
+        Tr2: for<'a> TraceReader<Key<'a>=&'a K, Val<'a>=&'a (), Time=G::Timestamp>+Clone+'static,
+        R: Multiply<Tr2::Diff, Output=R>,
+        R: Abelian+'static,
+    {
+        let matched = self.arrange_by_key().join_core(keys, |k,v,_| Some((k.clone(), v.clone())));
+        self.concat(&matched.negate())
+    }
 }
 
 impl<G, K, V, Tr> Join<G, K, V, Tr::Diff> for Arranged<G, Tr>
@@ -182,6 +257,16 @@ where
         self.join_core(&arranged2, move |k,v1,v2| Some(logic(k,v1,v2)))
     }
 
+    fn join_map_opt<V2: ExchangeData, R2: ExchangeData+Semigroup, D: Data, L>(&self, other: &Collection<G, (K, V2), R2>, mut logic: L) -> Collection<G, D, <Tr::Diff as Multiply<R2>>::Output>
+    where
+        Tr::Diff: Multiply<R2>,
+        <Tr::Diff as Multiply<R2>>::Output: Semigroup+'static,
+        L: for<'a> FnMut(Tr::Key<'a>, Tr::Val<'a>, &V2)->Option<D>+'static,
+    {
+        let arranged2 = other.arrange_by_key();
+        self.join_core(&arranged2, move |k,v1,v2| logic(k,v1,v2))
+    }
+
     fn semijoin<R2: ExchangeData+Semigroup>(&self, other: &Collection<G, K, R2>) -> Collection<G, (K, V), <Tr::Diff as Multiply<R2>>::Output>
     where Tr::Diff: Multiply<R2>, <Tr::Diff as Multiply<R2>>::Output: Semigroup+'static {
         let arranged2 = other.arrange_by_self();
@@ -193,6 +278,17 @@ where
         self.as_collection(|k,v| (k.clone(), v.clone()))
             .concat(&self.semijoin(other).negate())
     }
+
+    fn antijoin_core<Tr2>(&self, keys: &Arranged<G, Tr2>) -> Collection<G, (K, V), Tr::Diff>
+    where
+        Tr2: for<'a> TraceReader<Key<'a>=&'a K, Val<'a>=&'a (), Time=G::Timestamp>+Clone+'static,
+        Tr::Diff: Multiply<Tr2::Diff, Output=Tr::Diff>,
+        Tr::Diff: Abelian+'static,
+    {
+        let matched = self.join_core(keys, |k,v,_| Some((k.clone(), v.clone())));
+        self.as_collection(|k,v| (k.clone(), v.clone()))
+            .concat(&matched.negate())
+    }
 }
 
 /// Matches the elements of two arranged traces.
@@ -242,6 +338,39 @@ pub trait JoinCore<G: Scope, K: 'static + ?Sized, V: 'static + ?Sized, R: Semigr
         L: FnMut(&K,&V,Tr2::Val<'_>)->I+'static,
         ;
 
+    /// As `join_core`, but with a name for the join operator.
+    ///
+    /// The name is reported by timely's logging for the operator, and can be used to distinguish several joins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::ArrangeByKey;
+    /// use differential_dataflow::operators::join::JoinCore;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let x = scope.new_collection_from(vec![(0u32, 1), (1, 3)]).1;
+    ///     let y = scope.new_collection_from(vec![(0, 'a'), (1, 'b')]).1
+    ///                  .arrange_by_key();
+    ///
+    ///     let z = scope.new_collection_from(vec![(1, 'a'), (3, 'b')]).1;
+    ///
+    ///     x.join_core_named("JoinLetters", &y, |_key, &a, &b| Some((a, b)))
+    ///      .assert_eq(&z);
+    /// });
+    /// ```
+    fn join_core_named<Tr2,I,L> (&self, name: &str, stream2: &Arranged<G,Tr2>, result: L) -> Collection<G,I::Item,<R as Multiply<Tr2::Diff>>::Output>
+    where
+        Tr2: for<'a> TraceReader<Key<'a>=&'a K, Time=G::Timestamp>+Clone+'static,
+        R: Multiply<Tr2::Diff>,
+        <R as Multiply<Tr2::Diff>>::Output: Semigroup+'static,
+        I: IntoIterator,
+        I::Item: Data,
+        L: FnMut(&K,&V,Tr2::Val<'_>)->I+'static,
+        ;
+
     /// An unsafe variant of `join_core` where the `result` closure takes additional arguments for `time` and
     /// `diff` as input and returns an iterator over `(data, time, diff)` triplets. This allows for more
     /// flexibility, but is more error-prone.
@@ -307,6 +436,19 @@ where
             .join_core(stream2, result)
     }
 
+    fn join_core_named<Tr2,I,L> (&self, name: &str, stream2: &Arranged<G,Tr2>, result: L) -> Collection<G,I::Item,<R as Multiply<Tr2::Diff>>::Output>
+    where
+        Tr2: for<'a> TraceReader<Key<'a>=&'a K, Time=G::Timestamp>+Clone+'static,
+        R: Multiply<Tr2::Diff>,
+        <R as Multiply<Tr2::Diff>>::Output: Semigroup+'static,
+        I: IntoIterator,
+        I::Item: Data,
+        L: FnMut(&K,&V,Tr2::Val<'_>)->I+'static,
+    {
+        self.arrange_by_key_named(&format!("Arrange: {}", name))
+            .join_core_named(name, stream2, result)
+    }
+
     fn join_core_internal_unsafe<Tr2,I,L,D,ROut> (&self, stream2: &Arranged<G,Tr2>, result: L) -> Collection<G,D,ROut>
     where
         Tr2: for<'a> TraceReader<Key<'a>=&'a K, Time=G::Timestamp>+Clone+'static,
@@ -319,6 +461,282 @@ where
     }
 }
 
+/// Left and right joins, in which records without a match are retained.
+impl<G, K, V, R> Collection<G, (K, V), R>
+where
+    G: Scope,
+    K: ExchangeData+Hashable,
+    V: ExchangeData,
+    R: ExchangeData+Abelian+Multiply<isize, Output=R>,
+    G::Timestamp: Lattice+Ord,
+{
+    /// Joins the collection with another collection, retaining records of `self` that have no match.
+    ///
+    /// Each matching pair of records `(key, val1)` and `(key, val2)` is presented to `logic` as
+    /// `(key, val1, Some(val2))`, and each record `(key, val1)` whose key is absent from `other` is
+    /// presented as `(key, val1, None)`. When an unmatched key gains its first match in `other` the
+    /// `None` outputs for the key are retracted, and they are re-introduced when the last match is removed.
+    /// The keys of `other` are taken with multiplicity zero or one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let x = scope.new_collection_from(vec![(0u32, 1), (1, 3)]).1;
+    ///     let y = scope.new_collection_from(vec![(0u32, 'a')]).1;
+    ///
+    ///     let z = scope.new_collection_from(vec![(1, Some('a')), (3, None)]).1;
+    ///
+    ///     x.left_join(&y, |_key, &a, b| (a, b.cloned()))
+    ///      .assert_eq(&z);
+    /// });
+    /// ```
+    pub fn left_join<V2, D, L>(&self, other: &Collection<G, (K, V2), R>, logic: L) -> Collection<G, D, R>
+    where
+        V2: ExchangeData,
+        R: Multiply<R, Output=R>,
+        D: Data,
+        L: Fn(&K, &V, Option<&V2>)->D+'static,
+    {
+        self.left_join_arranged(&other.arrange_by_key(), logic)
+    }
+
+    /// As `left_join`, but with `other` provided as an arrangement.
+    ///
+    /// The arrangement `self` is formed once and used for both matched and unmatched records, and the
+    /// arrangement `other` is used as provided, for example as imported from another dataflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::ArrangeByKey;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let x = scope.new_collection_from(vec![(0u32, 1), (1, 3)]).1;
+    ///     let y = scope.new_collection_from(vec![(0u32, 'a')]).1
+    ///                  .arrange_by_key();
+    ///
+    ///     let z = scope.new_collection_from(vec![(1, Some('a')), (3, None)]).1;
+    ///
+    ///     x.left_join_arranged(&y, |_key, &a, b| (a, b.cloned()))
+    ///      .assert_eq(&z);
+    /// });
+    /// ```
+    pub fn left_join_arranged<Tr, D, L>(&self, other: &Arranged<G, Tr>, logic: L) -> Collection<G, D, R>
+    where
+        Tr: for<'a> TraceReader<Key<'a>=&'a K, Time=G::Timestamp>+Clone+'static,
+        Tr::Diff: ExchangeData+Semigroup,
+        R: Multiply<Tr::Diff, Output=R>,
+        D: Data,
+        L: Fn(&K, &V, Option<Tr::Val<'_>>)->D+'static,
+    {
+        use std::rc::Rc;
+        use crate::operators::Threshold;
+
+        let logic1 = Rc::new(logic);
+        let logic2 = Rc::clone(&logic1);
+
+        let arranged = self.arrange_by_key();
+        let matched = arranged.join_core(other, move |k, v1, v2| Some(logic1(k, v1, Some(v2))));
+
+        let keys = other.as_collection(|k, _| k.clone()).distinct();
+        let unmatched = arranged.antijoin(&keys).map(move |(k, v1)| logic2(&k, &v1, None));
+
+        matched.concat(&unmatched)
+    }
+
+    /// Joins the collection with another collection, retaining records of `other` that have no match.
+    ///
+    /// This is `left_join` with the roles of the inputs exchanged: each record `(key, val2)` of `other`
+    /// whose key is absent from `self` is presented to `logic` as `(key, None, val2)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let x = scope.new_collection_from(vec![(0u32, 1)]).1;
+    ///     let y = scope.new_collection_from(vec![(0u32, 'a'), (1, 'b')]).1;
+    ///
+    ///     let z = scope.new_collection_from(vec![(Some(1), 'a'), (None, 'b')]).1;
+    ///
+    ///     x.right_join(&y, |_key, a, &b| (a.cloned(), b))
+    ///      .assert_eq(&z);
+    /// });
+    /// ```
+    pub fn right_join<V2, D, L>(&self, other: &Collection<G, (K, V2), R>, logic: L) -> Collection<G, D, R>
+    where
+        V2: ExchangeData,
+        R: Multiply<R, Output=R>,
+        D: Data,
+        L: Fn(&K, Option<&V>, &V2)->D+'static,
+    {
+        self.right_join_arranged(&other.arrange_by_key(), logic)
+    }
+
+    /// As `right_join`, but with `other` provided as an arrangement.
+    ///
+    /// The arrangement `other` is used as provided for both matched and unmatched records, and `self`
+    /// is arranged once. The keys of `self` are taken with multiplicity zero or one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::ArrangeByKey;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let x = scope.new_collection_from(vec![(0u32, 1)]).1;
+    ///     let y = scope.new_collection_from(vec![(0u32, 'a'), (1, 'b')]).1
+    ///                  .arrange_by_key();
+    ///
+    ///     let z = scope.new_collection_from(vec![(Some(1), 'a'), (None, 'b')]).1;
+    ///
+    ///     x.right_join_arranged(&y, |_key, a, &b| (a.cloned(), b))
+    ///      .assert_eq(&z);
+    /// });
+    /// ```
+    pub fn right_join_arranged<Tr, V2, D, L>(&self, other: &Arranged<G, Tr>, logic: L) -> Collection<G, D, R>
+    where
+        Tr: for<'a> TraceReader<Key<'a>=&'a K, Val<'a>=&'a V2, Time=G::Timestamp, Diff=R>+Clone+'static,
+        V2: Data,
+        R: Multiply<R, Output=R>,
+        D: Data,
+        L: Fn(&K, Option<&V>, &V2)->D+'static,
+    {
+        use std::rc::Rc;
+        use crate::operators::Threshold;
+
+        let logic1 = Rc::new(logic);
+        let logic2 = Rc::clone(&logic1);
+
+        let arranged = self.arrange_by_key();
+        let matched = other.join_core(&arranged, move |k, v2, v1| Some(logic1(k, Some(v1), v2)));
+
+        let keys = arranged.as_collection(|k, _| k.clone()).distinct();
+        let unmatched = other.antijoin(&keys).map(move |(k, v2)| logic2(&k, None, &v2));
+
+        matched.concat(&unmatched)
+    }
+}
+
+/// Joins in which one input is broadcast to all workers.
+impl<G, K, V, R> Collection<G, (K, V), R>
+where
+    G: Scope,
+    K: ExchangeData+Hashable,
+    V: ExchangeData,
+    R: ExchangeData+Semigroup,
+    G::Timestamp: Lattice+Ord,
+{
+    /// Joins the collection with a small collection, which is broadcast rather than exchanging `self`.
+    ///
+    /// Each matching pair of records `(key, val1)` and `(key, val2)` is presented to `logic`, as by
+    /// `join_map`. The collection `small` is sent to every worker, and each worker joins its own records
+    /// of `self` against all of `small`, so that the records of `self` never leave their worker. Both
+    /// inputs are arranged by each worker, which maintains their histories so that changes to `small`
+    /// update the results for all matching records of `self`, at whichever times they occur.
+    ///
+    /// Each worker holds an arrangement of all of `small`, which should therefore be small enough to fit
+    /// in the memory of every worker. The arrangements are not exchanged by key, and are unsuitable for
+    /// re-use by operators that expect arrangements produced by `arrange_by_key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let x = scope.new_collection_from(vec![(0u32, 1), (1, 3), (1, 4)]).1;
+    ///     let y = scope.new_collection_from(vec![(1u32, 'a')]).1;
+    ///
+    ///     let z = scope.new_collection_from(vec![(3, 'a'), (4, 'a')]).1;
+    ///
+    ///     x.join_broadcast(&y, |_key, &a, &b| (a, b))
+    ///      .assert_eq(&z);
+    /// });
+    /// ```
+    pub fn join_broadcast<V2, R2, D, L>(&self, small: &Collection<G, (K, V2), R2>, mut logic: L) -> Collection<G, D, <R as Multiply<R2>>::Output>
+    where
+        V2: ExchangeData,
+        R2: ExchangeData+Semigroup,
+        R: Multiply<R2>,
+        <R as Multiply<R2>>::Output: Semigroup+'static,
+        D: Data,
+        L: FnMut(&K, &V, &V2)->D+'static,
+    {
+        use timely::dataflow::operators::Broadcast;
+        use crate::operators::arrange::arrangement::arrange_core;
+        use crate::trace::implementations::{ValBatcher, ValBuilder, ValSpine};
+
+        let large = arrange_core::<_,_,ValBatcher<_,_,_,_>,ValBuilder<_,_,_,_>,ValSpine<K,V,G::Timestamp,R>>(&self.inner, Pipeline, "JoinBroadcastLarge");
+        let small = arrange_core::<_,_,ValBatcher<_,_,_,_>,ValBuilder<_,_,_,_>,ValSpine<K,V2,G::Timestamp,R2>>(&small.inner.broadcast(), Pipeline, "JoinBroadcastSmall");
+        large.join_core(&small, move |k,v1,v2| Some(logic(k,v1,v2)))
+    }
+}
+
+/// Joins on composite keys drawn from indexable records.
+impl<G, D, R> Collection<G, D, R>
+where
+    G: Scope,
+    D: ExchangeData+std::ops::Index<usize>,
+    D::Output: ExchangeData+std::hash::Hash+Sized,
+    R: ExchangeData+Semigroup,
+    G::Timestamp: Lattice+Ord,
+{
+    /// Joins two collections of records on equality of the columns `left_keys` and `right_keys`.
+    ///
+    /// Records of `self` and `other` match when the values in column `left_keys[i]` of the former equal
+    /// those in column `right_keys[i]` of the latter, for each `i`. The records are arranged by the
+    /// composite key of their columns, and each matching pair is presented to `logic`. This removes the
+    /// need to map records to `(key, val)` pairs, for example with the `Vec` records of interpreted plans.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let x = scope.new_collection_from(vec![vec![0, 1, 2], vec![1, 1, 3]]).1;
+    ///     let y = scope.new_collection_from(vec![vec![2, 0, 1], vec![3, 1, 0]]).1;
+    ///
+    ///     let z = scope.new_collection_from(vec![(2, 2)]).1;
+    ///
+    ///     x.join_on(&y, &[0, 1], &[1, 2], |a, b| (a[2], b[0]))
+    ///      .assert_eq(&z);
+    /// });
+    /// ```
+    pub fn join_on<D2, R2, O, L>(&self, other: &Collection<G, D2, R2>, left_keys: &[usize], right_keys: &[usize], mut logic: L) -> Collection<G, O, <R as Multiply<R2>>::Output>
+    where
+        D2: ExchangeData+std::ops::Index<usize, Output=D::Output>,
+        R2: ExchangeData+Semigroup,
+        R: Multiply<R2>,
+        <R as Multiply<R2>>::Output: Semigroup+'static,
+        O: Data,
+        L: FnMut(&D, &D2)->O+'static,
+    {
+        assert_eq!(left_keys.len(), right_keys.len(), "join_on requires the same number of key columns on each side");
+
+        let left_keys = left_keys.to_vec();
+        let right_keys = right_keys.to_vec();
+
+        let left = self.map(move |data| (left_keys.iter().map(|i| data[*i].clone()).collect::<Vec<_>>(), data));
+        let right = other.map(move |data| (right_keys.iter().map(|i| data[*i].clone()).collect::<Vec<_>>(), data));
+
+        left.join_map(&right, move |_key, data1, data2| logic(data1, data2))
+    }
+}
+
 /// The session passed to join closures.
 pub type JoinSession<'a, T, CB, C> = Session<'a, T, EffortBuilder<CB>, Counter<T, C, Tee<T, C>>>;
 
@@ -364,7 +782,19 @@ impl<CB: PushInto<D>, D> PushInto<D> for EffortBuilder<CB> {
 /// The "correctness" of this method depends heavily on the behavior of the supplied `result` function.
 ///
 /// [`AsCollection`]: crate::collection::AsCollection
-pub fn join_traces<G, T1, T2, L, CB>(arranged1: &Arranged<G,T1>, arranged2: &Arranged<G,T2>, mut result: L) -> StreamCore<G, CB::Container>
+pub fn join_traces<G, T1, T2, L, CB>(arranged1: &Arranged<G,T1>, arranged2: &Arranged<G,T2>, result: L) -> StreamCore<G, CB::Container>
+where
+    G: Scope<Timestamp=T1::Time>,
+    T1: TraceReader+Clone+'static,
+    T2: for<'a> TraceReader<Key<'a>=T1::Key<'a>, Time=T1::Time>+Clone+'static,
+    L: FnMut(T1::Key<'_>,T1::Val<'_>,T2::Val<'_>,&G::Timestamp,&T1::Diff,&T2::Diff,&mut JoinSession<T1::Time, CB, CB::Container>)+'static,
+    CB: ContainerBuilder + 'static,
+{
+    join_traces_named("Join", arranged1, arranged2, result)
+}
+
+/// As [`join_traces`], but with a name for the join operator.
+pub fn join_traces_named<G, T1, T2, L, CB>(name: &str, arranged1: &Arranged<G,T1>, arranged2: &Arranged<G,T2>, mut result: L) -> StreamCore<G, CB::Container>
 where
     G: Scope<Timestamp=T1::Time>,
     T1: TraceReader+Clone+'static,
@@ -376,7 +806,7 @@ where
     let mut trace1 = arranged1.trace.clone();
     let mut trace2 = arranged2.trace.clone();
 
-    arranged1.stream.binary_frontier(&arranged2.stream, Pipeline, Pipeline, "Join", move |capability, info| {
+    arranged1.stream.binary_frontier(&arranged2.stream, Pipeline, Pipeline, name, move |capability, info| {
 
         // Acquire an activator to reschedule the operator when it has unfinished work.
         use timely::scheduling::Activator;
@@ -661,6 +1091,9 @@ where
 
         let mut thinker = JoinThinker::new();
 
+        // Keys present in only one cursor are skipped by seeking the other cursor to the next key of the first,
+        // rather than by stepping through the keys of both. The seek is cheap when the sought key is near, and
+        // otherwise jumps past keys of the larger cursor (typically the trace) without visiting them.
         while batch.key_valid(batch_storage) && trace.key_valid(trace_storage) && effort < *fuel {
 
             match trace.key(trace_storage).cmp(&batch.key(batch_storage)) {
@@ -7,7 +7,7 @@
 pub use self::reduce::{Reduce, Threshold, Count};
 pub use self::iterate::Iterate;
 pub use self::join::{Join, JoinCore};
-pub use self::count::CountTotal;
+pub use self::count::{CountTotal, SumTotal};
 pub use self::threshold::ThresholdTotal;
 
 pub mod arrange;
@@ -17,6 +17,7 @@ pub mod iterate;
 pub mod join;
 pub mod count;
 pub mod threshold;
+pub mod scan;
 
 use crate::lattice::Lattice;
 use crate::trace::Cursor;
@@ -49,6 +50,27 @@ impl<'a, C: Cursor> EditList<'a, C> {
             cursor.step_val(storage);
         }
     }
+    /// Loads the contents of a cursor in order of value, until `stop` returns true for a loaded value.
+    ///
+    /// The `stop` function is called with each value that has edits once they are consolidated, and
+    /// those edits. The cursor is left at the value following the last loaded value.
+    fn load_until<L, S>(&mut self, cursor: &mut C, storage: &'a C::Storage, logic: L, mut stop: S)
+    where
+        L: Fn(C::TimeGat<'_>)->C::Time,
+        S: FnMut(C::Val<'a>, &[(C::Time, C::Diff)])->bool,
+    {
+        self.clear();
+        while cursor.val_valid(storage) {
+            let prev = self.edits.len();
+            cursor.map_times(storage, |time1, diff1| self.push(logic(time1), diff1.into_owned()));
+            let value = cursor.val(storage);
+            self.seal(value);
+            cursor.step_val(storage);
+            if self.edits.len() > prev && stop(value, &self.edits[prev..]) {
+                break;
+            }
+        }
+    }
     /// Clears the list of edits.
     #[inline]
     fn clear(&mut self) {
@@ -129,6 +151,34 @@ impl<'storage, C: Cursor> ValueHistory<'storage, C> {
         self.replay()
     }
 
+    /// Loads and replays a specified key, loading values only until `stop` returns true for a value.
+    ///
+    /// Returns the last loaded value if the load stopped before the values of the key were exhausted,
+    /// in which case `cursor` is left at the first value that was not loaded.
+    fn replay_key_until<'history, L, S>(
+        &'history mut self,
+        cursor: &mut C,
+        storage: &'storage C::Storage,
+        key: C::Key<'storage>,
+        logic: L,
+        stop: S,
+    ) -> (HistoryReplay<'storage, 'history, C>, Option<C::Val<'storage>>)
+    where
+        L: Fn(C::TimeGat<'_>)->C::Time,
+        S: FnMut(C::Val<'storage>, &[(C::Time, C::Diff)])->bool,
+    {
+        self.clear();
+        let mut bound = None;
+        cursor.seek_key(storage, key);
+        if cursor.get_key(storage) == Some(key) {
+            self.edits.load_until(cursor, storage, logic, stop);
+            if cursor.val_valid(storage) {
+                bound = self.edits.values.last().map(|x| x.0);
+            }
+        }
+        (self.replay(), bound)
+    }
+
     /// Organizes history based on current contents of edits.
     fn replay<'history>(&'history mut self) -> HistoryReplay<'storage, 'history, C> {
 
@@ -165,6 +215,15 @@ impl<'storage, 'history, C: Cursor> HistoryReplay<'storage, 'history, C> {
         self.replay.history.last().map(|&(ref t, _, v, e)| (self.replay.edits.values[v].0, t, &self.replay.edits.edits[e].1))
     }
 
+    /// The loaded values, in order.
+    fn values(&self) -> impl Iterator<Item = C::Val<'storage>> + '_ {
+        self.replay.edits.values.iter().map(|x| x.0)
+    }
+    /// Indicates whether `value` has any loaded edits.
+    fn contains(&self, value: C::Val<'storage>) -> bool {
+        self.replay.edits.values.binary_search_by(|x| x.0.cmp(&value)).is_ok()
+    }
+
     fn buffer(&self) -> &[((C::Val<'storage>, C::Time), C::Diff)] {
         &self.replay.buffer[..]
     }
@@ -9,7 +9,7 @@ use timely::Container;
 use timely::container::PushInto;
 use crate::hashable::Hashable;
 use crate::{Data, ExchangeData, Collection};
-use crate::difference::{Semigroup, Abelian};
+use crate::difference::{Semigroup, Abelian, Multiply};
 
 use timely::order::PartialOrder;
 use timely::progress::frontier::Antichain;
@@ -42,7 +42,8 @@ pub trait Reduce<G: Scope, K: Data, V: Data, R: Semigroup> where G::Timestamp: L
     ///
     /// The user logic is only invoked for non-empty input collections, and it is safe to assume that the
     /// slice of input values is non-empty. The values are presented in sorted order, as defined by their
-    /// `Ord` implementations.
+    /// `Ord` implementations. Values whose updates accumulate to zero are omitted, and all other values are
+    /// presented with their exact accumulations, including those that are negative.
     ///
     /// # Examples
     ///
@@ -67,6 +68,269 @@ pub trait Reduce<G: Scope, K: Data, V: Data, R: Semigroup> where G::Timestamp: L
     /// As `reduce` with the ability to name the operator.
     fn reduce_named<L, V2: Data, R2: Ord+Abelian+'static>(&self, name: &str, logic: L) -> Collection<G, (K, V2), R2>
     where L: FnMut(&K, &[(&V, R)], &mut Vec<(V2, R2)>)+'static;
+
+    /// As `reduce_named`, but presenting at most the `limit` least values of each key, along with the prior output.
+    ///
+    /// The reduction function receives the key, its least values, the number of its values, and the output
+    /// produced for the key as of the time of evaluation, and populates the changes to that output. The number
+    /// of values is exact if at most `limit`, and otherwise an upper bound. The function is invoked for each key
+    /// at each time it is evaluated, even if its input and output are both empty.
+    ///
+    /// Values are loaded only until `limit` of them are known to be present at each time to evaluate, as described
+    /// for `Arranged::reduce_core_capped`, which bounds the memory used for keys with very many values.
+    fn reduce_capped_named<L, V2: Data, R2: Ord+Abelian+'static>(&self, name: &str, limit: usize, logic: L) -> Collection<G, (K, V2), R2>
+    where L: FnMut(&K, &[(&V, R)], usize, &mut Vec<(V2, R2)>, &mut Vec<(V2, R2)>)+'static;
+
+    /// As `reduce`, but with a limit on the number of distinct values presented for each key.
+    ///
+    /// This is a safety valve against hot or adversarial keys, for reduction logic whose cost grows with
+    /// the number of values. A key with more than `limit` distinct values produces no output, retracting any
+    /// output it previously produced, without the reduction logic being invoked. Keys with at most `limit`
+    /// values are reduced exactly as by `reduce`. The operator's own index of the input still holds all values,
+    /// but only the least `limit + 1` values of a key, and those with recent updates, are gathered to evaluate
+    /// it, as described for `Arranged::reduce_core_capped`.
+    ///
+    /// The `on_overflow` function is called with the key and its number of values when an evaluation of the
+    /// key finds more than `limit` values and the preceding evaluation did not. The number of values is an
+    /// upper bound, as values beyond those gathered are counted by the distinct values with updates, whether
+    /// or not those updates accumulate to zero. The operator remembers the keys that currently overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::Reduce;
+    ///
+    /// ::timely::example(|scope| {
+    ///     // report the smallest value for each group, for groups of at most two values.
+    ///     scope.new_collection_from(1 .. 10).1
+    ///          .map(|x| (x / 4, x))
+    ///          .reduce_with_limit(
+    ///              2,
+    ///              |key, count| println!("key {:?} has {:?} values", key, count),
+    ///              |_key, input, output| output.push((*input[0].0, 1)),
+    ///          )
+    ///          .assert_eq(&scope.new_collection_from(vec![(2, 8)]).1);
+    /// });
+    /// ```
+    fn reduce_with_limit<L, O, V2: Data, R2: Ord+Abelian+'static>(&self, limit: usize, mut on_overflow: O, mut logic: L) -> Collection<G, (K, V2), R2>
+    where
+        L: FnMut(&K, &[(&V, R)], &mut Vec<(V2, R2)>)+'static,
+        O: FnMut(&K, usize)+'static,
+    {
+        // Keys whose most recent evaluation found more than `limit` values.
+        let mut overflowed = std::collections::BTreeSet::new();
+        self.reduce_capped_named("ReduceWithLimit", limit.saturating_add(1), move |key, input, values, output, change| {
+            if input.len() > limit {
+                if overflowed.insert(key.clone()) {
+                    on_overflow(key, values);
+                }
+            }
+            else {
+                overflowed.remove(key);
+                if !input.is_empty() {
+                    logic(key, input, change);
+                }
+            }
+            change.extend(output.drain(..).map(|(x,mut d)| { d.negate(); (x, d) }));
+            crate::consolidation::consolidate(change);
+        })
+    }
+
+    /// As `reduce`, but presenting the values of each key to `logic` one at a time, until `limit` distinct outputs have been produced.
+    ///
+    /// Only the `limit` least values of each key are presented, and they are gathered as described for
+    /// `Arranged::reduce_core_capped`: values past them are loaded only if they have recent updates, and
+    /// so the whole group is not assembled for keys whose values mostly do not change. They are presented in sorted order, each with its accumulated
+    /// difference, and `logic` may push outputs for each. Once outputs with `limit` distinct values have
+    /// been pushed no further values are presented, and outputs with other values are discarded. This suits
+    /// "top-k" reductions, for which each value contributes at least one output. Logic that produces fewer
+    /// distinct outputs than the values it is presented may produce fewer than `limit` outputs for a key.
+    ///
+    /// The output for a key is recomputed from its accumulated values whenever they change, and so when a
+    /// value that contributed to the output is retracted, later values are presented to fill its place.
+    /// Outputs with the same value accumulate, and when outputs with distinct values compete for the last
+    /// places those pushed first are kept; the output is determined by the order of the values and the
+    /// order in which `logic` pushes outputs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::Reduce;
+    ///
+    /// ::timely::example(|scope| {
+    ///     // report the two smallest values for each group.
+    ///     scope.new_collection_from(1 .. 10).1
+    ///          .map(|x| (x / 4, x))
+    ///          .reduce_limited(2, |_key, val, _diff, output| output.push((*val, 1)))
+    ///          .assert_eq(&scope.new_collection_from(vec![(0, 1), (0, 2), (1, 4), (1, 5), (2, 8), (2, 9)]).1);
+    /// });
+    /// ```
+    fn reduce_limited<L, V2: Data, R2: Ord+Abelian+'static>(&self, limit: usize, mut logic: L) -> Collection<G, (K, V2), R2>
+    where L: FnMut(&K, &V, &R, &mut Vec<(V2, R2)>)+'static {
+        let mut buffer = Vec::new();
+        // The distinct output values pushed so far.
+        let mut values = std::collections::BTreeSet::new();
+        self.reduce_capped_named("ReduceLimited", limit, move |key, input, _values, output, change| {
+            for (val, diff) in input.iter() {
+                if values.len() >= limit { break; }
+                logic(key, val, diff, &mut buffer);
+                for (val2, diff2) in buffer.drain(..) {
+                    if !values.contains(&val2) {
+                        if values.len() >= limit { continue; }
+                        values.insert(val2.clone());
+                    }
+                    change.push((val2, diff2));
+                }
+            }
+            values.clear();
+            change.extend(output.drain(..).map(|(x,mut d)| { d.negate(); (x, d) }));
+            crate::consolidation::consolidate(change);
+        })
+    }
+
+    /// As `reduce`, but with a second output for errors detected by the reduction logic.
+    ///
+    /// The reduction function populates two vectors, one with output values and one with errors, and the
+    /// method returns the corresponding collections. Both are maintained by a single reduction, and so when
+    /// the input for a key changes the outputs and errors for its prior state are retracted together, and
+    /// each key's contribution to either collection reflects only its current input. The changes for a key
+    /// are sent to the two collections by one operator with two outputs, under the same capability.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::Reduce;
+    ///
+    /// ::timely::example(|scope| {
+    ///     // report the single value of each group, and an error for groups with several values.
+    ///     let (oks, errs) =
+    ///     scope.new_collection_from(1 .. 10).1
+    ///          .map(|x| (x / 4, x))
+    ///          .reduce_fallible(|_key, input, output, errors| {
+    ///              if input.len() == 1 { output.push((*input[0].0, 1)); }
+    ///              else { errors.push((input.len(), 1)); }
+    ///          });
+    ///
+    ///     oks.assert_eq(&scope.new_collection_from(vec![(2, 8)]).1);
+    ///     errs.assert_eq(&scope.new_collection_from(vec![(0, 3), (1, 4)]).1);
+    /// });
+    /// ```
+    fn reduce_fallible<L, V2: Data, E: Data, R2: Ord+Abelian+'static>(&self, mut logic: L) -> (Collection<G, (K, V2), R2>, Collection<G, (K, E), R2>)
+    where L: FnMut(&K, &[(&V, R)], &mut Vec<(V2, R2)>, &mut Vec<(E, R2)>)+'static {
+
+        use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
+        use crate::collection::AsCollection;
+
+        let mut oks = Vec::new();
+        let mut errs = Vec::new();
+        let results = self.reduce_named("ReduceFallible", move |key, input, output| {
+            logic(key, input, &mut oks, &mut errs);
+            output.extend(oks.drain(..).map(|(val, diff)| (Ok(val), diff)));
+            output.extend(errs.drain(..).map(|(err, diff)| (Err(err), diff)));
+        });
+
+        // Route the outputs and errors of each key to the two outputs, under the capability of their update.
+        let mut builder = OperatorBuilder::new("ReduceFallible Outputs".to_string(), results.scope());
+        let mut input = builder.new_input(&results.inner, Pipeline);
+        let (mut oks_out, oks) = builder.new_output();
+        let (mut errs_out, errs) = builder.new_output();
+
+        builder.build(move |_capabilities| {
+            move |_frontiers| {
+                let mut oks_out = oks_out.activate();
+                let mut errs_out = errs_out.activate();
+                while let Some((capability, updates)) = input.next() {
+                    let mut oks_session = oks_out.session(&capability);
+                    let mut errs_session = errs_out.session(&capability);
+                    for ((key, result), time, diff) in updates.drain(..) {
+                        match result {
+                            Ok(val) => oks_session.give(((key, val), time, diff)),
+                            Err(err) => errs_session.give(((key, err), time, diff)),
+                        }
+                    }
+                }
+            }
+        });
+
+        (oks.as_collection(), errs.as_collection())
+    }
+
+    /// As `reduce`, but emitting at most `max_per_step` output updates each time the operator is scheduled.
+    ///
+    /// When the output for a key changes drastically, `reduce` emits the retractions and insertions all at
+    /// once. This method instead queues the output updates and releases them in order of time, at most
+    /// `max_per_step` per scheduling, rescheduling itself until the queue is empty. Each update keeps its
+    /// time, and so the output is exactly that of `reduce`; the cost is that the output frontier is held
+    /// back until the updates at earlier times have been released.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::Reduce;
+    ///
+    /// ::timely::example(|scope| {
+    ///     // report the values of each group, a few updates at a time.
+    ///     let data = scope.new_collection_from(1 .. 10).1
+    ///                     .map(|x| (x / 3, x));
+    ///
+    ///     data.reduce_smoothed(2, |_key, input, output| {
+    ///             output.extend(input.iter().map(|(v, _)| (**v, 1)));
+    ///         })
+    ///         .assert_eq(&data);
+    /// });
+    /// ```
+    fn reduce_smoothed<L, V2: Data, R2: Ord+Abelian+'static>(&self, max_per_step: usize, logic: L) -> Collection<G, (K, V2), R2>
+    where L: FnMut(&K, &[(&V, R)], &mut Vec<(V2, R2)>)+'static {
+
+        use timely::scheduling::Activator;
+        use crate::collection::AsCollection;
+
+        assert!(max_per_step > 0, "reduce_smoothed requires a positive number of updates per step");
+
+        let reduced = self.reduce_named("ReduceSmoothed", logic);
+        let activations = reduced.scope().activations().clone();
+        reduced
+            .inner
+            .unary(Pipeline, "Smooth", move |_capability, info| {
+
+                let activator = Activator::new(info.address, activations);
+
+                // Queued updates for each capability, in the order received.
+                let mut queued: Vec<(Capability<G::Timestamp>, std::collections::VecDeque<((K, V2), G::Timestamp, R2)>)> = Vec::new();
+
+                move |input, output| {
+                    input.for_each(|time, data| {
+                        if let Some(index) = queued.iter().position(|(cap, _)| cap.time() == time.time()) {
+                            queued[index].1.extend(data.drain(..));
+                        }
+                        else {
+                            queued.push((time.retain(), data.drain(..).collect()));
+                        }
+                    });
+
+                    // Release updates at lesser times first, so that the frontier advances.
+                    queued.sort_by(|x, y| x.0.time().cmp(y.0.time()));
+                    let mut budget = max_per_step;
+                    for (cap, updates) in queued.iter_mut() {
+                        let count = std::cmp::min(budget, updates.len());
+                        if count > 0 {
+                            output.session(cap).give_iterator(updates.drain(..count));
+                            budget -= count;
+                        }
+                    }
+                    queued.retain(|(_, updates)| !updates.is_empty());
+
+                    if !queued.is_empty() {
+                        activator.activate();
+                    }
+                }
+            })
+            .as_collection()
+    }
 }
 
 impl<G, K, V, R> Reduce<G, K, V, R> for Collection<G, (K, V), R>
@@ -82,6 +346,12 @@ impl<G, K, V, R> Reduce<G, K, V, R> for Collection<G, (K, V), R>
         self.arrange_by_key_named(&format!("Arrange: {}", name))
             .reduce_named(name, logic)
     }
+
+    fn reduce_capped_named<L, V2: Data, R2: Ord+Abelian+'static>(&self, name: &str, limit: usize, logic: L) -> Collection<G, (K, V2), R2>
+        where L: FnMut(&K, &[(&V, R)], usize, &mut Vec<(V2, R2)>, &mut Vec<(V2, R2)>)+'static {
+        self.arrange_by_key_named(&format!("Arrange: {}", name))
+            .reduce_capped_named(name, limit, logic)
+    }
 }
 
 impl<G, K: Data, V: Data, T1, R: Ord+Semigroup+'static> Reduce<G, K, V, R> for Arranged<G, T1>
@@ -96,6 +366,128 @@ where
         self.reduce_abelian::<_,K,V2,ValBuilder<_,_,_,_>,ValSpine<_,_,_,_>>(name, logic)
             .as_collection(|k,v| (k.clone(), v.clone()))
     }
+
+    fn reduce_capped_named<L, V2: Data, R2: Ord+Abelian+'static>(&self, name: &str, limit: usize, logic: L) -> Collection<G, (K, V2), R2>
+        where L: FnMut(&K, &[(&V, R)], usize, &mut Vec<(V2, R2)>, &mut Vec<(V2, R2)>)+'static {
+        self.reduce_core_capped::<_,K,V2,ValBuilder<_,_,_,_>,ValSpine<_,_,_,_>>(name, limit, logic)
+            .as_collection(|k,v| (k.clone(), v.clone()))
+    }
+}
+
+impl<G, K, V, R> Collection<G, (K, V), R>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    K: ExchangeData+Hashable,
+    V: ExchangeData,
+    R: ExchangeData+Semigroup,
+{
+    /// Applies a reduction function on records grouped by key, with access to a side arrangement.
+    ///
+    /// The reduction function is as in `Reduce::reduce`, but also receives the value associated with
+    /// the key in `side`, or `None` if there is no such value. If `side` has several values for a key,
+    /// the least is presented. Changes to `side` re-evaluate the reduction for the keys they affect, but
+    /// the reduction is only invoked for keys with values in `self`.
+    ///
+    /// The side values are read from the trace shared by `side`, by a join with the distinct keys of `self`,
+    /// and only the side values of those keys are arranged again alongside the values of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::arrange::ArrangeByKey;
+    ///
+    /// ::timely::example(|scope| {
+    ///
+    ///     let data = scope.new_collection_from(1 .. 10).1.map(|x| (x / 3, x));
+    ///     let limits = scope.new_collection_from(vec![(0, 1), (1, 4)]).1.arrange_by_key();
+    ///
+    ///     // report values no greater than the per-key limit, if it exists.
+    ///     data.reduce_with_side(&limits, |_key, limit, input, output| {
+    ///             for (val, diff) in input.iter() {
+    ///                 if limit.map(|l| *val <= l).unwrap_or(true) {
+    ///                     output.push((**val, *diff));
+    ///                 }
+    ///             }
+    ///         })
+    ///         .assert_eq(&scope.new_collection_from(vec![(0, 1), (1, 3), (1, 4), (2, 6), (2, 7), (2, 8), (3, 9)]).1);
+    /// });
+    /// ```
+    pub fn reduce_with_side<P, Tr, L, V2, R2>(&self, side: &Arranged<G, Tr>, mut logic: L) -> Collection<G, (K, V2), R2>
+    where
+        P: ExchangeData,
+        Tr: for<'a> TraceReader<Key<'a>=&'a K, Val<'a>=&'a P, Time=G::Timestamp, Diff=R>+Clone+'static,
+        R: Multiply<isize, Output = R>,
+        V2: Data,
+        R2: Ord+Abelian+'static,
+        L: FnMut(&K, Option<&P>, &[(&V, R)], &mut Vec<(V2, R2)>)+'static,
+    {
+        // The distinct keys of `self`, which select the side values the reduction needs.
+        let keys = self.map(|(k, _)| k)
+                       .arrange_by_self_named("Arrange: ReduceWithSide Keys")
+                       .reduce_abelian::<_,K,(),KeyBuilder<K,G::Timestamp,isize>,KeySpine<K,G::Timestamp,isize>>("ReduceWithSide Keys", |_,_,output| output.push(((), 1)));
+
+        // Side values are presented as `Ok` and sort before values of `self`, presented as `Err`.
+        let side = side.join_core(&keys, |k, p, _| Some((k.clone(), Ok(p.clone()))));
+        self.map(|(k, v)| (k, Err(v)))
+            .concat(&side)
+            .reduce_named::<_, V2, R2>("ReduceWithSide", move |key, input, output| {
+                let side = input.first().and_then(|(x, _)| x.as_ref().ok());
+                let values = input.iter().filter_map(|(x, r)| x.as_ref().err().map(|v| (v, r.clone()))).collect::<Vec<_>>();
+                if !values.is_empty() {
+                    logic(key, side, &values[..], output);
+                }
+            })
+    }
+
+    /// Applies a reduction function on records grouped by key, which produces changes to its prior output.
+    ///
+    /// The reduction function receives the key, its input values as in `Reduce::reduce`, and the output
+    /// the operator has produced for the key as of the time of evaluation, consolidated. Rather than the
+    /// new output for the key, the function populates the changes to make to that prior output, which
+    /// lets it compute minimal changes itself. The changes are consolidated before they are applied.
+    ///
+    /// The user logic is only invoked for non-empty inputs; when the input for a key becomes empty, all
+    /// of its prior output is retracted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     // report the smallest value for each group, retracting only a replaced minimum.
+    ///     scope.new_collection_from(1 .. 10).1
+    ///          .map(|x| (x / 3, x))
+    ///          .reduce_deltas(|_key, input, prior, deltas| {
+    ///              let least = *input[0].0;
+    ///              if prior.iter().all(|(val, _)| *val != least) {
+    ///                  deltas.extend(prior.iter().map(|(val, diff)| (*val, -diff)));
+    ///                  deltas.push((least, 1));
+    ///              }
+    ///          })
+    ///          .assert_eq(&scope.new_collection_from(vec![(0, 1), (1, 3), (2, 6), (3, 9)]).1);
+    /// });
+    /// ```
+    pub fn reduce_deltas<L, V2, R2>(&self, mut logic: L) -> Collection<G, (K, V2), R2>
+    where
+        V2: Data,
+        R2: Ord+Abelian+'static,
+        L: FnMut(&K, &[(&V, R)], &[(V2, R2)], &mut Vec<(V2, R2)>)+'static,
+    {
+        self.arrange_by_key_named("Arrange: ReduceDeltas")
+            .reduce_core::<_,K,V2,ValBuilder<_,_,_,_>,ValSpine<_,_,_,_>>("ReduceDeltas", move |key, input, output, change| {
+                if !input.is_empty() {
+                    logic(key, input, &output[..], change);
+                }
+                else {
+                    change.extend(output.drain(..).map(|(x,mut d)| { d.negate(); (x, d) }));
+                }
+                crate::consolidation::consolidate(change);
+            })
+            .as_collection(|k,v| (k.clone(), v.clone()))
+    }
 }
 
 /// Extension trait for the `threshold` and `distinct` differential dataflow methods.
@@ -124,6 +516,25 @@ pub trait Threshold<G: Scope, K: Data, R1: Semigroup> where G::Timestamp: Lattic
     }
 
     /// A `threshold` with the ability to name the operator.
+    ///
+    /// The `thresh` function receives both the key and its accumulated multiplicity, and may produce
+    /// a multiplicity of a different type. As the accumulation of a key changes, the previous output
+    /// multiplicity is retracted and the new one asserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::Threshold;
+    ///
+    /// ::timely::example(|scope| {
+    ///     // report one copy of each key per ten occurrences, rounding up.
+    ///     scope.new_collection_from(0 .. 25).1
+    ///          .map(|_| "key".to_string())
+    ///          .threshold_named("Buckets", |_key, count| (*count + 9) / 10)
+    ///          .assert_eq(&scope.new_collection_from(vec!["key".to_string(); 3]).1);
+    /// });
+    /// ```
     fn threshold_named<R2: Ord+Abelian+'static, F: FnMut(&K, &R1)->R2+'static>(&self, name: &str, thresh: F) -> Collection<G, K, R2>;
 
     /// Reduces the collection to one occurrence of each distinct element.
@@ -175,6 +586,155 @@ where
     }
 }
 
+impl<G, K, R> Collection<G, K, R>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    K: ExchangeData+std::hash::Hash,
+    R: ExchangeData+Semigroup,
+{
+    /// Reduces the collection to one occurrence of each distinct element, using hash-ordered arrangements.
+    ///
+    /// The result is identical to that of `Threshold::distinct`, but the input and output are arranged
+    /// using the Robin Hood hashing batches of `trace::implementations::rhh`, which order keys by hash
+    /// rather than by their `Ord` implementation. Whether this is cheaper depends on the cost of comparing
+    /// keys and on the distribution of their hashes; the `distinct` example runs a reachability loop with
+    /// either method, selected by its last argument, to compare them on a particular workload.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::Threshold;
+    ///
+    /// ::timely::example(|scope| {
+    ///     let data = scope.new_collection_from(1 .. 10).1
+    ///                     .map(|x| x / 3);
+    ///
+    ///     data.distinct_hashed()
+    ///         .assert_eq(&data.distinct());
+    /// });
+    /// ```
+    pub fn distinct_hashed(&self) -> Collection<G, K, isize> {
+        use crate::operators::arrange::Arrange;
+        use crate::trace::implementations::rhh::{HashWrapper, VecBatcher, VecBuilder, VecSpine};
+
+        // The hashed layout fills vacant slots with default keys; wrapping keys in `Option` provides
+        // a default without requiring one of `K`.
+        self.map(|key| HashWrapper { inner: Some(key) })
+            .arrange_named::<VecBatcher<_,(),_,_>, VecBuilder<_,(),_,_>, VecSpine<_,(),_,_>>("Arrange: DistinctHashed")
+            .reduce_abelian::<_,_,(),VecBuilder<_,(),_,isize>,VecSpine<_,(),_,isize>>("DistinctHashed", |_,_,output| output.push(((), 1)))
+            .as_collection(|key, _| key.inner.clone().expect("DistinctHashed: vacant key presented"))
+    }
+}
+
+impl<G, K, R> Collection<G, K, R>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord+ExchangeData+std::ops::Add<Output=G::Timestamp>,
+    K: ExchangeData+Hashable,
+    R: ExchangeData+Abelian,
+{
+    /// Reduces each record to the times at which it occurs after an absence of at least `window`.
+    ///
+    /// A record occurs at each time at which it has an update, as long as the accumulated difference of those
+    /// updates is non-zero. An occurrence is reported as the pair of the record and its time, unless it is less
+    /// than `window` after the previous occurrence of the same record, in which case it is suppressed. Each
+    /// occurrence is compared with the one before it, whether or not that one was reported, and so a record that
+    /// recurs more often than `window` is reported only once. The report of an occurrence appears at its time,
+    /// and is not retracted.
+    ///
+    /// Each occurrence is retracted from the operator's state `window` after its time, by a delayed negation, and
+    /// so the state holds only the occurrences of the most recent `window`. An occurrence is reported if it is the
+    /// earliest occurrence of its record in the state at its own time. The method applies to totally ordered
+    /// timestamps for which addition is meaningful, such as `usize` and `Duration`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use timely::dataflow::operators::ToStream;
+    /// use differential_dataflow::AsCollection;
+    ///
+    /// ::timely::example(|scope| {
+    ///     // the occurrence at time 5 is within 10 of that at time 0, and is suppressed.
+    ///     vec![("a", 0u64, 1isize), ("a", 5, 1), ("a", 20, 1)]
+    ///         .to_stream(scope)
+    ///         .as_collection()
+    ///         .distinct_within(10)
+    ///         .assert_eq(&vec![(("a", 0), 0, 1), (("a", 20), 20, 1)].to_stream(scope).as_collection());
+    /// });
+    /// ```
+    pub fn distinct_within(&self, window: G::Timestamp) -> Collection<G, (K, G::Timestamp), isize> {
+
+        use timely::dataflow::operators::Filter;
+        use crate::collection::AsCollection;
+
+        // Occurrences are retracted `window` after their time, so that those present at any time are
+        // exactly the occurrences of the most recent `window`.
+        let occurrences = self.times();
+        let expirations = occurrences.delay(move |time| time.clone() + window.clone()).negate();
+
+        // The earliest occurrence present is reported, but only the changes at the time of the occurrence
+        // itself are kept; later changes reflect only the expiration of earlier occurrences.
+        occurrences
+            .concat(&expirations)
+            .reduce_named("DistinctWithin", |_key, input, output| output.push(((*input[0].0).clone(), 1)))
+            .inner
+            .filter(|((_, occurrence), time, _)| occurrence == time)
+            .as_collection()
+    }
+}
+
+impl<G, K, V> Collection<G, (K, V), isize>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    K: ExchangeData+Hashable,
+    V: ExchangeData,
+{
+    /// Reports for each key the value at the `p`-th percentile of its values, ranked by multiplicity.
+    ///
+    /// The values of each key are ordered, and each value is repeated as many times as its accumulated
+    /// count. The reported value is the one at rank `ceil(p * total)` of this sequence, and at least the
+    /// first, where `total` is the sum of the counts; `p` of `0.0` reports the least value and `1.0` the
+    /// greatest. Negative counts reduce the ranks of the values that follow them, and the reported value
+    /// is the first at which the running sum of counts reaches the rank. Keys whose counts do not sum to
+    /// a positive total report no value. The requirement that `p` lie between `0.0` and `1.0` is asserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     // the values of key zero are 1, 2, 2, 5, and 9.
+    ///     scope.new_collection_from(vec![(0, 1), (0, 2), (0, 2), (0, 5), (0, 9)]).1
+    ///          .percentile(0.5)
+    ///          .assert_eq(&scope.new_collection_from(vec![(0, 2)]).1);
+    /// });
+    /// ```
+    pub fn percentile(&self, p: f64) -> Collection<G, (K, V), isize> {
+        assert!((0.0 ..= 1.0).contains(&p), "percentile requires 0.0 <= p <= 1.0; found {}", p);
+        self.reduce_named("Percentile", move |_key, input, output| {
+            let total: isize = input.iter().map(|(_val, count)| *count).sum();
+            if total > 0 {
+                let rank = std::cmp::max(1, (p * total as f64).ceil() as isize);
+                let mut running = 0;
+                let found = input.iter().find(|(_val, count)| { running += *count; running >= rank });
+                if let Some((val, _count)) = found {
+                    output.push(((*val).clone(), 1));
+                }
+            }
+        })
+    }
+    /// Reports for each key the median of its values, ranked by multiplicity.
+    ///
+    /// This is `percentile(0.5)`, which reports the lesser of the two middle values of an even number of values.
+    pub fn median(&self) -> Collection<G, (K, V), isize> {
+        self.percentile(0.5)
+    }
+}
+
 /// Extension trait for the `count` differential dataflow method.
 pub trait Count<G: Scope, K: Data, R: Semigroup> where G::Timestamp: Lattice+Ord {
     /// Counts the number of occurrences of each element.
@@ -202,6 +762,33 @@ pub trait Count<G: Scope, K: Data, R: Semigroup> where G::Timestamp: Lattice+Ord
     /// type is something other than an `isize` integer, for example perhaps an
     /// `i32`.
     fn count_core<R2: Ord + Abelian + From<i8> + 'static>(&self) -> Collection<G, (K, R), R2>;
+
+    /// Counts the number of distinct values associated with each key, for records that are `(key, val)` pairs.
+    ///
+    /// A value is counted if its accumulated difference is non-zero, and the count of a key changes as
+    /// its values come into and go out of existence. Keys without values are absent from the output.
+    /// The result is that of `distinct` followed by a `count` of the keys, without the intermediate
+    /// arrangement of the distinct collection. Counts are reported with the difference type of the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::Count;
+    ///
+    /// ::timely::example(|scope| {
+    ///     scope.new_collection_from(vec![(0, 'a'), (0, 'b'), (0, 'b'), (1, 'c')]).1
+    ///          .count_distinct()
+    ///          .assert_eq(&scope.new_collection_from(vec![(0, 2), (1, 1)]).1);
+    /// });
+    /// ```
+    fn count_distinct<K1: Data, V1: Data>(&self) -> Collection<G, (K1, isize), R>
+    where
+        Self: Reduce<G, K1, V1, R>,
+        R: Ord + Abelian + From<i8> + 'static,
+    {
+        self.reduce_named("CountDistinct", |_key, input, output| output.push((input.len() as isize, R::from(1i8))))
+    }
 }
 
 impl<G: Scope, K: ExchangeData+Hashable, R: ExchangeData+Semigroup> Count<G, K, R> for Collection<G, K, R>
@@ -324,6 +911,28 @@ where
     Bu: Builder<Time=T2::Time, Output = T2::Batch>,
     Bu::Input: Container + PushInto<((K, V), T2::Time, T2::Diff)>,
     L: FnMut(T1::Key<'_>, &[(T1::Val<'_>, T1::Diff)], &mut Vec<(V,T2::Diff)>, &mut Vec<(V, T2::Diff)>)+'static,
+{
+    reduce_trace_capped::<_,_,Bu,_,_,_,_>(trace, name, None, move |key, input, _values, output, change| logic(key, input, output, change))
+}
+
+/// As `reduce_trace`, but presenting to `logic` at most the `value_limit` least values of each key.
+///
+/// The logic also receives the number of values of the key, which is exact if at most `value_limit`, and
+/// otherwise an upper bound. With a limit, the logic is called for each key at each time it is evaluated,
+/// even if its input and output are both empty. See `Arranged::reduce_core_capped` for how values are loaded.
+pub(crate) fn reduce_trace_capped<G, T1, Bu, T2, K, V, L>(trace: &Arranged<G, T1>, name: &str, value_limit: Option<usize>, mut logic: L) -> Arranged<G, TraceAgent<T2>>
+where
+    G: Scope<Timestamp=T1::Time>,
+    T1: TraceReader + Clone + 'static,
+    for<'a> T1::Key<'a> : IntoOwned<'a, Owned = K>,
+    T2: for<'a> Trace<Key<'a>=T1::Key<'a>, Time=T1::Time> + 'static,
+    K: Ord + 'static,
+    V: Data,
+    for<'a> T2::Val<'a> : IntoOwned<'a, Owned = V>,
+    T2::Batch: Batch,
+    Bu: Builder<Time=T2::Time, Output = T2::Batch>,
+    Bu::Input: Container + PushInto<((K, V), T2::Time, T2::Diff)>,
+    L: FnMut(T1::Key<'_>, &[(T1::Val<'_>, T1::Diff)], usize, &mut Vec<(V,T2::Diff)>, &mut Vec<(V, T2::Diff)>)+'static,
 {
     let mut result_trace = None;
 
@@ -471,7 +1080,7 @@ where
                         let (mut batch_cursor, batch_storage) = (CursorList::new(batch_cursors, &batch_storage), batch_storage);
                         let batch_storage = &batch_storage;
 
-                        let mut thinker = history_replay::HistoryReplayer::new();
+                        let mut thinker = history_replay::HistoryReplayer::new(value_limit);
 
                         // We now march through the keys we must work on, drawing from `batch_cursors` and `exposed`.
                         //
@@ -644,7 +1253,7 @@ where
     V: Clone + Ord,
     for<'b> C2::Val<'b> : IntoOwned<'b, Owned = V>,
 {
-    fn new() -> Self;
+    fn new(value_limit: Option<usize>) -> Self;
     fn compute<L>(
         &mut self,
         key: C1::Key<'a>,
@@ -660,6 +1269,7 @@ where
         L: FnMut(
             C1::Key<'a>,
             &[(C1::Val<'a>, C1::Diff)],
+            usize,
             &mut Vec<(V, C2::Diff)>,
             &mut Vec<(V, C2::Diff)>,
         );
@@ -699,6 +1309,7 @@ mod history_replay {
         meets: Vec<C1::Time>,
         times_current: Vec<C1::Time>,
         temporary: Vec<C1::Time>,
+        value_limit: Option<usize>,
     }
 
     impl<'a, C1, C2, C3, V> PerKeyCompute<'a, C1, C2, C3, V> for HistoryReplayer<'a, C1, C2, C3, V>
@@ -709,7 +1320,7 @@ mod history_replay {
         V: Clone + Ord,
         for<'b> C2::Val<'b> : IntoOwned<'b, Owned = V>,
     {
-        fn new() -> Self {
+        fn new(value_limit: Option<usize>) -> Self {
             HistoryReplayer {
                 input_history: ValueHistory::new(),
                 output_history: ValueHistory::new(),
@@ -722,6 +1333,7 @@ mod history_replay {
                 meets: Vec::new(),
                 times_current: Vec::new(),
                 temporary: Vec::new(),
+                value_limit,
             }
         }
         #[inline(never)]
@@ -740,6 +1352,7 @@ mod history_replay {
             L: FnMut(
                 C1::Key<'a>,
                 &[(C1::Val<'a>, C1::Diff)],
+                usize,
                 &mut Vec<(V, C2::Diff)>,
                 &mut Vec<(V, C2::Diff)>,
             )
@@ -789,15 +1402,51 @@ mod history_replay {
             // and guaranteed to accumulate identically for times greater or equal to `meet`.
 
             // Load the input and output histories.
-            let mut input_replay = if let Some(meet) = meet.as_ref() {
-                self.input_history.replay_key(source_cursor, source_storage, key, |time| {
-                    let mut time = time.into_owned();
-                    time.join_assign(meet);
-                    time
-                })
-            }
-            else {
-                self.input_history.replay_key(source_cursor, source_storage, key, |time| time.into_owned())
+            //
+            // With a value limit, input values are loaded in order only until `limit` values are found that
+            // are present at every time we will evaluate: their updates all collapse to `meet` and they have
+            // no updates in `batch`. The least `limit` values at each evaluated time are then among those
+            // loaded, and later values are counted but not loaded. `input_bound` records the last loaded
+            // value if some were not loaded, and `unloaded` counts the distinct values past it.
+            let mut input_bound = None;
+            let mut unloaded = 0;
+            let mut input_replay = match (meet.as_ref(), self.value_limit) {
+                (Some(meet), Some(limit)) => {
+                    let mut settled = 0;
+                    let (replay, bound) = self.input_history.replay_key_until(source_cursor, source_storage, key, |time| {
+                        let mut time = time.into_owned();
+                        time.join_assign(meet);
+                        time
+                    }, |value, edits| {
+                        if edits.len() == 1 && &edits[0].0 == meet && !batch_replay.contains(value) {
+                            settled += 1;
+                        }
+                        settled >= limit
+                    });
+                    if let Some(bound) = bound {
+                        let mut batch_values = batch_replay.values().filter(|value| value > &bound).peekable();
+                        while source_cursor.val_valid(source_storage) {
+                            let value = source_cursor.val(source_storage);
+                            while batch_values.next_if(|other| other < &value).is_some() { unloaded += 1; }
+                            batch_values.next_if(|other| other == &value);
+                            unloaded += 1;
+                            source_cursor.step_val(source_storage);
+                        }
+                        unloaded += batch_values.count();
+                    }
+                    input_bound = bound;
+                    replay
+                },
+                (Some(meet), None) => {
+                    self.input_history.replay_key(source_cursor, source_storage, key, |time| {
+                        let mut time = time.into_owned();
+                        time.join_assign(meet);
+                        time
+                    })
+                },
+                (None, _) => {
+                    self.input_history.replay_key(source_cursor, source_storage, key, |time| time.into_owned())
+                },
             };
             let mut output_replay = if let Some(meet) = meet.as_ref() {
                 self.output_history.replay_key(output_cursor, output_storage, key, |time| {
@@ -901,13 +1550,20 @@ mod history_replay {
                         }
                         for &((value, ref time), ref diff) in batch_replay.buffer().iter() {
                             if time.less_equal(&next_time) {
-                                self.input_buffer.push((value, diff.clone()));
+                                // Values past those loaded from the input are counted in `unloaded` instead.
+                                if input_bound.map(|bound| value <= bound).unwrap_or(true) {
+                                    self.input_buffer.push((value, diff.clone()));
+                                }
                             }
                             else {
                                 self.temporary.push(next_time.join(time));
                             }
                         }
                         crate::consolidation::consolidate(&mut self.input_buffer);
+                        let values = self.input_buffer.len() + unloaded;
+                        if let Some(limit) = self.value_limit {
+                            self.input_buffer.truncate(limit);
+                        }
 
                         meet.as_ref().map(|meet| output_replay.advance_buffer_by(meet));
                         for &((value, ref time), ref diff) in output_replay.buffer().iter() {
@@ -929,8 +1585,9 @@ mod history_replay {
                         crate::consolidation::consolidate(&mut self.output_buffer);
 
                         // Apply user logic if non-empty input and see what happens!
-                        if !self.input_buffer.is_empty() || !self.output_buffer.is_empty() {
-                            logic(key, &self.input_buffer[..], &mut self.output_buffer, &mut self.update_buffer);
+                        // With a value limit, the logic is also shown keys whose input and output are empty.
+                        if !self.input_buffer.is_empty() || !self.output_buffer.is_empty() || self.value_limit.is_some() {
+                            logic(key, &self.input_buffer[..], values, &mut self.output_buffer, &mut self.update_buffer);
                             self.input_buffer.clear();
                             self.output_buffer.clear();
                         }
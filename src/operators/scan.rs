@@ -0,0 +1,125 @@
+//! Running folds of the values of each key, for totally ordered times.
+//!
+//! The `scan` operator maintains a state for each key, which it updates with the changes to the values
+//! of the key in time order, and reports the state of each key as a collection.
+
+use std::collections::BTreeMap;
+
+use timely::order::TotalOrder;
+use timely::dataflow::*;
+use timely::dataflow::operators::{CapabilitySet, Operator};
+use timely::dataflow::channels::pact::Exchange;
+
+use crate::lattice::Lattice;
+use crate::{Data, ExchangeData, Collection, AsCollection};
+use crate::difference::Semigroup;
+use crate::hashable::Hashable;
+use crate::consolidation::consolidate;
+
+impl<G, K, V, R> Collection<G, (K, V), R>
+where
+    G: Scope,
+    G::Timestamp: TotalOrder+Lattice+Ord,
+    K: ExchangeData+Hashable,
+    V: ExchangeData,
+    R: ExchangeData+Semigroup,
+{
+    /// Maintains a running fold of the changes to the values of each key, reporting the state of each key.
+    ///
+    /// Each key's state starts from `init`, and once all updates at a time are known, `logic` is applied to the
+    /// state and to each of the key's changed values at that time, with its accumulated difference. Times are
+    /// visited in order, and the changes at a time in the order of their values. The output collection contains
+    /// one record `(key, state)` for each key whose input is non-empty, reflecting all updates up to that time;
+    /// when a key's state changes its previous state is retracted. When a key's input becomes empty its state is
+    /// retracted and discarded, and should the key receive updates again its state starts anew from `init`.
+    ///
+    /// The input changes are folded as they are; a retraction of a value is presented to `logic` with a negative
+    /// difference, and it is up to `logic` to undo the value's contribution, if that is the intent. Because times
+    /// are totally ordered, the updates at a time are folded only once all of them are known, and the folded state
+    /// is never revisited. The operator keeps the current state of each key, and the accumulated values of each
+    /// key's input to determine when it becomes empty, but not the history of its input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    ///
+    /// ::timely::example(|scope| {
+    ///     // report the running sum of the values of each key.
+    ///     scope.new_collection_from(1 .. 10u64).1
+    ///          .map(|x| (x % 3, x))
+    ///          .scan(0, |sum, val, diff| *sum += *val as isize * *diff)
+    ///          .assert_eq(&scope.new_collection_from(vec![(0, 18), (1, 12), (2, 15)]).1);
+    /// });
+    /// ```
+    pub fn scan<S, L>(&self, init: S, mut logic: L) -> Collection<G, (K, S), isize>
+    where
+        S: Data,
+        L: FnMut(&mut S, &V, &R)+'static,
+    {
+        let exchange = Exchange::new(move |update: &((K,V),G::Timestamp,R)| (update.0).0.hashed().into());
+        self.inner
+            .unary_frontier(exchange, "Scan", move |_,_| {
+
+                // Capabilities for the times of pending updates.
+                let mut capabilities = CapabilitySet::new();
+                // Updates not yet folded, as their times may not be complete, grouped by time.
+                let mut pending = BTreeMap::<G::Timestamp, Vec<((K, V), R)>>::new();
+                // The current state of each key with a non-empty input, and the accumulated values of that input.
+                let mut states = BTreeMap::<K, (S, Vec<(V, R)>)>::new();
+
+                move |input, output| {
+
+                    input.for_each(|capability, data| {
+                        capabilities.insert(capability.retain());
+                        for ((key, val), time, diff) in data.drain(..) {
+                            pending.entry(time).or_insert_with(Vec::new).push(((key, val), diff));
+                        }
+                    });
+
+                    // Fold the updates at complete times, in order of time, key, and value.
+                    let frontier = input.frontier().frontier();
+                    while let Some(entry) = pending.first_entry() {
+                        if frontier.less_equal(entry.key()) {
+                            break;
+                        }
+                        let (time, mut ready) = entry.remove_entry();
+                        consolidate(&mut ready);
+
+                        let capability = capabilities.delayed(&time);
+                        let mut session = output.session(&capability);
+                        let mut index = 0;
+                        while index < ready.len() {
+                            let key = ready[index].0.0.clone();
+                            let (state, values) = states.entry(key.clone()).or_insert_with(|| (init.clone(), Vec::new()));
+                            let prior = if values.is_empty() { None } else { Some(state.clone()) };
+                            while index < ready.len() && ready[index].0.0 == key {
+                                let ((_, ref val), ref diff) = ready[index];
+                                logic(state, val, diff);
+                                values.push((val.clone(), diff.clone()));
+                                index += 1;
+                            }
+                            consolidate(values);
+                            let current = if values.is_empty() { None } else { Some(state.clone()) };
+                            if prior != current {
+                                if let Some(prior) = prior {
+                                    session.give(((key.clone(), prior), time.clone(), -1));
+                                }
+                                if let Some(current) = current {
+                                    session.give(((key.clone(), current), time.clone(), 1));
+                                }
+                            }
+                            // Keys whose input is empty are removed, and start again from `init`.
+                            if values.is_empty() {
+                                states.remove(&key);
+                            }
+                        }
+                    }
+
+                    // Retain capabilities only for the times of pending updates.
+                    capabilities.downgrade(pending.keys().next());
+                }
+            })
+            .as_collection()
+    }
+}
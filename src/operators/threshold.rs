@@ -10,11 +10,12 @@ use timely::dataflow::channels::pact::Pipeline;
 
 use crate::lattice::Lattice;
 use crate::{ExchangeData, Collection};
-use crate::difference::{Semigroup, Abelian};
+use crate::difference::{Semigroup, Monoid, Abelian};
 use crate::hashable::Hashable;
 use crate::collection::AsCollection;
 use crate::operators::arrange::{Arranged, ArrangeBySelf};
 use crate::trace::{BatchReader, Cursor, TraceReader};
+use crate::trace::implementations::{KeySpine, KeyBuilder};
 
 /// Extension trait for the `distinct` differential dataflow method.
 pub trait ThresholdTotal<G: Scope, K: ExchangeData, R: ExchangeData+Semigroup> where G::Timestamp: TotalOrder+Lattice+Ord {
@@ -24,7 +25,12 @@ pub trait ThresholdTotal<G: Scope, K: ExchangeData, R: ExchangeData+Semigroup> w
         R2: Semigroup+'static,
         F: FnMut(&K,&R,Option<&R>)->Option<R2>+'static,
         ;
-    /// Reduces the collection to one occurrence of each distinct element.
+    /// Transforms the accumulated weight of each record by `thresh`.
+    ///
+    /// The output weight of each record is `thresh` applied to its accumulated input weight, except that a
+    /// record whose accumulated weight is zero has zero output weight, and `thresh` is not called for it.
+    /// Unlike `Threshold::threshold`, then, `thresh` is not obliged to map zero to zero, and records whose
+    /// accumulated weight is zero are never reported.
     ///
     /// # Examples
     ///
@@ -40,12 +46,13 @@ pub trait ThresholdTotal<G: Scope, K: ExchangeData, R: ExchangeData+Semigroup> w
     /// });
     /// ```
     fn threshold_total<R2: Abelian+'static, F: FnMut(&K,&R)->R2+'static>(&self, mut thresh: F) -> Collection<G, K, R2> {
+        // As with `threshold`, `thresh` is only applied to non-zero accumulations.
         self.threshold_semigroup(move |key, new, old| {
-            let mut new = thresh(key, new);
-            if let Some(old) = old { 
+            let mut new = if new.is_zero() { R2::zero() } else { thresh(key, new) };
+            if let Some(old) = old.filter(|old| !old.is_zero()) {
                 let mut add = thresh(key, old);
                 add.negate();
-                new.plus_equals(&add); 
+                new.plus_equals(&add);
             }
             if !new.is_zero() { Some(new) } else { None }
         })
@@ -56,6 +63,11 @@ pub trait ThresholdTotal<G: Scope, K: ExchangeData, R: ExchangeData+Semigroup> w
     /// ignores its specific value. To take more general actions based on the accumulated weight, consider
     /// the `threshold` method.
     ///
+    /// The result is identical to that of `Threshold::distinct`, but relies on the times being totally
+    /// ordered. Rather than maintain an arrangement of its output and reconcile it with the input at each
+    /// interesting time, the operator accumulates each changed record's prior weight and applies changes
+    /// in time order, producing output from the change in weight alone.
+    ///
     /// # Examples
     ///
     /// ```
@@ -82,6 +94,46 @@ pub trait ThresholdTotal<G: Scope, K: ExchangeData, R: ExchangeData+Semigroup> w
         self.threshold_total(|_,_| R2::from(1i8))
     }
 
+    /// Reports each record while its accumulated weight is high, with hysteresis.
+    ///
+    /// A record that is not reported becomes reported at the first time its accumulated weight is strictly
+    /// greater than `high`, and a reported record stops being reported at the first time its accumulated
+    /// weight is strictly less than `low`. Weights between the two thresholds, inclusive, leave the record
+    /// as it was, so that a weight bouncing between them does not cause the output to flap. Initially no
+    /// record is reported, as if its weight were zero. The requirement `low <= high` is asserted.
+    ///
+    /// Whether a record is reported depends on the history of its weights, and not only on its current
+    /// weight. The implementations for collections and arrangements read it from the operator's own
+    /// output, which they arrange in addition to the input, as of each time at which the record's weight
+    /// changes; both arrangements are compacted as usual. The default implementation, for other types,
+    /// instead builds on `threshold_semigroup` and keeps the set of reported records on each worker.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::ThresholdTotal;
+    ///
+    /// ::timely::example(|scope| {
+    ///     // report the keys with more than two occurrences.
+    ///     scope.new_collection_from(1 .. 10).1
+    ///          .map(|x| x / 4)
+    ///          .threshold_hysteresis(2, 1)
+    ///          .assert_eq(&scope.new_collection_from(vec![0, 1]).1);
+    /// });
+    /// ```
+    fn threshold_hysteresis(&self, high: R, low: R) -> Collection<G, K, isize>
+    where R: Ord+Monoid {
+        assert!(low <= high, "threshold_hysteresis requires low <= high");
+        // `threshold_semigroup` presents the weights of each record in time order.
+        let mut reported = std::collections::BTreeSet::new();
+        self.threshold_semigroup(move |key, count, _old| {
+            if reported.contains(key) {
+                if *count < low { reported.remove(key); Some(-1) } else { None }
+            }
+            else if *count > high { reported.insert(key.clone()); Some(1) } else { None }
+        })
+    }
 }
 
 impl<G: Scope, K: ExchangeData+Hashable, R: ExchangeData+Semigroup> ThresholdTotal<G, K, R> for Collection<G, K, R>
@@ -94,6 +146,12 @@ where G::Timestamp: TotalOrder+Lattice+Ord {
         self.arrange_by_self_named("Arrange: ThresholdTotal")
             .threshold_semigroup(thresh)
     }
+
+    fn threshold_hysteresis(&self, high: R, low: R) -> Collection<G, K, isize>
+    where R: Ord+Monoid {
+        self.arrange_by_self_named("Arrange: ThresholdHysteresis")
+            .threshold_hysteresis(high, low)
+    }
 }
 
 impl<G, K, T1> ThresholdTotal<G, K, T1::Diff> for Arranged<G, T1>
@@ -202,4 +260,19 @@ where
         })
         .as_collection()
     }
+
+    fn threshold_hysteresis(&self, high: T1::Diff, low: T1::Diff) -> Collection<G, K, isize>
+    where T1::Diff: Ord+Monoid {
+        assert!(low <= high, "threshold_hysteresis requires low <= high");
+        let zero = T1::Diff::zero();
+        self.reduce_core::<_,K,(),KeyBuilder<K,G::Timestamp,isize>,KeySpine<K,G::Timestamp,isize>>("ThresholdHysteresis", move |_key, input, output, change| {
+            // `output` holds the prior report of the record, if any, as of the time of evaluation.
+            let count = input.first().map(|(_, count)| count).unwrap_or(&zero);
+            if !output.is_empty() {
+                if *count < low { change.push(((), -1)); }
+            }
+            else if *count > high { change.push(((), 1)); }
+        })
+        .as_collection(|key, _| key.clone())
+    }
 }
@@ -0,0 +1,60 @@
+//! A cursor restricted to the updates visible at a query frontier.
+
+use timely::order::PartialOrder;
+use timely::progress::{Antichain, Timestamp};
+
+use super::{Cursor, IntoOwned};
+
+/// Presents only the updates at times less or equal to an element of a query frontier.
+///
+/// The accumulated updates for each `(key, val)` pair are its accumulation as of the frontier. Keys and
+/// values are navigated as in the wrapped cursor, and may have no times to present.
+pub struct CursorAt<C: Cursor> {
+    cursor: C,
+    frontier: Antichain<C::Time>,
+}
+
+impl<C: Cursor> CursorAt<C> {
+    /// Restricts `cursor` to the updates at times less or equal to an element of `frontier`.
+    pub fn new(cursor: C, frontier: Antichain<C::Time>) -> Self {
+        CursorAt { cursor, frontier }
+    }
+}
+
+impl<C: Cursor> Cursor for CursorAt<C> {
+    type Key<'a> = C::Key<'a>;
+    type Val<'a> = C::Val<'a>;
+    type Time = C::Time;
+    type TimeGat<'a> = C::TimeGat<'a>;
+    type Diff = C::Diff;
+    type DiffGat<'a> = C::DiffGat<'a>;
+
+    type Storage = C::Storage;
+
+    #[inline] fn key_valid(&self, storage: &Self::Storage) -> bool { self.cursor.key_valid(storage) }
+    #[inline] fn val_valid(&self, storage: &Self::Storage) -> bool { self.cursor.val_valid(storage) }
+
+    #[inline] fn key<'a>(&self, storage: &'a Self::Storage) -> Self::Key<'a> { self.cursor.key(storage) }
+    #[inline] fn val<'a>(&self, storage: &'a Self::Storage) -> Self::Val<'a> { self.cursor.val(storage) }
+
+    #[inline]
+    fn map_times<L: FnMut(Self::TimeGat<'_>, Self::DiffGat<'_>)>(&mut self, storage: &Self::Storage, mut logic: L) {
+        let frontier = &self.frontier;
+        let mut temp: C::Time = <C::Time as Timestamp>::minimum();
+        self.cursor.map_times(storage, |time, diff| {
+            time.clone_onto(&mut temp);
+            if frontier.elements().iter().any(|query| temp.less_equal(query)) {
+                logic(time, diff);
+            }
+        })
+    }
+
+    #[inline] fn step_key(&mut self, storage: &Self::Storage) { self.cursor.step_key(storage) }
+    #[inline] fn seek_key(&mut self, storage: &Self::Storage, key: Self::Key<'_>) { self.cursor.seek_key(storage, key) }
+
+    #[inline] fn step_val(&mut self, storage: &Self::Storage) { self.cursor.step_val(storage) }
+    #[inline] fn seek_val(&mut self, storage: &Self::Storage, val: Self::Val<'_>) { self.cursor.seek_val(storage, val) }
+
+    #[inline] fn rewind_keys(&mut self, storage: &Self::Storage) { self.cursor.rewind_keys(storage) }
+    #[inline] fn rewind_vals(&mut self, storage: &Self::Storage) { self.cursor.rewind_vals(storage) }
+}
@@ -0,0 +1,111 @@
+//! A cursor adapter visiting the keys common to two cursors.
+
+use super::Cursor;
+
+/// Advances two cursors with a common key type in lockstep, visiting only the keys present in both.
+///
+/// The two cursors may have different value, time, and difference types. Once positioned at a key
+/// common to both, their values may be traversed through `cursors`, or all pairs of values visited
+/// with `map_val_pairs`. This is the navigation performed by `join_core`, for operators that require
+/// more control over the paired values than its closure provides.
+#[derive(Debug)]
+pub struct CursorJoin<C1, C2> {
+    cursor1: C1,
+    cursor2: C2,
+}
+
+impl<C1, C2> CursorJoin<C1, C2>
+where
+    C1: Cursor,
+    C2: for<'a> Cursor<Key<'a> = C1::Key<'a>>,
+{
+    /// Creates a cursor over the keys common to `cursor1` and `cursor2`, positioned at the first.
+    pub fn new(cursor1: C1, cursor2: C2, storage1: &C1::Storage, storage2: &C2::Storage) -> Self {
+        let mut result = CursorJoin { cursor1, cursor2 };
+        result.align_keys(storage1, storage2);
+        result
+    }
+
+    /// Advances whichever cursor has the lesser key, until the keys are equal or a cursor is exhausted.
+    fn align_keys<'s>(&mut self, storage1: &'s C1::Storage, storage2: &'s C2::Storage) {
+        while self.cursor1.key_valid(storage1) && self.cursor2.key_valid(storage2) {
+            let key1 = self.cursor1.key(storage1);
+            let key2 = self.cursor2.key(storage2);
+            match key1.cmp(&key2) {
+                std::cmp::Ordering::Less => self.cursor1.seek_key(storage1, key2),
+                std::cmp::Ordering::Greater => self.cursor2.seek_key(storage2, key1),
+                std::cmp::Ordering::Equal => return,
+            }
+        }
+    }
+
+    /// Indicates if the current key is valid, which is the case while both cursors have a key.
+    ///
+    /// A value of `false` indicates that the cursor has exhausted the keys common to both cursors.
+    pub fn key_valid(&self, storage1: &C1::Storage, storage2: &C2::Storage) -> bool {
+        self.cursor1.key_valid(storage1) && self.cursor2.key_valid(storage2)
+    }
+    /// A reference to the current key. Asserts if invalid.
+    pub fn key<'a>(&self, storage1: &'a C1::Storage) -> C1::Key<'a> {
+        self.cursor1.key(storage1)
+    }
+    /// Returns a reference to the current key, if valid.
+    pub fn get_key<'a>(&self, storage1: &'a C1::Storage, storage2: &C2::Storage) -> Option<C1::Key<'a>> {
+        if self.key_valid(storage1, storage2) { Some(self.key(storage1)) } else { None }
+    }
+
+    /// Advances the cursor to the next common key.
+    pub fn step_key(&mut self, storage1: &C1::Storage, storage2: &C2::Storage) {
+        if self.key_valid(storage1, storage2) {
+            self.cursor1.step_key(storage1);
+            self.cursor2.step_key(storage2);
+            self.align_keys(storage1, storage2);
+        }
+    }
+    /// Advances the cursor to the first common key greater or equal to `key`.
+    pub fn seek_key(&mut self, storage1: &C1::Storage, storage2: &C2::Storage, key: C1::Key<'_>) {
+        self.cursor1.seek_key(storage1, key);
+        self.cursor2.seek_key(storage2, key);
+        self.align_keys(storage1, storage2);
+    }
+    /// Rewinds the cursor to the first common key.
+    pub fn rewind_keys(&mut self, storage1: &C1::Storage, storage2: &C2::Storage) {
+        self.cursor1.rewind_keys(storage1);
+        self.cursor2.rewind_keys(storage2);
+        self.align_keys(storage1, storage2);
+    }
+
+    /// The two cursors, for traversal of the values of the current key.
+    ///
+    /// The cursors' keys should not be moved, other than through the methods of `self`.
+    pub fn cursors(&mut self) -> (&mut C1, &mut C2) {
+        (&mut self.cursor1, &mut self.cursor2)
+    }
+
+    /// Applies `logic` to each pair of values of the current key, in the order of their first and then second values.
+    ///
+    /// Both cursors are left at the first value of the key. Does nothing if the current key is invalid.
+    pub fn map_val_pairs<L>(&mut self, storage1: &C1::Storage, storage2: &C2::Storage, mut logic: L)
+    where
+        L: FnMut(C1::Val<'_>, C2::Val<'_>),
+    {
+        if self.key_valid(storage1, storage2) {
+            self.cursor1.rewind_vals(storage1);
+            while let Some(val1) = self.cursor1.get_val(storage1) {
+                self.cursor2.rewind_vals(storage2);
+                while let Some(val2) = self.cursor2.get_val(storage2) {
+                    logic(val1, val2);
+                    self.cursor2.step_val(storage2);
+                }
+                self.cursor1.step_val(storage1);
+            }
+            self.cursor1.rewind_vals(storage1);
+            self.cursor2.rewind_vals(storage2);
+        }
+    }
+
+    /// Unwraps the two cursors.
+    pub fn into_inner(self) -> (C1, C2) {
+        (self.cursor1, self.cursor2)
+    }
+}
@@ -168,4 +168,16 @@ impl<C: Cursor> Cursor for CursorList<C> {
         }
         self.minimize_vals(storage);
     }
+
+    // Sums the counts of the cursors at the current key, so that a value present in several
+    // cursors is counted once for each, rather than stepping through the merged values.
+    #[inline]
+    fn count_vals(&mut self, storage: &Vec<C::Storage>) -> usize {
+        let mut count = 0;
+        for &index in self.min_key.iter() {
+            count += self.cursors[index].count_vals(&storage[index]);
+        }
+        self.minimize_vals(storage);
+        count
+    }
 }
@@ -10,8 +10,12 @@ use crate::difference::Semigroup;
 use crate::lattice::Lattice;
 
 pub mod cursor_list;
+pub mod cursor_at;
+pub mod cursor_join;
 
 pub use self::cursor_list::CursorList;
+pub use self::cursor_at::CursorAt;
+pub use self::cursor_join::CursorJoin;
 
 pub use timely::container::flatcontainer::IntoOwned;
 
@@ -76,6 +80,25 @@ pub trait Cursor {
     /// Rewinds the cursor to the first value for current key.
     fn rewind_vals(&mut self, storage: &Self::Storage);
 
+    /// Counts the values for the current key, leaving the cursor at the first value for the key.
+    ///
+    /// The default implementation rewinds and steps through the values. Cursors that can determine
+    /// the number of values more directly, for example from offsets, should override this method.
+    /// Cursors that merge several sources of updates, such as `CursorList`, may count a value once for
+    /// each source that contains it, and then report an upper bound on the number of distinct values.
+    /// Returns zero if the cursor does not reference a valid key.
+    fn count_vals(&mut self, storage: &Self::Storage) -> usize {
+        if !self.key_valid(storage) { return 0; }
+        self.rewind_vals(storage);
+        let mut count = 0;
+        while self.val_valid(storage) {
+            count += 1;
+            self.step_val(storage);
+        }
+        self.rewind_vals(storage);
+        count
+    }
+
     /// Rewinds the cursor and outputs its contents to a Vec
     fn to_vec<K, V>(&mut self, storage: &Self::Storage) -> Vec<((K, V), Vec<(Self::Time, Self::Diff)>)>
     where 
@@ -0,0 +1,134 @@
+//! Trace and batch implementations for append-only collections.
+//!
+//! Collections whose records are only ever inserted (for example, event logs) accumulate to
+//! non-negative multiplicities, and nearly all of their updates have a difference of exactly one.
+//! The types in this module reuse the ordered batches of `ord_neu`, but with a layout whose
+//! differences are implicit: a difference of one is not stored at all, and only the occasional
+//! larger multiplicity (from repeated insertions, or from compaction) is recorded to the side.
+//!
+//! Negative differences cannot be represented, and building a batch that contains one panics.
+//!
+//! # Examples
+//!
+//! ```
+//! use differential_dataflow::input::Input;
+//! use differential_dataflow::operators::Join;
+//! use differential_dataflow::operators::arrange::Arrange;
+//! use differential_dataflow::trace::implementations::append_only::{AppendOnlyBatcher, AppendOnlyBuilder, AppendOnlySpine};
+//!
+//! ::timely::example(|scope| {
+//!
+//!     let events = scope.new_collection_from(vec![(1, 'a'), (2, 'b'), (1, 'c'), (1, 'c')]).1;
+//!     let names = scope.new_collection_from(vec![(1, "one"), (2, "two")]).1;
+//!
+//!     let events_arr = events.arrange::<AppendOnlyBatcher<_,_,_>, AppendOnlyBuilder<_,_,_>, AppendOnlySpine<_,_,_>>();
+//!     let names_arr = names.arrange::<AppendOnlyBatcher<_,_,_>, AppendOnlyBuilder<_,_,_>, AppendOnlySpine<_,_,_>>();
+//!
+//!     events_arr
+//!         .join_core(&names_arr, |key, event, name| Some((*key, *event, *name)))
+//!         .assert_eq(&events.join(&names).map(|(key, (event, name))| (key, event, name)));
+//! });
+//! ```
+
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use timely::container::PushInto;
+
+use crate::trace::implementations::chunker::VecChunker;
+use crate::trace::implementations::merge_batcher::{MergeBatcher, VecMerger};
+use crate::trace::implementations::ord_neu::{OrdKeyBatch, OrdKeyBuilder, OrdValBatch, OrdValBuilder};
+use crate::trace::implementations::spine_fueled::Spine;
+use crate::trace::rc_blanket_impls::RcBuilder;
+
+use super::{BatchContainer, Layout, OffsetList, Update};
+
+/// A trace implementation for append-only collections, using a spine of ordered lists.
+pub type AppendOnlySpine<K, V, T> = Spine<Rc<OrdValBatch<AppendOnly<((K,V),T,isize)>>>>;
+/// A batcher for append-only collections.
+pub type AppendOnlyBatcher<K, V, T> = MergeBatcher<Vec<((K,V),T,isize)>, VecChunker<((K,V),T,isize)>, VecMerger<(K, V), T, isize>>;
+/// A builder for append-only collections, which panics on negative differences.
+pub type AppendOnlyBuilder<K, V, T> = RcBuilder<OrdValBuilder<AppendOnly<((K,V),T,isize)>, Vec<((K,V),T,isize)>>>;
+
+/// A trace implementation for append-only collections of keys, using a spine of ordered lists.
+pub type AppendOnlyKeySpine<K, T> = Spine<Rc<OrdKeyBatch<AppendOnly<((K,()),T,isize)>>>>;
+/// A batcher for append-only collections of keys.
+pub type AppendOnlyKeyBatcher<K, T> = MergeBatcher<Vec<((K,()),T,isize)>, VecChunker<((K,()),T,isize)>, VecMerger<(K, ()), T, isize>>;
+/// A builder for append-only collections of keys, which panics on negative differences.
+pub type AppendOnlyKeyBuilder<K, T> = RcBuilder<OrdKeyBuilder<AppendOnly<((K,()),T,isize)>, Vec<((K,()),T,isize)>>>;
+
+/// A layout based on vectors, whose differences are implicitly positive.
+pub struct AppendOnly<U: Update<Diff = isize>> {
+    phantom: std::marker::PhantomData<U>,
+}
+
+impl<U: Update<Diff = isize>> Layout for AppendOnly<U> {
+    type Target = U;
+    type KeyContainer = Vec<U::Key>;
+    type ValContainer = Vec<U::Val>;
+    type TimeContainer = Vec<U::Time>;
+    type DiffContainer = ImplicitDiffs;
+    type OffsetContainer = OffsetList;
+}
+
+/// A container of strictly positive differences, which does not store differences equal to one.
+///
+/// Pushing a difference that is not strictly positive panics, as the container (and any batch
+/// built on it) only represents collections that have never experienced retractions.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ImplicitDiffs {
+    /// The number of differences in the container.
+    len: usize,
+    /// Positions and values of differences other than one, in increasing order of position.
+    others: Vec<(usize, isize)>,
+}
+
+impl ImplicitDiffs {
+    /// Records `diff` as the next difference, asserting that it is positive.
+    fn push_diff(&mut self, diff: isize) {
+        assert!(diff > 0, "append-only trace received non-positive difference {}; retractions are not supported", diff);
+        if diff != 1 {
+            self.others.push((self.len, diff));
+        }
+        self.len += 1;
+    }
+}
+
+impl PushInto<isize> for ImplicitDiffs {
+    fn push_into(&mut self, item: isize) {
+        self.push_diff(item);
+    }
+}
+
+impl<'a> PushInto<&'a isize> for ImplicitDiffs {
+    fn push_into(&mut self, item: &'a isize) {
+        self.push_diff(*item);
+    }
+}
+
+impl BatchContainer for ImplicitDiffs {
+    type Owned = isize;
+    type ReadItem<'a> = &'a isize;
+
+    fn reborrow<'b, 'a: 'b>(item: Self::ReadItem<'a>) -> Self::ReadItem<'b> { item }
+
+    fn with_capacity(_size: usize) -> Self {
+        Self::default()
+    }
+    fn merge_capacity(cont1: &Self, cont2: &Self) -> Self {
+        Self {
+            len: 0,
+            others: Vec::with_capacity(cont1.others.len() + cont2.others.len()),
+        }
+    }
+    fn index(&self, index: usize) -> Self::ReadItem<'_> {
+        assert!(index < self.len);
+        match self.others.binary_search_by_key(&index, |(position, _)| *position) {
+            Ok(found) => &self.others[found].1,
+            Err(_) => &1,
+        }
+    }
+    fn len(&self) -> usize {
+        self.len
+    }
+}
@@ -1,5 +1,11 @@
 //! A slice container that Huffman encodes its contents.
+//!
+//! A container starts out holding its slices raw, and merged containers are Huffman encoded. The
+//! code is learned from the symbol frequencies of the first pair of raw containers merged, and it
+//! is then reused by the merges that descend from them. Symbols absent from the code are encoded
+//! with an escape code, and recorded separately in the order they occur.
 
+use std::rc::Rc;
 use std::collections::BTreeMap;
 use timely::container::PushInto;
 
@@ -12,11 +18,19 @@ use self::huffman::Huffman;
 /// A container that contains slices `[B]` as items.
 pub struct HuffmanContainer<B: Ord+Clone> {
     /// Either encoded data or raw data.
-    inner: Result<(Huffman<B>, Vec<u8>), Vec<B>>,
+    inner: Result<(Rc<Huffman<B>>, Vec<u8>), Vec<B>>,
     /// Offsets that bound each contained slice.
     ///
     /// The length will be one greater than the number of contained items.
     offsets: OffsetList,
+    /// Offsets that bound the symbols of each contained slice.
+    ///
+    /// Encoded slices may end with a partial byte, and the count of symbols bounds their decoding.
+    symbols: OffsetList,
+    /// Symbols absent from the Huffman code, in the order they were pushed.
+    escapes: Vec<B>,
+    /// Offsets that bound the escaped symbols of each contained slice.
+    escape_offs: OffsetList,
     /// Counts of the number of each pattern we've seen.
     stats: BTreeMap<B, i64>
 }
@@ -28,55 +42,84 @@ where
     /// Prints statistics about encoded containers.
     pub fn print(&self) {
         if let Ok((_huff, bytes)) = &self.inner {
-            println!("Bytes: {:?}, Symbols: {:?}", bytes.len(), self.stats.values().sum::<i64>());
+            println!("Bytes: {:?}, Symbols: {:?}, Escapes: {:?}", bytes.len(), self.stats.values().sum::<i64>(), self.escapes.len());
         }
     }
-}
-
-impl<B: Ord + Clone + 'static> PushInto<Vec<B>> for HuffmanContainer<B> {
-    fn push_into(&mut self, item: Vec<B>) {
-        for x in item.iter() { *self.stats.entry(x.clone()).or_insert(0) += 1; }
+    /// True if the contents are Huffman encoded, rather than held raw.
+    pub fn is_encoded(&self) -> bool {
+        self.inner.is_ok()
+    }
+    /// The number of symbols encoded with the escape code, as they are absent from the Huffman code.
+    pub fn escaped(&self) -> usize {
+        self.escapes.len()
+    }
+    /// An empty container, with the supplied inner storage and room for `size` slices.
+    fn with_inner(inner: Result<(Rc<Huffman<B>>, Vec<u8>), Vec<B>>, size: usize) -> Self {
+        let mut offsets = OffsetList::with_capacity(size + 1);
+        offsets.push(0);
+        let mut symbols = OffsetList::with_capacity(size + 1);
+        symbols.push(0);
+        let mut escape_offs = OffsetList::with_capacity(size + 1);
+        escape_offs.push(0);
+        Self {
+            inner,
+            offsets,
+            symbols,
+            escapes: Vec::new(),
+            escape_offs,
+            stats: Default::default(),
+        }
+    }
+    /// Appends the sequence of `symbols` as a contained slice.
+    fn push_symbols<'a, I>(&mut self, symbols: I)
+    where
+        I: Iterator<Item=&'a B> + Clone,
+        B: 'a,
+    {
+        let mut count = 0;
+        for x in symbols.clone() {
+            *self.stats.entry(x.clone()).or_insert(0) += 1;
+            count += 1;
+        }
         match &mut self.inner {
             Ok((huffman, bytes)) => {
-                bytes.extend(huffman.encode(item.iter()));
+                self.escapes.extend(symbols.clone().filter(|x| !huffman.encodes(x)).cloned());
+                bytes.extend(huffman.encode(symbols));
                 self.offsets.push(bytes.len());
             },
-            Err(raw) => { 
-                raw.extend(item); 
+            Err(raw) => {
+                raw.extend(symbols.cloned());
                 self.offsets.push(raw.len());
             }
         }
+        self.symbols.push(self.symbols.index(self.symbols.len() - 1) + count);
+        self.escape_offs.push(self.escapes.len());
+    }
+}
+
+impl<B: Ord + Clone + 'static> PushInto<Vec<B>> for HuffmanContainer<B> {
+    fn push_into(&mut self, item: Vec<B>) {
+        self.push_symbols(item.iter());
+    }
+}
+
+impl<B: Ord + Clone + 'static> PushInto<&Vec<B>> for HuffmanContainer<B> {
+    fn push_into(&mut self, item: &Vec<B>) {
+        self.push_symbols(item.iter());
+    }
+}
+
+impl<B: Ord + Clone + 'static> PushInto<&[B]> for HuffmanContainer<B> {
+    fn push_into(&mut self, item: &[B]) {
+        self.push_symbols(item.iter());
     }
 }
 
 impl<'a, B: Ord + Clone + 'static> PushInto<Wrapped<'a, B>> for HuffmanContainer<B> {
     fn push_into(&mut self, item: Wrapped<'a, B>) {
         match item.decode() {
-            Ok(decoded) => {
-                for x in decoded { *self.stats.entry(x.clone()).or_insert(0) += 1; }
-
-            },
-            Err(symbols) => {
-                for x in symbols.iter() { *self.stats.entry(x.clone()).or_insert(0) += 1; }
-            }
-        }
-        match (item.decode(), &mut self.inner) {
-            (Ok(decoded), Ok((huffman, bytes))) => {
-                bytes.extend(huffman.encode(decoded));
-                self.offsets.push(bytes.len());
-            }
-            (Ok(decoded), Err(raw)) => {
-                raw.extend(decoded.cloned());
-                self.offsets.push(raw.len());
-            }
-            (Err(symbols), Ok((huffman, bytes))) => {
-                bytes.extend(huffman.encode(symbols.iter()));
-                self.offsets.push(bytes.len());
-            }
-            (Err(symbols), Err(raw)) => {
-                raw.extend(symbols.iter().cloned());
-                self.offsets.push(raw.len());
-            }
+            Ok(decoded) => self.push_symbols(decoded),
+            Err(symbols) => self.push_symbols(symbols.iter()),
         }
     }
 }
@@ -88,46 +131,40 @@ impl<B: Ord + Clone + 'static> BatchContainer for HuffmanContainer<B> {
     fn reborrow<'b, 'a: 'b>(item: Self::ReadItem<'a>) -> Self::ReadItem<'b> { item }
 
     fn with_capacity(size: usize) -> Self {
-        let mut offsets = OffsetList::with_capacity(size + 1);
-        offsets.push(0);
-        Self {
-            inner: Err(Vec::with_capacity(size)),
-            offsets,
-            stats: Default::default(),
-        }
+        Self::with_inner(Err(Vec::with_capacity(size)), size)
     }
     fn merge_capacity(cont1: &Self, cont2: &Self) -> Self {
 
-        if cont1.len() > 0 { cont1.print(); }
-        if cont2.len() > 0 { cont2.print(); }
+        let length = cont1.len() + cont2.len();
+        let symbols = cont1.symbols.index(cont1.len()) + cont2.symbols.index(cont2.len());
 
-        let mut counts = BTreeMap::default();
-        for (symbol, count) in cont1.stats.iter() {
-            *counts.entry(symbol.clone()).or_insert(0) += count;
-        }
-        for (symbol, count) in cont2.stats.iter() {
-            *counts.entry(symbol.clone()).or_insert(0) += count;
-        }
-
-        let bytes = Vec::with_capacity(counts.values().cloned().sum::<i64>() as usize);
-        let huffman = Huffman::create_from(counts);
-        let inner = Ok((huffman, bytes));
-        // : Err(Vec::with_capacity(length))
+        // Reuse an existing code if there is one, and otherwise learn one from the raw contents.
+        let huffman = match (&cont1.inner, &cont2.inner) {
+            (Ok((huffman, _)), _) | (_, Ok((huffman, _))) => Some(Rc::clone(huffman)),
+            _ => {
+                let mut counts = BTreeMap::default();
+                for (symbol, count) in cont1.stats.iter().chain(cont2.stats.iter()) {
+                    *counts.entry(symbol.clone()).or_insert(0) += count;
+                }
+                if counts.is_empty() { None } else { Some(Rc::new(Huffman::create_from(counts))) }
+            }
+        };
 
-        let length = cont1.offsets.len() + cont2.offsets.len() - 2;
-        let mut offsets = OffsetList::with_capacity(length + 1);
-        offsets.push(0);
-        Self {
-            inner,
-            offsets,
-            stats: Default::default(),
-        }
+        let inner = match huffman {
+            Some(huffman) => Ok((huffman, Vec::with_capacity(symbols))),
+            None => Err(Vec::with_capacity(symbols)),
+        };
+        Self::with_inner(inner, length)
     }
     fn index(&self, index: usize) -> Self::ReadItem<'_> {
         let lower = self.offsets.index(index);
         let upper = self.offsets.index(index+1);
         match &self.inner {
-            Ok((huffman, bytes)) => Wrapped::encoded(Encoded::new(huffman, &bytes[lower .. upper])),
+            Ok((huffman, bytes)) => {
+                let symbols = self.symbols.index(index+1) - self.symbols.index(index);
+                let escapes = &self.escapes[self.escape_offs.index(index) .. self.escape_offs.index(index+1)];
+                Wrapped::encoded(Encoded::new(huffman, &bytes[lower .. upper], symbols, escapes))
+            },
             Err(raw) => Wrapped::decoded(&raw[lower .. upper]),
         }
     }
@@ -138,13 +175,7 @@ impl<B: Ord + Clone + 'static> BatchContainer for HuffmanContainer<B> {
 /// Default implementation introduces a first offset.
 impl<B: Ord+Clone> Default for HuffmanContainer<B> {
     fn default() -> Self {
-        let mut offsets = OffsetList::with_capacity(1);
-        offsets.push(0);
-        Self {
-            inner: Err(Vec::new()),
-            offsets,
-            stats: Default::default(),
-        }
+        Self::with_inner(Err(Vec::new()), 0)
     }
 }
 
@@ -159,7 +190,7 @@ mod wrapper {
 
     impl<'a, B: Ord> Wrapped<'a, B> {
         /// Returns either a decoding iterator, or just the bytes themselves.
-        pub fn decode(&'a self) -> Result<impl Iterator<Item=&'a B> + 'a, &'a [B]> {
+        pub fn decode(&'a self) -> Result<impl Iterator<Item=&'a B> + Clone + 'a, &'a [B]> {
             match &self.inner {
                 Ok(encoded) => Ok(encoded.decode()),
                 Err(symbols) => Err(symbols),
@@ -187,6 +218,14 @@ mod wrapper {
             }
         }
     }
+    impl<'a, 'b, B: Ord> PartialEq<&'a Vec<B>> for Wrapped<'b, B> {
+        fn eq(&self, other: &&'a Vec<B>) -> bool {
+            match self.decode() {
+                Ok(decode) => decode.eq(other.iter()),
+                Err(bytes) => bytes.eq(&other[..]),
+            }
+        }
+    }
     impl<'a, B: Ord> Eq for Wrapped<'a, B> { }
     impl<'a, 'b, B: Ord> PartialOrd<Wrapped<'a, B>> for Wrapped<'b, B> {
         fn partial_cmp(&self, other: &Wrapped<'a, B>) -> Option<Ordering> {
@@ -235,15 +274,23 @@ mod encoded {
         huffman: &'a Huffman<B>,
         /// The data itself.
         bytes: &'a [u8],
+        /// The number of encoded symbols, as the last byte may be partial.
+        symbols: usize,
+        /// Symbols absent from the code, in the order their escape codes occur.
+        escapes: &'a [B],
     }
 
     impl<'a, B: Ord> Encoded<'a, B> {
         /// Returns either a decoding iterator, or just the bytes themselves.
-        pub fn decode(&'a self) -> impl Iterator<Item=&'a B> + 'a {
-            self.huffman.decode(self.bytes.iter().cloned())
+        pub fn decode(&'a self) -> impl Iterator<Item=&'a B> + Clone + 'a {
+            let mut escapes = self.escapes.iter();
+            self.huffman
+                .decode(self.bytes.iter().cloned())
+                .take(self.symbols)
+                .map(move |symbol| symbol.unwrap_or_else(|| escapes.next().expect("escaped symbol not found")))
         }
-        pub fn new(huffman: &'a Huffman<B>, bytes: &'a [u8]) -> Self {
-            Self { huffman, bytes }
+        pub fn new(huffman: &'a Huffman<B>, bytes: &'a [u8], symbols: usize, escapes: &'a [B]) -> Self {
+            Self { huffman, bytes, symbols, escapes }
         }
     }
 
@@ -267,6 +314,8 @@ mod huffman {
         /// An entry `(bits, code)` indicates that the low `bits` of `code` should be blatted down.
         /// Probably every `code` fits in a `u64`, unless there are crazy frequencies?
         encode: BTreeMap<T, (usize, u64)>,
+        /// The description of the escape code, used for symbols absent from `encode`.
+        escape: Option<(usize, u64)>,
         /// Byte-by-byte decoder.
         decode: [Decode<T>; 256],
     }
@@ -275,15 +324,24 @@ mod huffman {
         /// Encodes the provided symbols as a sequence of bytes.
         ///
         /// The last byte may only contain partial information, but it should be recorded as presented,
-        /// as we haven't a way to distinguish (e.g. a `Result` return type).
-        pub fn encode<'a, I>(&'a self, symbols: I) -> Encoder<'a, T, I::IntoIter>
+        /// as we haven't a way to distinguish (e.g. a `Result` return type). Symbols absent from the
+        /// code are encoded with the escape code, and must be recorded separately.
+        pub fn encode<'a, 'b, I>(&'a self, symbols: I) -> Encoder<'a, T, I::IntoIter>
         where
-            I: IntoIterator<Item = &'a T>,
+            I: IntoIterator<Item = &'b T>,
+            T: 'b,
         {
-            Encoder::new(&self.encode, symbols.into_iter())
+            Encoder::new(&self.encode, self.escape, symbols.into_iter())
+        }
+
+        /// True if `symbol` has a code, rather than requiring the escape code.
+        pub fn encodes(&self, symbol: &T) -> bool {
+            self.encode.contains_key(symbol)
         }
 
-        /// Decodes the provided bytes as a sequence of symbols.
+        /// Decodes the provided bytes as a sequence of symbols, where `None` indicates the escape code.
+        ///
+        /// The final byte may be partial, and the decoder may produce symbols from its padding.
         pub fn decode<I>(&self, bytes: I) -> Decoder<'_, T, I::IntoIter> 
         where
             I: IntoIterator<Item=u8>
@@ -296,13 +354,16 @@ mod huffman {
             if counts.is_empty() {
                 return Self {
                     encode: Default::default(),
+                    escape: None,
                     decode: Decode::map(),
                 };
             }
 
+            // The escape code is the least frequent symbol, represented by `None`.
             let mut heap = std::collections::BinaryHeap::new();
+            heap.push((0, Node::Leaf(None)));
             for (item, count) in counts {
-                heap.push((-count, Node::Leaf(item)));
+                heap.push((-count, Node::Leaf(Some(item))));
             }
             let mut tree = Vec::with_capacity(2 * heap.len() - 1);
             while heap.len() > 1 {
@@ -330,13 +391,17 @@ mod huffman {
             let mut code: u64 = 0;
             let mut prev_level = 0;
             let mut encode = BTreeMap::new();
+            let mut escape = None;
             let mut decode = Decode::map();
             for (level, sym) in levels {
                 if prev_level != level {
                     code <<= level - prev_level;
                     prev_level = level;
                 }
-                encode.insert(sym.clone(), (level, code));
+                match sym {
+                    Some(sym) => { encode.insert(sym.clone(), (level, code)); },
+                    None => { escape = Some((level, code)); },
+                }
                 Self::insert_decode(&mut decode, sym, level, code << (64-level));
 
                 code += 1;
@@ -350,12 +415,13 @@ mod huffman {
 
             Huffman { 
                 encode,
+                escape,
                 decode,
             }
         }
 
         /// Inserts a symbol, and 
-        fn insert_decode(map: &mut [Decode<T>; 256], symbol: &T, bits: usize, code: u64) where T: Clone {
+        fn insert_decode(map: &mut [Decode<T>; 256], symbol: &Option<T>, bits: usize, code: u64) where T: Clone {
             let byte: u8 = (code >> 56).try_into().unwrap();
             if bits <= 8 {
                 for off in 0 .. (1 << (8 - bits)) {
@@ -385,8 +451,8 @@ mod huffman {
         /// An as-yet unfilled slot.
         #[default]
         Void,
-        /// The symbol, and the number of bits consumed.
-        Symbol(T, usize),
+        /// The symbol, or `None` for the escape code, and the number of bits consumed.
+        Symbol(Option<T>, usize),
         /// An additional map to push subsequent bytes at.
         Further(Box<[Decode<T>; 256]>),
     }
@@ -420,7 +486,6 @@ mod huffman {
 
         use super::Decode;
 
-        #[derive(Copy, Clone)]
         pub struct Decoder<'a, T, I> {
             decode: &'a [Decode<T>; 256],
             bytes: I,
@@ -428,6 +493,17 @@ mod huffman {
             pending_bits: usize,
         }
 
+        impl<'a, T, I: Clone> Clone for Decoder<'a, T, I> {
+            fn clone(&self) -> Self {
+                Self {
+                    decode: self.decode,
+                    bytes: self.bytes.clone(),
+                    pending_byte: self.pending_byte,
+                    pending_bits: self.pending_bits,
+                }
+            }
+        }
+
         impl<'a, T, I> Decoder<'a, T, I> {
             pub fn new(decode: &'a [Decode<T>; 256], bytes: I) -> Self {
                 Self {
@@ -443,10 +519,10 @@ mod huffman {
         where
             I: Iterator<Item=u8>,
         {
-            type Item = &'a T;
-            fn next(&mut self) -> Option<&'a T> {
+            type Item = Option<&'a T>;
+            fn next(&mut self) -> Option<Option<&'a T>> {
                 // We must navigate `self.decode`, restocking bits whenever possible.
-                // We stop if ever there are not enough bits remaining.
+                // Once the bytes are exhausted, remaining bits are padded with zeros.
                 let mut map = self.decode;
                 loop {
                     if self.pending_bits < 8 {
@@ -454,6 +530,10 @@ mod huffman {
                             self.pending_byte = (self.pending_byte << 8) + next_byte as u16;
                             self.pending_bits += 8;
                         }
+                        else if self.pending_bits > 0 {
+                            self.pending_byte <<= 8 - self.pending_bits;
+                            self.pending_bits = 8;
+                        }
                         else {
                             return None;
                         }
@@ -464,7 +544,7 @@ mod huffman {
                         Decode::Symbol(s, bits) => {
                             self.pending_bits -= bits;
                             self.pending_byte &= (1 << self.pending_bits) - 1;
-                            return Some(s);
+                            return Some(s.as_ref());
                         }
                         Decode::Further(next_map) => {
                             self.pending_bits -= 8;
@@ -485,15 +565,17 @@ mod huffman {
         #[derive(Copy, Clone)]
         pub struct Encoder<'a, T, I> {
             encode: &'a BTreeMap<T, (usize, u64)>,
+            escape: Option<(usize, u64)>,
             symbols: I,
             pending_byte: u64,
             pending_bits: usize,
         }
 
         impl<'a, T, I> Encoder<'a, T, I> {
-            pub fn new(encode: &'a BTreeMap<T, (usize, u64)>, symbols: I) -> Self {
+            pub fn new(encode: &'a BTreeMap<T, (usize, u64)>, escape: Option<(usize, u64)>, symbols: I) -> Self {
                 Self {
                     encode,
+                    escape,
                     symbols,
                     pending_byte: 0,
                     pending_bits: 0,
@@ -501,16 +583,16 @@ mod huffman {
             }
         }
 
-        impl<'a, T: Ord, I> Iterator for Encoder<'a, T, I>
+        impl<'a, 'b, T: Ord + 'b, I> Iterator for Encoder<'a, T, I>
         where
-            I: Iterator<Item=&'a T>,
+            I: Iterator<Item=&'b T>,
         {
             type Item = u8;
             fn next(&mut self) -> Option<u8> {
                 // We repeatedly ship bytes out of `self.pending_byte`, restocking from `self.symbols`.
                 while self.pending_bits < 8 {
                     if let Some(symbol) = self.symbols.next() {
-                        let (bits, code) = self.encode.get(symbol).unwrap();
+                        let (bits, code) = self.encode.get(symbol).or(self.escape.as_ref()).expect("symbol absent from code");
                         self.pending_byte <<= bits;
                         self.pending_byte += code;
                         self.pending_bits += bits;
@@ -17,7 +17,7 @@ use timely::progress::{frontier::Antichain, Timestamp};
 use timely::Container;
 use timely::container::{ContainerBuilder, PushInto};
 
-use crate::logging::{BatcherEvent, Logger};
+use crate::logging::{BatcherEvent, HoldingPenEvent, Logger};
 use crate::trace::{Batcher, Builder, Description};
 
 /// Creates batches from containers of unordered tuples.
@@ -31,6 +31,12 @@ pub struct MergeBatcher<Input, C, M: Merger> {
     ///
     /// Do not push/pop directly but use the corresponding functions ([`Self::chain_push`]/[`Self::chain_pop`]).
     chains: Vec<Vec<M::Chunk>>,
+    /// Chains of updates held back by previous calls to seal, each with a lower bound on its times.
+    ///
+    /// Buckets are ordered by decreasing length, and sealing only merges in buckets whose lower bound
+    /// is not beyond the sealed `upper`, which leaves updates far in the future untouched until they
+    /// may be extracted. Do not push/pop directly but use the corresponding functions.
+    buckets: Vec<(Antichain<M::Time>, Vec<M::Chunk>)>,
     /// Stash of empty chunks, recycled through the merging process.
     stash: Vec<M::Chunk>,
     /// Merges consolidated chunks, and extracts the subset of an update chain that lies in an interval of time.
@@ -39,6 +45,10 @@ pub struct MergeBatcher<Input, C, M: Merger> {
     lower: Antichain<M::Time>,
     /// The lower-bound frontier of the data, after the last call to seal.
     frontier: Antichain<M::Time>,
+    /// An optional bound on the number of chunks merged by each call to `push_container`.
+    ///
+    /// Merges that would exceed the bound are deferred, at the latest until the next call to `seal`.
+    fuel: Option<usize>,
     /// Logger for size accounting.
     logger: Option<Logger>,
     /// Timely operator ID.
@@ -64,9 +74,11 @@ where
             chunker: C::default(),
             merger: M::default(),
             chains: Vec::new(),
+            buckets: Vec::new(),
             stash: Vec::new(),
             frontier: Antichain::new(),
             lower: Antichain::from_elem(M::Time::minimum()),
+            fuel: None,
             _marker: PhantomData,
         }
     }
@@ -75,10 +87,19 @@ where
     /// needed.
     fn push_container(&mut self, container: &mut Input) {
         self.chunker.push_into(container);
+        // The bound applies to the call as a whole, rather than to each extracted chunk.
+        let mut fuel = self.fuel;
         while let Some(chunk) = self.chunker.extract() {
             let chunk = std::mem::take(chunk);
-            self.insert_chain(vec![chunk]);
+            if let Some(fuel) = fuel.as_mut() {
+                self.chain_push(vec![chunk]);
+                self.merge_fueled(fuel);
+            }
+            else {
+                self.insert_chain(vec![chunk]);
+            }
         }
+        self.log_holding_pen();
     }
 
     // Sealing a batch means finding those updates with times not greater or equal to any time
@@ -92,6 +113,17 @@ where
             self.insert_chain(vec![chunk]);
         }
 
+        // Complete merges deferred for lack of fuel, re-establishing geometrically sized chains.
+        if self.fuel.is_some() {
+            let mut chains = Vec::with_capacity(self.chains.len());
+            while let Some(chain) = self.chain_pop() {
+                chains.push(chain);
+            }
+            for chain in chains.into_iter().rev() {
+                self.insert_chain(chain);
+            }
+        }
+
         // Merge all remaining chains into a single chain.
         while self.chains.len() > 1 {
             let list1 = self.chain_pop().unwrap();
@@ -99,20 +131,41 @@ where
             let merged = self.merge_by(list1, list2);
             self.chain_push(merged);
         }
-        let merged = self.chain_pop().unwrap_or_default();
+        let mut merged = self.chain_pop().unwrap_or_default();
+
+        // Merge in held back updates, from buckets that may contain times not beyond `upper`.
+        let mut index = 0;
+        while index < self.buckets.len() {
+            if self.buckets[index].0.elements().iter().all(|time| upper.less_equal(time)) {
+                index += 1;
+            }
+            else {
+                let (_, bucket) = self.bucket_remove(index);
+                merged = self.merge_by(merged, bucket);
+            }
+        }
 
         // Extract readied data.
         let mut kept = Vec::new();
         let mut readied = Vec::new();
-        self.frontier.clear();
+        let mut kept_lower = Antichain::new();
 
-        self.merger.extract(merged, upper.borrow(), &mut self.frontier, &mut readied, &mut kept, &mut self.stash);
+        self.merger.extract(merged, upper.borrow(), &mut kept_lower, &mut readied, &mut kept, &mut self.stash);
 
         if !kept.is_empty() {
-            self.chain_push(kept);
+            self.insert_bucket(kept_lower, kept);
+        }
+
+        // Updates remain only in buckets, whose lower bounds jointly bound the remaining updates.
+        self.frontier.clear();
+        for (bucket_lower, _) in self.buckets.iter() {
+            for time in bucket_lower.elements() {
+                self.frontier.insert(time.clone());
+            }
         }
 
         self.stash.clear();
+        self.log_holding_pen();
 
         let description = Description::new(self.lower.clone(), upper.clone(), Antichain::from_elem(M::Time::minimum()));
         let seal = B::seal(&mut readied, description);
@@ -125,12 +178,40 @@ where
     fn frontier(&mut self) -> AntichainRef<M::Time> {
         self.frontier.borrow()
     }
+
+    /// Bounds the number of chunks merged by each call to `push_container`, or removes the bound if `None`.
+    ///
+    /// By default, pushing a container may merge chains of any size, which occasionally takes time proportional
+    /// to all updates held by the batcher. With a bound, merges that would exceed it are deferred, and the
+    /// batcher may hold more and smaller chains than it otherwise would. Deferred merges are completed by
+    /// `seal`, whose results are unaffected by the bound.
+    fn set_fuel(&mut self, fuel: Option<usize>) {
+        self.fuel = fuel;
+    }
 }
 
 impl<Input, C, M> MergeBatcher<Input, C, M>
 where
     M: Merger,
 {
+    /// Merges the most recent chains while they are not geometrically sized, until merging the next two
+    /// chains would exceed the remaining `fuel`, which is reduced by the number of chunks merged.
+    ///
+    /// Work is measured in chunks, the same unit in which chain lengths are compared.
+    fn merge_fueled(&mut self, fuel: &mut usize) {
+        while self.chains.len() > 1 && (self.chains[self.chains.len() - 1].len() >= self.chains[self.chains.len() - 2].len() / 2) {
+            let work = self.chains[self.chains.len() - 2 ..].iter().map(|chain| chain.len()).sum::<usize>();
+            if work > *fuel {
+                break;
+            }
+            *fuel -= work;
+            let list1 = self.chain_pop().unwrap();
+            let list2 = self.chain_pop().unwrap();
+            let merged = self.merge_by(list1, list2);
+            self.chain_push(merged);
+        }
+    }
+
     /// Insert a chain and maintain chain properties: Chains are geometrically sized and ordered
     /// by decreasing length.
     fn insert_chain(&mut self, chain: Vec<M::Chunk>) {
@@ -145,6 +226,25 @@ where
         }
     }
 
+    /// Insert a bucket of held back updates, and merge buckets to keep them geometrically sized.
+    ///
+    /// Merged buckets take the lower bound of both of their times.
+    fn insert_bucket(&mut self, lower: Antichain<M::Time>, chain: Vec<M::Chunk>)
+    where
+        M::Time: timely::PartialOrder + Clone,
+    {
+        self.bucket_push(lower, chain);
+        while self.buckets.len() > 1 && (self.buckets[self.buckets.len() - 1].1.len() >= self.buckets[self.buckets.len() - 2].1.len() / 2) {
+            let (mut lower, list1) = self.bucket_pop().unwrap();
+            let (lower2, list2) = self.bucket_pop().unwrap();
+            let merged = self.merge_by(list1, list2);
+            for time in lower2.elements() {
+                lower.insert(time.clone());
+            }
+            self.bucket_push(lower, merged);
+        }
+    }
+
     // merges two sorted input lists into one sorted output list.
     fn merge_by(&mut self, list1: Vec<M::Chunk>, list2: Vec<M::Chunk>) -> Vec<M::Chunk> {
         // TODO: `list1` and `list2` get dropped; would be better to reuse?
@@ -169,6 +269,43 @@ where
         self.chains.push(chain);
     }
 
+    /// Pop a bucket and account size changes.
+    #[inline]
+    fn bucket_pop(&mut self) -> Option<(Antichain<M::Time>, Vec<M::Chunk>)> {
+        let bucket = self.buckets.pop();
+        self.account(bucket.iter().flat_map(|(_, chain)| chain).map(M::account), -1);
+        bucket
+    }
+
+    /// Remove the bucket at `index` and account size changes.
+    #[inline]
+    fn bucket_remove(&mut self, index: usize) -> (Antichain<M::Time>, Vec<M::Chunk>) {
+        let bucket = self.buckets.remove(index);
+        self.account(bucket.1.iter().map(M::account), -1);
+        bucket
+    }
+
+    /// Push a bucket and account size changes.
+    #[inline]
+    fn bucket_push(&mut self, lower: Antichain<M::Time>, chain: Vec<M::Chunk>) {
+        self.account(chain.iter().map(M::account), 1);
+        self.buckets.push((lower, chain));
+    }
+
+    /// Logs the number of updates held in chains and buckets. Only performs work if a logger exists.
+    fn log_holding_pen(&self) {
+        if let Some(logger) = &self.logger {
+            let count = |chain: &Vec<M::Chunk>| chain.iter().map(|chunk| M::account(chunk).0).sum::<usize>();
+            let chained = self.chains.iter().map(count).sum::<usize>();
+            let held_back = self.buckets.iter().map(|(_, chain)| count(chain)).sum::<usize>();
+            logger.log(HoldingPenEvent {
+                operator: self.operator_id,
+                records: chained + held_back,
+                held_back,
+            })
+        }
+    }
+
     /// Account size changes. Only performs work if a logger exists.
     ///
     /// Calculate the size based on the iterator passed along, with each attribute
@@ -199,8 +336,9 @@ where
     M: Merger,
 {
     fn drop(&mut self) {
-        // Cleanup chain to retract accounting information.
+        // Cleanup chains and buckets to retract accounting information.
         while self.chain_pop().is_some() {}
+        while self.bucket_pop().is_some() {}
     }
 }
 
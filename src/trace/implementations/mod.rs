@@ -20,11 +20,12 @@
 //! *  The `base` module is meant for collections with a single time value equivalent to the least time.
 //!    These collections must always accumulate to non-negative collections, and as such we can indicate
 //!    the frequency of an element by its multiplicity. This removes both the time and weight from the
-//!    representation, but is only appropriate for a subset (often substantial) of the data.
+//!    representation, but is only appropriate for a subset (often substantial) of the data. The
+//!    `append_only` module takes a step in this direction, storing only those weights other than one.
 //!
 //! Each of these representations is best suited for different data, but they can be combined to get the
-//! benefits of each, as appropriate. There are several `Cursor` combiners, `CursorList` and `CursorPair`,
-//! for homogeneous and inhomogeneous cursors, respectively.
+//! benefits of each, as appropriate. There are several `Cursor` combiners, `CursorList` and `CursorJoin`,
+//! for merging homogeneous cursors and for visiting the common keys of inhomogeneous cursors, respectively.
 //!
 //! #Musings
 //!
@@ -46,6 +47,7 @@ pub mod ord_neu;
 pub mod rhh;
 pub mod huffman_container;
 pub mod chunker;
+pub mod append_only;
 
 // Opinionated takes on default spines.
 pub use self::ord_neu::OrdValSpine as ValSpine;
@@ -204,6 +206,41 @@ where
     type OffsetContainer = OffsetList;
 }
 
+/// An update and layout description for byte sequence keys, which are Huffman encoded.
+///
+/// Values use their preferred containers, as with `Preferred`.
+pub struct HuffmanLayout<V: ?Sized, T, D> {
+    phantom: std::marker::PhantomData<(Box<V>, T, D)>,
+}
+
+impl<V,T,R> Update for HuffmanLayout<V, T, R>
+where
+    V: ToOwned + ?Sized,
+    V::Owned: Ord+Clone+'static,
+    T: Ord+Clone+Lattice+timely::progress::Timestamp,
+    R: Ord+Clone+Semigroup+'static,
+{
+    type Key = Vec<u8>;
+    type Val = V::Owned;
+    type Time = T;
+    type Diff = R;
+}
+
+impl<V, T, D> Layout for HuffmanLayout<V, T, D>
+where
+    V: Ord+ToOwned+PreferredContainer + ?Sized,
+    V::Owned: Ord+Clone+'static,
+    T: Ord+Clone+Lattice+timely::progress::Timestamp,
+    D: Ord+Clone+Semigroup+'static,
+{
+    type Target = HuffmanLayout<V, T, D>;
+    type KeyContainer = huffman_container::HuffmanContainer<u8>;
+    type ValContainer = V::Container;
+    type TimeContainer = Vec<T>;
+    type DiffContainer = Vec<D>;
+    type OffsetContainer = OffsetList;
+}
+
 /// A list of unsigned integers that uses `u32` elements as long as they are small enough, and switches to `u64` once they are not.
 #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Serialize, Deserialize)]
 pub struct OffsetList {
@@ -263,6 +300,24 @@ impl OffsetList {
     pub fn len(&self) -> usize {
         self.zero_prefix + self.smol.len() + self.chonk.len()
     }
+    /// Shortens the list to its first `len` offsets, and has no effect if the list is not longer.
+    ///
+    /// Offsets at indexes less than `len` are unchanged, and subsequent pushes behave as they would
+    /// have had the list been built with only those offsets.
+    pub fn truncate(&mut self, len: usize) {
+        if len <= self.zero_prefix {
+            self.zero_prefix = len;
+            self.smol.clear();
+            self.chonk.clear();
+        }
+        else if len - self.zero_prefix <= self.smol.len() {
+            self.smol.truncate(len - self.zero_prefix);
+            self.chonk.clear();
+        }
+        else {
+            self.chonk.truncate(len - self.zero_prefix - self.smol.len());
+        }
+    }
 }
 
 impl<'a> IntoIterator for &'a OffsetList {
@@ -651,6 +706,25 @@ pub mod containers {
                 (start .. limit).filter(|x| function(self.index(*x))).count()
             }
         }
+
+        /// Searches the sorted elements at indexes `lower .. upper` for `item`.
+        ///
+        /// As with `slice::binary_search`, returns `Ok(index)` for an index holding an element equal to
+        /// `item`, and otherwise `Err(index)` for the index at which `item` could be inserted to maintain
+        /// the order. If several elements are equal to `item`, any one of their indexes may be returned.
+        fn find<'a>(&'a self, lower: usize, upper: usize, item: Self::ReadItem<'a>) -> Result<usize, usize> {
+            let mut lower = lower;
+            let mut upper = upper;
+            while lower < upper {
+                let middle = lower + (upper - lower) / 2;
+                match self.index(middle).cmp(&item) {
+                    std::cmp::Ordering::Less => { lower = middle + 1; },
+                    std::cmp::Ordering::Greater => { upper = middle; },
+                    std::cmp::Ordering::Equal => { return Ok(middle); },
+                }
+            }
+            Err(lower)
+        }
     }
 
     // All `T: Clone` also implement `ToOwned<Owned = T>`, but without the constraint Rust
@@ -813,3 +887,114 @@ pub mod containers {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchContainer, OffsetList, SliceContainer};
+
+    /// A list with two leading zeros, then offsets that fit in a `u32`, then offsets that do not.
+    fn spanning() -> (OffsetList, Vec<usize>) {
+        let large = u32::MAX as usize;
+        let offsets = vec![0, 0, 3, 7, 10, large + 1, 12, large + 5];
+        let mut list = OffsetList::with_capacity(offsets.len());
+        for offset in offsets.iter() {
+            list.push(*offset);
+        }
+        assert_eq!(list.zero_prefix, 2);
+        assert_eq!(list.smol.len(), 3);
+        assert_eq!(list.chonk.len(), 3);
+        (list, offsets)
+    }
+
+    #[test]
+    fn truncate_offsets() {
+        let (_, offsets) = spanning();
+        for len in 0 ..= offsets.len() + 1 {
+            let (mut list, _) = spanning();
+            list.truncate(len);
+            let expected = &offsets[.. len.min(offsets.len())];
+            assert_eq!(list.len(), expected.len());
+            assert_eq!(list.into_iter().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn truncate_then_push() {
+        // truncating into the zero prefix allows further zeros to extend it.
+        let (mut list, _) = spanning();
+        list.truncate(1);
+        list.push(0);
+        list.push(4);
+        assert_eq!((list.zero_prefix, list.smol.len(), list.chonk.len()), (2, 1, 0));
+
+        // truncating into the small offsets returns small offsets to them.
+        let (mut list, _) = spanning();
+        list.truncate(4);
+        list.push(11);
+        assert_eq!((list.zero_prefix, list.smol.len(), list.chonk.len()), (2, 3, 0));
+        assert_eq!(list.index(4), 11);
+
+        // truncating into the large offsets continues with large offsets.
+        let (mut list, _) = spanning();
+        list.truncate(6);
+        list.push(13);
+        assert_eq!((list.zero_prefix, list.smol.len(), list.chonk.len()), (2, 3, 2));
+        assert_eq!(list.index(6), 13);
+    }
+
+    /// Checks `find` against `slice::binary_search` for each element of `items` and for values between them.
+    fn check_find<C: BatchContainer>(container: &C, probes: &[C::Owned], sorted: &[C::Owned])
+    where
+        C::Owned: Ord,
+    {
+        use crate::trace::IntoOwned;
+        for probe in probes.iter() {
+            let expected = sorted.binary_search(probe);
+            let found = container.find(0, container.len(), IntoOwned::borrow_as(probe));
+            assert_eq!(found, expected);
+            // searches within a range clamp their results to that range.
+            if container.len() > 2 {
+                let (lower, upper) = (1, container.len() - 1);
+                let found = container.find(lower, upper, IntoOwned::borrow_as(probe));
+                let expected = match sorted[lower .. upper].binary_search(probe) {
+                    Ok(index) => Ok(index + lower),
+                    Err(index) => Err(index + lower),
+                };
+                assert_eq!(found, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn find_vec() {
+        let sorted = vec![1u64, 3, 5, 7, 9, 11];
+        let probes = (0 .. 13).collect::<Vec<_>>();
+        check_find(&sorted, &probes, &sorted);
+        check_find(&Vec::<u64>::new(), &probes, &[]);
+    }
+
+    #[test]
+    fn find_slice_container() {
+        let sorted: Vec<Vec<u8>> = vec![b"".to_vec(), b"a".to_vec(), b"ab".to_vec(), b"b".to_vec(), b"ba".to_vec()];
+        let mut container = SliceContainer::<u8>::with_capacity(sorted.len());
+        for item in sorted.iter() {
+            container.push(&item[..]);
+        }
+        let probes: Vec<Vec<u8>> = vec![b"".to_vec(), b"a".to_vec(), b"aa".to_vec(), b"ab".to_vec(), b"abc".to_vec(), b"b".to_vec(), b"c".to_vec()];
+        check_find(&container, &probes, &sorted);
+    }
+
+    #[test]
+    fn find_offset_list() {
+        // a single zero, then offsets that fit in a `u32`, then offsets that do not.
+        let large = u32::MAX as usize;
+        let sorted = vec![0, 3, 7, 10, large + 1, large + 5, large + 9];
+        let mut container = OffsetList::with_capacity(sorted.len());
+        for offset in sorted.iter() {
+            container.push(*offset);
+        }
+        assert_eq!((container.zero_prefix, container.smol.len(), container.chonk.len()), (1, 3, 3));
+        let probes = vec![0, 1, 3, 5, 7, 10, 11, large, large + 1, large + 5, large + 6, large + 9, usize::MAX];
+        check_find(&container, &probes, &sorted);
+    }
+}
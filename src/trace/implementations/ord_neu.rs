@@ -9,6 +9,7 @@
 //! and should consume fewer resources (computation and memory) when it applies.
 
 use std::rc::Rc;
+use std::sync::Arc;
 use timely::container::columnation::{TimelyStack};
 use timely::container::flatcontainer::{FlatStack, RegionPreference};
 use timely::container::flatcontainer::impls::tuple::{TupleABCRegion, TupleABRegion};
@@ -17,8 +18,9 @@ use crate::trace::implementations::spine_fueled::Spine;
 use crate::trace::implementations::merge_batcher::{MergeBatcher, VecMerger, ColMerger};
 use crate::trace::implementations::merge_batcher_flat::FlatcontainerMerger;
 use crate::trace::rc_blanket_impls::RcBuilder;
+use crate::trace::arc_blanket_impls::ArcBuilder;
 
-use super::{Update, Layout, Vector, TStack, Preferred, FlatLayout};
+use super::{Update, Layout, Vector, TStack, Preferred, HuffmanLayout, FlatLayout};
 
 pub use self::val_batch::{OrdValBatch, OrdValBuilder};
 pub use self::key_batch::{OrdKeyBatch, OrdKeyBuilder};
@@ -30,6 +32,11 @@ pub type OrdValBatcher<K, V, T, R> = MergeBatcher<Vec<((K,V),T,R)>, VecChunker<(
 /// A builder using ordered lists.
 pub type RcOrdValBuilder<K, V, T, R> = RcBuilder<OrdValBuilder<Vector<((K,V),T,R)>, Vec<((K,V),T,R)>>>;
 
+/// A trace implementation using a spine of ordered lists, whose batches can be shared with other threads.
+pub type ArcOrdValSpine<K, V, T, R> = Spine<Arc<OrdValBatch<Vector<((K,V),T,R)>>>>;
+/// A builder using ordered lists, whose batches can be shared with other threads.
+pub type ArcOrdValBuilder<K, V, T, R> = ArcBuilder<OrdValBuilder<Vector<((K,V),T,R)>, Vec<((K,V),T,R)>>>;
+
 // /// A trace implementation for empty values using a spine of ordered lists.
 // pub type OrdKeySpine<K, T, R> = Spine<Rc<OrdKeyBatch<Vector<((K,()),T,R)>>>>;
 
@@ -98,6 +105,13 @@ pub type PreferredBatcher<K, V, T, R> = MergeBatcher<Vec<((<K as ToOwned>::Owned
 /// A builder for columnar storage.
 pub type PreferredBuilder<K, V, T, R> = RcBuilder<OrdValBuilder<Preferred<K,V,T,R>, TimelyStack<((<K as ToOwned>::Owned,<V as ToOwned>::Owned),T,R)>>>;
 
+/// A trace implementation for byte sequence keys, which are Huffman encoded.
+pub type HuffmanSpine<V, T, R> = Spine<Rc<OrdValBatch<HuffmanLayout<V,T,R>>>>;
+/// A batcher for Huffman encoded keys.
+pub type HuffmanBatcher<V, T, R> = PreferredBatcher<[u8], V, T, R>;
+/// A builder for Huffman encoded keys.
+pub type HuffmanBuilder<V, T, R> = RcBuilder<OrdValBuilder<HuffmanLayout<V,T,R>, TimelyStack<((Vec<u8>,<V as ToOwned>::Owned),T,R)>>>;
+
 // /// A trace implementation backed by columnar storage.
 // pub type ColKeySpine<K, T, R> = Spine<Rc<OrdKeyBatch<TStack<((K,()),T,R)>>>>;
 
@@ -107,11 +121,12 @@ mod val_batch {
     use std::marker::PhantomData;
     use serde::{Deserialize, Serialize};
     use timely::container::PushInto;
-    use timely::progress::{Antichain, frontier::AntichainRef};
+    use timely::progress::{Antichain, Timestamp, frontier::AntichainRef};
 
     use crate::trace::{Batch, BatchReader, Builder, Cursor, Description, Merger};
     use crate::trace::implementations::{BatchContainer, BuilderInput};
     use crate::trace::cursor::IntoOwned;
+    use crate::difference::IsZero;
 
     use super::{Layout, Update};
 
@@ -574,6 +589,12 @@ mod val_batch {
         fn rewind_vals(&mut self, storage: &OrdValBatch<L>) {
             self.val_cursor = storage.storage.values_for_key(self.key_cursor).0;
         }
+        fn count_vals(&mut self, storage: &OrdValBatch<L>) -> usize {
+            if !self.key_valid(storage) { return 0; }
+            let (lower, upper) = storage.storage.values_for_key(self.key_cursor);
+            self.val_cursor = lower;
+            upper - lower
+        }
     }
 
     /// A builder for creating layers from unsorted update tuples.
@@ -620,6 +641,41 @@ mod val_batch {
         }
     }
 
+    impl<L: Layout, K, V, T, R> OrdValBuilder<L, Vec<((K, V), T, R)>>
+    where
+        Self: Builder<Input = Vec<((K, V), T, R)>, Time = T, Output = OrdValBatch<L>>,
+        K: Ord,
+        V: Ord,
+        T: Timestamp,
+        R: IsZero,
+    {
+        /// Builds a batch directly from updates that are already sorted and consolidated.
+        ///
+        /// This skips the batcher, for updates whose order is already known, for example when bulk-loading
+        /// precomputed data. The updates must be sorted by `(key, val, time)`, with no repeated triples and
+        /// no zero differences, and their times must lie between the lower and upper frontiers of
+        /// `description`; this is checked in debug builds only. An empty input produces an empty batch.
+        pub fn from_sorted<I>(iter: I, description: Description<T>) -> OrdValBatch<L>
+        where
+            I: IntoIterator<Item = ((K, V), T, R)>,
+        {
+            let updates: Vec<_> = iter.into_iter().collect();
+            debug_assert!(
+                updates.windows(2).all(|w| (&w[0].0, &w[0].1) < (&w[1].0, &w[1].1)),
+                "OrdValBuilder::from_sorted: updates not sorted and consolidated",
+            );
+            debug_assert!(
+                updates.iter().all(|(_, _, diff)| !diff.is_zero()),
+                "OrdValBuilder::from_sorted: update with zero difference",
+            );
+            debug_assert!(
+                updates.iter().all(|(_, time, _)| description.lower().less_equal(time) && !description.upper().less_equal(time)),
+                "OrdValBuilder::from_sorted: update time outside of description",
+            );
+            Self::seal(&mut vec![updates], description)
+        }
+    }
+
     impl<L, CI> Builder for OrdValBuilder<L, CI>
     where
         L: Layout,
@@ -1070,6 +1126,11 @@ mod key_batch {
         fn rewind_vals(&mut self, _storage: &Self::Storage) {
             self.val_stepped = false;
         }
+        fn count_vals(&mut self, storage: &Self::Storage) -> usize {
+            if !self.key_valid(storage) { return 0; }
+            self.rewind_vals(storage);
+            1
+        }
     }
 
     /// A builder for creating layers from unsorted update tuples.
@@ -19,14 +19,14 @@ use crate::trace::rc_blanket_impls::RcBuilder;
 
 use super::{Update, Layout, Vector, TStack};
 
-use self::val_batch::{RhhValBatch, RhhValBuilder};
+use self::val_batch::RhhValBatch;
 
 /// A trace implementation using a spine of ordered lists.
 pub type VecSpine<K, V, T, R> = Spine<Rc<RhhValBatch<Vector<((K,V),T,R)>>>>;
 /// A batcher for ordered lists.
 pub type VecBatcher<K,V,T,R> = MergeBatcher<Vec<((K,V),T,R)>, VecChunker<((K,V),T,R)>, VecMerger<(K, V), T, R>>;
 /// A builder for ordered lists.
-pub type VecBuilder<K,V,T,R> = RcBuilder<RhhValBuilder<Vector<((K,V),T,R)>, Vec<((K,V),T,R)>>>;
+pub type VecBuilder<K,V,T,R,const SLOTS: usize = SLOTS_PER_KEY> = RcBuilder<val_batch::RhhValBuilder<Vector<((K,V),T,R)>, Vec<((K,V),T,R)>, SLOTS>>;
 
 // /// A trace implementation for empty values using a spine of ordered lists.
 // pub type OrdKeySpine<K, T, R> = Spine<Rc<OrdKeyBatch<Vector<((K,()),T,R)>>>>;
@@ -36,11 +36,30 @@ pub type ColSpine<K, V, T, R> = Spine<Rc<RhhValBatch<TStack<((K,V),T,R)>>>>;
 /// A batcher for columnar storage.
 pub type ColBatcher<K,V,T,R> = MergeBatcher<Vec<((K,V),T,R)>, ColumnationChunker<((K,V),T,R)>, ColMerger<(K,V),T,R>>;
 /// A builder for columnar storage.
-pub type ColBuilder<K,V,T,R> = RcBuilder<RhhValBuilder<TStack<((K,V),T,R)>, TimelyStack<((K,V),T,R)>>>;
+pub type ColBuilder<K,V,T,R,const SLOTS: usize = SLOTS_PER_KEY> = RcBuilder<val_batch::RhhValBuilder<TStack<((K,V),T,R)>, TimelyStack<((K,V),T,R)>, SLOTS>>;
 
 // /// A trace implementation backed by columnar storage.
 // pub type ColKeySpine<K, T, R> = Spine<Rc<OrdKeyBatch<TStack<((K,()),T,R)>>>>;
 
+/// A trace implementation for any hashable key, placed by `Hashable::hashed()`.
+///
+/// Keys are wrapped in `HashWrapper`, which orders them by their hash and then by the keys themselves,
+/// so that keys with colliding hashes are located by ordered comparison among their neighbors.
+/// This layout is best suited to keys whose hashes are uniformly distributed.
+pub type RhhValSpine<K, V, T, R> = VecSpine<HashWrapper<K>, V, T, R>;
+/// A batcher for hash-ordered keys.
+pub type RhhValBatcher<K, V, T, R> = VecBatcher<HashWrapper<K>, V, T, R>;
+/// A builder for hash-ordered keys, reserving `SLOTS` key slots for each key.
+pub type RhhValBuilder<K, V, T, R, const SLOTS: usize = SLOTS_PER_KEY> = VecBuilder<HashWrapper<K>, V, T, R, SLOTS>;
+
+/// The default number of key slots allocated for each key, the inverse of the target load factor.
+///
+/// A batch with `n` keys places them among `SLOTS * n` slots, at locations proportional to
+/// their hashes. Larger values reduce the displacement of keys from their desired locations, and with
+/// it the probe lengths of `seek_key`, at the expense of dead space in the batch. Builders accept
+/// a different value through their `SLOTS` parameter, and merges keep the value of their inputs.
+pub const SLOTS_PER_KEY: usize = 2;
+
 /// A carrier trait indicating that the type's `Ord` and `PartialOrd` implementations are by `Hashable::hashed()`.
 pub trait HashOrdered: Hashable { }
 
@@ -125,6 +144,10 @@ mod val_batch {
         /// A number large enough that when it divides any `u64` the result is at most `self.key_capacity`.
         /// When that capacity is zero or one, this is set to zero instead.
         pub divisor: u64,
+        /// The number of key slots reserved for each key when the batch was built, or zero for empty batches.
+        ///
+        /// Merges reserve this many slots per key in their output, so that the load factor persists.
+        pub slots_per_key: usize,
         /// The number of present keys, distinct from `keys.len()` which contains 
         pub key_count: usize,
 
@@ -329,6 +352,7 @@ mod val_batch {
                     key_count: 0,
                     key_capacity: 0,
                     divisor: 0,
+                    slots_per_key: 0,
                 },
                 description: Description::new(lower, upper, Antichain::from_elem(Self::Time::minimum())),
                 updates: 0,
@@ -377,7 +401,12 @@ mod val_batch {
             // This is a massive overestimate on the number of keys, but we don't have better information.
             // An over-estimate can be a massive problem as well, with sparse regions being hard to cross.
             let max_cap = batch1.len() + batch2.len();
-            let rhh_cap = 2 * max_cap;
+            // Retain the load factor of the inputs, unless both are empty and have none.
+            let slots_per_key = match std::cmp::max(batch1.storage.slots_per_key, batch2.storage.slots_per_key) {
+                0 => super::SLOTS_PER_KEY,
+                slots => slots,
+            };
+            let rhh_cap = slots_per_key * max_cap;
 
             let batch1 = &batch1.storage;
             let batch2 = &batch2.storage;
@@ -392,6 +421,7 @@ mod val_batch {
                 key_count: 0,
                 key_capacity: rhh_cap,
                 divisor: RhhValStorage::<L>::divisor_for_capacity(rhh_cap),
+                slots_per_key,
             };
 
             // Mark explicit types because type inference fails to resolve it.
@@ -735,7 +765,9 @@ mod val_batch {
     }
 
     /// A builder for creating layers from unsorted update tuples.
-    pub struct RhhValBuilder<L: Layout, CI>
+    ///
+    /// The builder reserves `SLOTS` key slots for each key, the inverse of the target load factor.
+    pub struct RhhValBuilder<L: Layout, CI, const SLOTS: usize = { super::SLOTS_PER_KEY }>
     where 
         <L::Target as Update>::Key: Default + HashOrdered,
     {
@@ -749,7 +781,7 @@ mod val_batch {
         _marker: PhantomData<CI>,
     }
 
-    impl<L: Layout, CI> RhhValBuilder<L, CI>
+    impl<L: Layout, CI, const SLOTS: usize> RhhValBuilder<L, CI, SLOTS>
     where 
         <L::Target as Update>::Key: Default + HashOrdered,
     {
@@ -784,7 +816,7 @@ mod val_batch {
         }
     }
 
-    impl<L: Layout, CI> Builder for RhhValBuilder<L, CI>
+    impl<L: Layout, CI, const SLOTS: usize> Builder for RhhValBuilder<L, CI, SLOTS>
     where
         <L::Target as Update>::Key: Default + HashOrdered,
         CI: for<'a> BuilderInput<L::KeyContainer, L::ValContainer, Key<'a> = <L::Target as Update>::Key, Time=<L::Target as Update>::Time, Diff=<L::Target as Update>::Diff>,
@@ -797,8 +829,8 @@ mod val_batch {
 
         fn with_capacity(keys: usize, vals: usize, upds: usize) -> Self {
 
-            // Double the capacity for RHH; probably excessive.
-            let rhh_capacity = 2 * keys;
+            // Multiply the capacity for RHH, leaving slots vacant to reduce displacement.
+            let rhh_capacity = SLOTS * keys;
             let divisor = RhhValStorage::<L>::divisor_for_capacity(rhh_capacity);                        
             // We want some additive slop, in case we spill over.
             // This number magically chosen based on nothing in particular.
@@ -817,6 +849,7 @@ mod val_batch {
                     key_count: 0,
                     key_capacity: rhh_capacity,
                     divisor,
+                    slots_per_key: SLOTS,
                 },
                 singleton: None,
                 singletons: 0,
@@ -70,7 +70,7 @@
 
 
 use crate::logging::Logger;
-use crate::trace::{Batch, BatchReader, Trace, TraceReader, ExertionLogic};
+use crate::trace::{Batch, BatchReader, Trace, TraceReader, ExertionLogic, MergeStats};
 use crate::trace::cursor::CursorList;
 use crate::trace::Merger;
 
@@ -78,6 +78,32 @@ use ::timely::dataflow::operators::generic::OperatorInfo;
 use ::timely::progress::{Antichain, frontier::AntichainRef};
 use ::timely::order::PartialOrder;
 
+use std::time::{Duration, Instant};
+
+/// Parameters that determine how eagerly a [`Spine`] merges its batches.
+///
+/// Batches are assigned to layers by their size, rounded up to a power of two, and each layer holds
+/// at most two batches, which are merged progressively as fuel is supplied. The policy allows tuning
+/// both the layer assignment and the fuel, for example to merge many small batches more eagerly.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MergePolicy {
+    /// Batches with fewer updates are assigned to layers as if they had this many updates.
+    ///
+    /// Small batches then share a layer, and are merged with each other promptly, as the fuel
+    /// granted for each batch corresponds to its assigned layer rather than its actual size.
+    pub min_batch_size: usize,
+    /// Multiplier of the fuel granted to merges for each introduced update and each unit of exertion.
+    ///
+    /// This value should be at least one; a value of zero is treated as one.
+    pub effort: usize,
+}
+
+impl Default for MergePolicy {
+    fn default() -> Self {
+        MergePolicy { min_batch_size: 1, effort: 1 }
+    }
+}
+
 /// An append-only collection of update tuples.
 ///
 /// A spine maintains a small number of immutable collections of update tuples, merging the collections when
@@ -92,11 +118,17 @@ pub struct Spine<B: Batch> {
     pending: Vec<B>,                        // Batches at times in advance of `frontier`.
     upper: Antichain<B::Time>,
     effort: usize,
+    /// Batches with fewer updates are introduced at the layer for this many updates.
+    min_batch_size: usize,
     activator: Option<timely::scheduling::activate::Activator>,
     /// Parameters to `exert_logic`, containing tuples of `(index, count, length)`.
     exert_logic_param: Vec<(usize, usize, usize)>,
     /// Logic to indicate whether and how many records we should introduce in the absence of actual updates.
     exert_logic: Option<ExertionLogic>,
+    /// Statistics about completed merges, if collection is enabled.
+    merge_stats: Option<MergeStats>,
+    /// Time spent so far on the in-progress merge at each layer, if statistics collection is enabled.
+    merge_work: Vec<Duration>,
 }
 
 impl<B> TraceReader for Spine<B>
@@ -266,6 +298,9 @@ where
         // Determine whether we should apply effort independent of updates.
         if let Some(effort) = self.exert_effort() {
 
+            // Scale up by the effort parameter, as for introduced batches.
+            let effort = effort * self.effort;
+
             // If any merges exist, we can directly call `apply_fuel`.
             if self.merging.iter().any(|b| b.is_double()) {
                 self.apply_fuel(&mut (effort as isize));
@@ -287,6 +322,20 @@ where
         self.exert_logic = Some(logic);
     }
 
+    fn set_merge_stats(&mut self, enabled: bool) {
+        if !enabled {
+            self.merge_stats = None;
+            self.merge_work.clear();
+        }
+        else if self.merge_stats.is_none() {
+            self.merge_stats = Some(MergeStats::default());
+        }
+    }
+
+    fn merge_stats(&self) -> MergeStats {
+        self.merge_stats.clone().unwrap_or_default()
+    }
+
     // Ideally, this method acts as insertion of `batch`, even if we are not yet able to begin
     // merging the batch. This means it is a good time to perform amortized work proportional
     // to the size of batch.
@@ -400,6 +449,19 @@ impl<B: Batch> Spine<B> {
             .collect()
     }
 
+    /// Sets the policy for merging batches, which applies to batches introduced from now on.
+    ///
+    /// This replaces the effort multiplier supplied to `with_effort`.
+    pub fn set_merge_policy(&mut self, policy: MergePolicy) {
+        self.effort = std::cmp::max(policy.effort, 1);
+        self.min_batch_size = std::cmp::max(policy.min_batch_size, 1);
+    }
+
+    /// The current policy for merging batches.
+    pub fn merge_policy(&self) -> MergePolicy {
+        MergePolicy { min_batch_size: self.min_batch_size, effort: self.effort }
+    }
+
     /// Allocates a fueled `Spine` with a specified effort multiplier.
     ///
     /// This trace will merge batches progressively, with each inserted batch applying a multiple
@@ -424,9 +486,12 @@ impl<B: Batch> Spine<B> {
             pending: Vec::new(),
             upper: Antichain::from_elem(<B::Time as timely::progress::Timestamp>::minimum()),
             effort,
+            min_batch_size: 1,
             activator,
             exert_logic_param: Vec::default(),
             exert_logic: None,
+            merge_stats: None,
+            merge_work: Vec::new(),
         }
     }
 
@@ -461,7 +526,7 @@ impl<B: Batch> Spine<B> {
 
             // Normal insertion for the batch.
             if let Some(batch) = batch {
-                let index = batch.len().next_power_of_two();
+                let index = std::cmp::max(batch.len(), self.min_batch_size).next_power_of_two();
                 self.introduce_batch(Some(batch), index.trailing_zeros() as usize);
             }
         }
@@ -608,7 +673,11 @@ impl<B: Batch> Spine<B> {
             // Give each level independent fuel, for now.
             let mut fuel = *fuel;
             // Pass along various logging stuffs, in case we need to report success.
+            let timer = self.merge_stats.as_ref().map(|_| Instant::now());
             self.merging[index].work(&mut fuel);
+            if let Some(timer) = timer {
+                self.charge_merge_work(index, timer.elapsed());
+            }
             // `fuel` could have a deficit at this point, meaning we over-spent when
             // we took a merge step. We could ignore this, or maintain the deficit
             // and account future fuel against it before spending again. It isn't
@@ -663,8 +732,19 @@ impl<B: Batch> Spine<B> {
 
     /// Completes and extracts what ever is at layer `index`.
     fn complete_at(&mut self, index: usize) -> Option<B> {
-        if let Some((merged, inputs)) = self.merging[index].complete() {
+        // Merges not yet complete have their remaining work forced, which we time if collecting statistics.
+        let forced = !self.merging[index].is_complete();
+        let timer = self.merge_stats.as_ref().map(|_| Instant::now());
+        let completed = self.merging[index].complete();
+        if let Some(timer) = timer {
+            self.charge_merge_work(index, timer.elapsed());
+        }
+        let duration = self.merge_work.get_mut(index).map(std::mem::take).unwrap_or_default();
+        if let Some((merged, inputs)) = completed {
             if let Some((input1, input2)) = inputs {
+                if let Some(stats) = &mut self.merge_stats {
+                    stats.record(input1.len() + input2.len(), duration, forced);
+                }
                 // Log the completion of a merge from existing parts.
                 self.logger.as_ref().map(|l| l.log(
                     crate::logging::MergeEvent {
@@ -683,6 +763,14 @@ impl<B: Batch> Spine<B> {
         }
     }
 
+    /// Accounts `duration` of merge work against the merge at layer `index`.
+    fn charge_merge_work(&mut self, index: usize, duration: Duration) {
+        if self.merge_work.len() <= index {
+            self.merge_work.resize(index + 1, Duration::default());
+        }
+        self.merge_work[index] += duration;
+    }
+
     /// Attempts to draw down large layers to size appropriate layers.
     fn tidy_layers(&mut self) {
 
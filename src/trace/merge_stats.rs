@@ -0,0 +1,71 @@
+//! Statistics about the merges a trace performs.
+//!
+//! These statistics are collected in-process by traces that merge batches, and can be read
+//! synchronously through `TraceAgent::merge_stats`, without subscribing to the logging stream.
+
+use std::time::Duration;
+
+/// Histograms of the sizes and durations of completed merges.
+///
+/// Both histograms use power-of-two buckets: entry `i` counts merges whose measure `m` satisfies
+/// `2^(i-1) <= m < 2^i`, with entry zero counting merges of measure zero. Sizes are measured in
+/// the total number of updates in the two merged batches, and durations in microseconds spent
+/// merging, which accumulates over each invocation of merge work rather than wall-clock time
+/// from the start of the merge.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MergeStats {
+    /// Merges completed by the amortized work applied as batches are introduced, or as the trace is exerted.
+    pub amortized: usize,
+    /// Merges whose remaining work was forced to completion, for example to make room for a new batch.
+    pub forced: usize,
+    /// Histogram of merge sizes, in updates.
+    pub sizes: Vec<usize>,
+    /// Histogram of merge durations, in microseconds.
+    pub durations: Vec<usize>,
+    /// Total number of updates across all merged batches.
+    pub total_updates: usize,
+    /// Total time spent in merge work.
+    pub total_duration: Duration,
+}
+
+impl MergeStats {
+    /// Records the completion of a merge of `updates` updates that took `duration` to perform.
+    pub fn record(&mut self, updates: usize, duration: Duration, forced: bool) {
+        if forced { self.forced += 1; } else { self.amortized += 1; }
+        Self::increment(&mut self.sizes, updates as u64);
+        Self::increment(&mut self.durations, duration.as_micros() as u64);
+        self.total_updates += updates;
+        self.total_duration += duration;
+    }
+
+    /// The number of completed merges.
+    pub fn merges(&self) -> usize {
+        self.amortized + self.forced
+    }
+
+    /// Incorporates the statistics of `other`, for example to combine statistics across workers.
+    pub fn extend(&mut self, other: &MergeStats) {
+        self.amortized += other.amortized;
+        self.forced += other.forced;
+        for (index, count) in other.sizes.iter().enumerate() {
+            Self::add_at(&mut self.sizes, index, *count);
+        }
+        for (index, count) in other.durations.iter().enumerate() {
+            Self::add_at(&mut self.durations, index, *count);
+        }
+        self.total_updates += other.total_updates;
+        self.total_duration += other.total_duration;
+    }
+
+    fn increment(histogram: &mut Vec<usize>, measure: u64) {
+        let bucket = (64 - measure.leading_zeros()) as usize;
+        Self::add_at(histogram, bucket, 1);
+    }
+
+    fn add_at(histogram: &mut Vec<usize>, index: usize, count: usize) {
+        if histogram.len() <= index {
+            histogram.resize(index + 1, 0);
+        }
+        histogram[index] += count;
+    }
+}
@@ -10,18 +10,21 @@
 pub mod cursor;
 pub mod description;
 pub mod implementations;
+pub mod merge_stats;
 pub mod wrappers;
 
 use timely::progress::{Antichain, frontier::AntichainRef};
 use timely::progress::Timestamp;
+use timely::order::PartialOrder;
 
 use crate::logging::Logger;
-use crate::trace::cursor::IntoOwned;
+use crate::trace::cursor::{CursorAt, IntoOwned};
 use crate::difference::Semigroup;
 use crate::lattice::Lattice;
 // use ::difference::Semigroup;
 pub use self::cursor::Cursor;
 pub use self::description::Description;
+pub use self::merge_stats::MergeStats;
 
 /// A type used to express how much effort a trace should exert even in the absence of updates.
 pub type ExertionLogic = std::sync::Arc<dyn for<'a> Fn(&'a [(usize, usize, usize)])->Option<usize>+Send+Sync>;
@@ -89,6 +92,20 @@ pub trait TraceReader {
     /// should allow `upper` such as `&[]` as used by `self.cursor()`, though it is difficult to imagine other uses.
     fn cursor_through(&mut self, upper: AntichainRef<Self::Time>) -> Option<(Self::Cursor, Self::Storage)>;
 
+    /// Acquires a cursor presenting only updates at times less or equal to an element of `frontier`.
+    ///
+    /// The updates for each `(key, val)` pair accumulate to their value as of `frontier`, which allows point
+    /// in time queries against the trace. As update times not beyond the logical compaction frontier may have
+    /// been advanced, the method returns `None` unless `frontier` is beyond `get_logical_compaction()`.
+    fn cursor_at(&mut self, frontier: AntichainRef<Self::Time>) -> Option<(CursorAt<Self::Cursor>, Self::Storage)> {
+        if !PartialOrder::less_equal(&self.get_logical_compaction(), &frontier) {
+            return None;
+        }
+        let frontier = frontier.to_owned();
+        self.cursor_through(Antichain::new().borrow())
+            .map(|(cursor, storage)| (CursorAt::new(cursor, frontier), storage))
+    }
+
     /// Advances the frontier that constrains logical compaction.
     ///
     /// Logical compaction is the ability of the trace to change the times of the updates it contains.
@@ -195,6 +212,27 @@ pub trait TraceReader {
         });
     }
 
+    /// Estimates the size of the trace, as numbers of keys, of `(key, val)` pairs, and of updates.
+    ///
+    /// Each count is summed across the batches of the trace, and a key or pair present in several batches
+    /// is counted once for each. The number of updates is the sum of the batches' lengths, whereas the keys
+    /// and pairs are counted by a pass of a cursor over each batch, which visits no times.
+    fn estimate_size(&self) -> (usize, usize, usize) {
+        let mut keys = 0;
+        let mut vals = 0;
+        let mut updates = 0;
+        self.map_batches(|batch| {
+            updates += batch.len();
+            let mut cursor = batch.cursor();
+            while cursor.key_valid(batch) {
+                keys += 1;
+                vals += cursor.count_vals(batch);
+                cursor.step_key(batch);
+            }
+        });
+        (keys, vals, updates)
+    }
+
 }
 
 /// An append-only collection of `(key, val, time, diff)` tuples.
@@ -224,6 +262,15 @@ where <Self as TraceReader>::Batch: Batch {
     /// updates to perform, or `None` if no work is required.
     fn set_exert_logic(&mut self, logic: ExertionLogic);
 
+    /// Enables or disables the collection of merge statistics.
+    ///
+    /// Disabling collection discards any statistics collected so far. Traces that do not merge
+    /// batches may ignore this, which is the default behavior.
+    fn set_merge_stats(&mut self, _enabled: bool) { }
+
+    /// Statistics about the merges completed while collection was enabled.
+    fn merge_stats(&self) -> MergeStats { MergeStats::default() }
+
     /// Introduces a batch of updates to the trace.
     ///
     /// Batches describe the time intervals they contain, and they should be added to the trace in contiguous
@@ -315,6 +362,10 @@ pub trait Batcher {
     fn seal<B: Builder<Input=Self::Output, Time=Self::Time>>(&mut self, upper: Antichain<Self::Time>) -> B::Output;
     /// Returns the lower envelope of contained update times.
     fn frontier(&mut self) -> timely::progress::frontier::AntichainRef<Self::Time>;
+    /// Bounds the work performed by each call to `push_container`, or removes the bound if `None`.
+    ///
+    /// The unit of work is up to the batcher, and batchers that cannot bound their work may ignore the bound.
+    fn set_fuel(&mut self, _fuel: Option<usize>) { }
 }
 
 /// Functionality for building batches from ordered update sequences.
@@ -441,6 +492,7 @@ pub mod rc_blanket_impls {
 
         #[inline] fn rewind_keys(&mut self, storage: &Self::Storage) { self.cursor.rewind_keys(storage) }
         #[inline] fn rewind_vals(&mut self, storage: &Self::Storage) { self.cursor.rewind_vals(storage) }
+        #[inline] fn count_vals(&mut self, storage: &Self::Storage) -> usize { self.cursor.count_vals(storage) }
     }
 
     /// An immutable collection of updates.
@@ -477,3 +529,118 @@ pub mod rc_blanket_impls {
         fn done(self) -> Rc<B> { Rc::new(self.merger.done()) }
     }
 }
+
+/// Blanket implementations for atomically reference counted batches.
+///
+/// These mirror the implementations for `Rc`, and allow batches to be shared with other threads.
+/// Only batches are shared: each batch is immutable once built, and a thread holding a batch (or a
+/// cursor over it) sees the same updates regardless of what the trace that produced it does next.
+/// The trace itself, including its spine of batches, remains confined to the worker that maintains it.
+pub mod arc_blanket_impls {
+
+    use std::sync::Arc;
+
+    use timely::progress::{Antichain, frontier::AntichainRef};
+    use super::{Batch, BatchReader, Builder, Merger, Cursor, Description};
+
+    impl<B: BatchReader> BatchReader for Arc<B> {
+        type Key<'a> = B::Key<'a>;
+        type Val<'a> = B::Val<'a>;
+        type Time = B::Time;
+        type TimeGat<'a> = B::TimeGat<'a>;
+        type Diff = B::Diff;
+        type DiffGat<'a> = B::DiffGat<'a>;
+
+        /// The type used to enumerate the batch's contents.
+        type Cursor = ArcBatchCursor<B::Cursor>;
+        /// Acquires a cursor to the batch's contents.
+        fn cursor(&self) -> Self::Cursor {
+            ArcBatchCursor::new((**self).cursor())
+        }
+
+        /// The number of updates in the batch.
+        fn len(&self) -> usize { (**self).len() }
+        /// Describes the times of the updates in the batch.
+        fn description(&self) -> &Description<Self::Time> { (**self).description() }
+    }
+
+    /// Wrapper to provide cursor to atomically reference counted batches.
+    pub struct ArcBatchCursor<C> {
+        cursor: C,
+    }
+
+    impl<C> ArcBatchCursor<C> {
+        fn new(cursor: C) -> Self {
+            ArcBatchCursor {
+                cursor,
+            }
+        }
+    }
+
+    impl<C: Cursor> Cursor for ArcBatchCursor<C> {
+
+        type Key<'a> = C::Key<'a>;
+        type Val<'a> = C::Val<'a>;
+        type Time = C::Time;
+        type TimeGat<'a> = C::TimeGat<'a>;
+        type Diff = C::Diff;
+        type DiffGat<'a> = C::DiffGat<'a>;
+
+        type Storage = Arc<C::Storage>;
+
+        #[inline] fn key_valid(&self, storage: &Self::Storage) -> bool { self.cursor.key_valid(storage) }
+        #[inline] fn val_valid(&self, storage: &Self::Storage) -> bool { self.cursor.val_valid(storage) }
+
+        #[inline] fn key<'a>(&self, storage: &'a Self::Storage) -> Self::Key<'a> { self.cursor.key(storage) }
+        #[inline] fn val<'a>(&self, storage: &'a Self::Storage) -> Self::Val<'a> { self.cursor.val(storage) }
+
+        #[inline]
+        fn map_times<L: FnMut(Self::TimeGat<'_>, Self::DiffGat<'_>)>(&mut self, storage: &Self::Storage, logic: L) {
+            self.cursor.map_times(storage, logic)
+        }
+
+        #[inline] fn step_key(&mut self, storage: &Self::Storage) { self.cursor.step_key(storage) }
+        #[inline] fn seek_key(&mut self, storage: &Self::Storage, key: Self::Key<'_>) { self.cursor.seek_key(storage, key) }
+
+        #[inline] fn step_val(&mut self, storage: &Self::Storage) { self.cursor.step_val(storage) }
+        #[inline] fn seek_val(&mut self, storage: &Self::Storage, val: Self::Val<'_>) { self.cursor.seek_val(storage, val) }
+
+        #[inline] fn rewind_keys(&mut self, storage: &Self::Storage) { self.cursor.rewind_keys(storage) }
+        #[inline] fn rewind_vals(&mut self, storage: &Self::Storage) { self.cursor.rewind_vals(storage) }
+        #[inline] fn count_vals(&mut self, storage: &Self::Storage) -> usize { self.cursor.count_vals(storage) }
+    }
+
+    /// An immutable collection of updates.
+    impl<B: Batch> Batch for Arc<B> {
+        type Merger = ArcMerger<B>;
+        fn empty(lower: Antichain<Self::Time>, upper: Antichain<Self::Time>) -> Self {
+            Arc::new(B::empty(lower, upper))
+        }
+    }
+
+    /// Wrapper type for building atomically reference counted batches.
+    pub struct ArcBuilder<B: Builder> { builder: B }
+
+    /// Functionality for building batches from ordered update sequences.
+    impl<B: Builder> Builder for ArcBuilder<B> {
+        type Input = B::Input;
+        type Time = B::Time;
+        type Output = Arc<B::Output>;
+        fn with_capacity(keys: usize, vals: usize, upds: usize) -> Self { ArcBuilder { builder: B::with_capacity(keys, vals, upds) } }
+        fn push(&mut self, input: &mut Self::Input) { self.builder.push(input) }
+        fn done(self, description: Description<Self::Time>) -> Arc<B::Output> { Arc::new(self.builder.done(description)) }
+        fn seal(chain: &mut Vec<Self::Input>, description: Description<Self::Time>) -> Self::Output {
+            Arc::new(B::seal(chain, description))
+        }
+    }
+
+    /// Wrapper type for merging atomically reference counted batches.
+    pub struct ArcMerger<B:Batch> { merger: B::Merger }
+
+    /// Represents a merge in progress.
+    impl<B:Batch> Merger<Arc<B>> for ArcMerger<B> {
+        fn new(source1: &Arc<B>, source2: &Arc<B>, compaction_frontier: AntichainRef<B::Time>) -> Self { ArcMerger { merger: B::begin_merge(source1, source2, compaction_frontier) } }
+        fn work(&mut self, source1: &Arc<B>, source2: &Arc<B>, fuel: &mut isize) { self.merger.work(source1, source2, fuel) }
+        fn done(self) -> Arc<B> { Arc::new(self.merger.done()) }
+    }
+}
@@ -1,15 +1,18 @@
 //! Wrappers to provide trace access to nested scopes.
 
+use timely::order::PartialOrder;
 use timely::progress::timestamp::Refines;
 use timely::progress::{Antichain, frontier::AntichainRef};
 
 use crate::lattice::Lattice;
 use crate::trace::{TraceReader, BatchReader, Description};
-use crate::trace::cursor::Cursor;
+use crate::trace::cursor::{Cursor, IntoOwned};
 
 /// Wrapper to provide trace to nested scope.
 ///
-/// Each wrapped update is presented with a timestamp determined by `logic`.
+/// Each wrapped update is presented with a timestamp determined by `logic`, from its key, value, and time.
+/// The timestamp must be in advance of the time entered into the nested scope, which is checked by debug
+/// assertions when the times are presented.
 ///
 /// At the same time, we require a method `prior` that can "invert" timestamps,
 /// and which will be applied to compaction frontiers as they are communicated
@@ -217,7 +220,9 @@ where
         let val = self.val(storage);
         let logic2 = &mut self.logic;
         self.cursor.map_times(storage, |time, diff| {
-            logic(&logic2(key, val, time), diff)
+            let inner = logic2(key, val, time);
+            debug_assert!(TInner::to_inner(time.into_owned()).less_equal(&inner), "enter_at: time {:?} not in advance of entered time {:?}", inner, time.into_owned());
+            logic(&inner, diff)
         })
     }
 
@@ -276,7 +281,9 @@ where
         let val = self.val(storage);
         let logic2 = &mut self.logic;
         self.cursor.map_times(&storage.batch, |time, diff| {
-            logic(&logic2(key, val, time), diff)
+            let inner = logic2(key, val, time);
+            debug_assert!(TInner::to_inner(time.into_owned()).less_equal(&inner), "enter_at: time {:?} not in advance of entered time {:?}", inner, time.into_owned());
+            logic(&inner, diff)
         })
     }
 
@@ -0,0 +1,297 @@
+//! Wrapper for a trace whose values are filtered and projected together.
+//!
+//! The wrapper presents each value through a function that either discards the value, or returns a
+//! projection that borrows from it. This allows an arrangement to be both restricted and projected
+//! (for example, read as an arrangement of `(K, A)` from the `(K, Option<A>)` values present) without
+//! building new batches, which the wrapper is unable to do.
+//!
+//! As with `val_project`, the underlying trace is ordered and consolidated by its full values, and
+//! the function must be order-preserving on the values it retains: if one retained value precedes
+//! another, its projection must not follow the other's. Distinct values may share a projection, and
+//! the wrapped cursors may then present the same value at several consecutive positions. Keys all of
+//! whose values are discarded are still presented, with no values.
+
+use std::marker::PhantomData;
+
+use timely::progress::frontier::AntichainRef;
+
+use crate::trace::{TraceReader, BatchReader, Description};
+use crate::trace::cursor::Cursor;
+
+/// Wrapper to provide a trace with filtered and projected values.
+pub struct TraceFilterMap<Tr, F, A: ?Sized> {
+    trace: Tr,
+    logic: F,
+    phantom: PhantomData<A>,
+}
+
+impl<Tr, F, A> Clone for TraceFilterMap<Tr, F, A>
+where
+    Tr: TraceReader+Clone,
+    F: Clone,
+    A: ?Sized,
+{
+    fn clone(&self) -> Self {
+        TraceFilterMap {
+            trace: self.trace.clone(),
+            logic: self.logic.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<Tr, F, A> TraceReader for TraceFilterMap<Tr, F, A>
+where
+    Tr: TraceReader,
+    Tr::Batch: Clone,
+    A: Ord+?Sized+'static,
+    F: for<'a> Fn(Tr::Val<'a>)->Option<&'a A>+Clone+'static,
+{
+    type Key<'a> = Tr::Key<'a>;
+    type Val<'a> = &'a A;
+    type Time = Tr::Time;
+    type TimeGat<'a> = Tr::TimeGat<'a>;
+    type Diff = Tr::Diff;
+    type DiffGat<'a> = Tr::DiffGat<'a>;
+
+    type Batch = BatchFilterMap<Tr::Batch, F, A>;
+    type Storage = Tr::Storage;
+    type Cursor = CursorFilterMap<Tr::Cursor, F, A>;
+
+    fn map_batches<F2: FnMut(&Self::Batch)>(&self, mut f: F2) {
+        let logic = self.logic.clone();
+        self.trace
+            .map_batches(|batch| f(&Self::Batch::make_from(batch.clone(), logic.clone())))
+    }
+
+    fn set_logical_compaction(&mut self, frontier: AntichainRef<Tr::Time>) { self.trace.set_logical_compaction(frontier) }
+    fn get_logical_compaction(&mut self) -> AntichainRef<Tr::Time> { self.trace.get_logical_compaction() }
+
+    fn set_physical_compaction(&mut self, frontier: AntichainRef<Tr::Time>) { self.trace.set_physical_compaction(frontier) }
+    fn get_physical_compaction(&mut self) -> AntichainRef<Tr::Time> { self.trace.get_physical_compaction() }
+
+    fn cursor_through(&mut self, upper: AntichainRef<Tr::Time>) -> Option<(Self::Cursor, Self::Storage)> {
+        self.trace.cursor_through(upper).map(|(x,y)| {
+            let mut cursor = CursorFilterMap::new(x, self.logic.clone());
+            cursor.skip_discarded(&y);
+            (cursor, y)
+        })
+    }
+}
+
+impl<Tr, F, A> TraceFilterMap<Tr, F, A>
+where
+    Tr: TraceReader,
+    A: ?Sized,
+{
+    /// Makes a new trace wrapper
+    pub fn make_from(trace: Tr, logic: F) -> Self {
+        TraceFilterMap {
+            trace,
+            logic,
+            phantom: PhantomData,
+        }
+    }
+}
+
+
+/// Wrapper to provide a batch with filtered and projected values.
+pub struct BatchFilterMap<B, F, A: ?Sized> {
+    batch: B,
+    logic: F,
+    phantom: PhantomData<A>,
+}
+
+impl<B: Clone, F: Clone, A: ?Sized> Clone for BatchFilterMap<B, F, A> {
+    fn clone(&self) -> Self {
+        BatchFilterMap {
+            batch: self.batch.clone(),
+            logic: self.logic.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<B, F, A> BatchReader for BatchFilterMap<B, F, A>
+where
+    B: BatchReader,
+    A: Ord+?Sized+'static,
+    F: for<'a> Fn(B::Val<'a>)->Option<&'a A>+Clone+'static,
+{
+    type Key<'a> = B::Key<'a>;
+    type Val<'a> = &'a A;
+    type Time = B::Time;
+    type TimeGat<'a> = B::TimeGat<'a>;
+    type Diff = B::Diff;
+    type DiffGat<'a> = B::DiffGat<'a>;
+
+    type Cursor = BatchCursorFilterMap<B::Cursor, F, A>;
+
+    fn cursor(&self) -> Self::Cursor {
+        let mut cursor = BatchCursorFilterMap::new(self.batch.cursor(), self.logic.clone());
+        cursor.skip_discarded(self);
+        cursor
+    }
+    fn len(&self) -> usize { self.batch.len() }
+    fn description(&self) -> &Description<B::Time> { self.batch.description() }
+}
+
+impl<B, F, A> BatchFilterMap<B, F, A>
+where
+    B: BatchReader,
+    A: ?Sized,
+{
+    /// Makes a new batch wrapper
+    pub fn make_from(batch: B, logic: F) -> Self {
+        BatchFilterMap {
+            batch,
+            logic,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Wrapper to provide a cursor with filtered and projected values.
+pub struct CursorFilterMap<C, F, A: ?Sized> {
+    cursor: C,
+    logic: F,
+    phantom: PhantomData<A>,
+}
+
+impl<C, F, A: ?Sized> CursorFilterMap<C, F, A> {
+    fn new(cursor: C, logic: F) -> Self {
+        CursorFilterMap {
+            cursor,
+            logic,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<C, F, A> CursorFilterMap<C, F, A>
+where
+    C: Cursor,
+    A: Ord+?Sized+'static,
+    F: for<'a> Fn(C::Val<'a>)->Option<&'a A>+'static,
+{
+    /// Steps past discarded values, so that any valid value is retained.
+    fn skip_discarded(&mut self, storage: &C::Storage) {
+        while self.cursor.val_valid(storage) && (self.logic)(self.cursor.val(storage)).is_none() {
+            self.cursor.step_val(storage);
+        }
+    }
+}
+
+impl<C, F, A> Cursor for CursorFilterMap<C, F, A>
+where
+    C: Cursor,
+    A: Ord+?Sized+'static,
+    F: for<'a> Fn(C::Val<'a>)->Option<&'a A>+'static,
+{
+    type Key<'a> = C::Key<'a>;
+    type Val<'a> = &'a A;
+    type Time = C::Time;
+    type TimeGat<'a> = C::TimeGat<'a>;
+    type Diff = C::Diff;
+    type DiffGat<'a> = C::DiffGat<'a>;
+
+    type Storage = C::Storage;
+
+    #[inline] fn key_valid(&self, storage: &Self::Storage) -> bool { self.cursor.key_valid(storage) }
+    #[inline] fn val_valid(&self, storage: &Self::Storage) -> bool { self.cursor.val_valid(storage) }
+
+    #[inline] fn key<'a>(&self, storage: &'a Self::Storage) -> Self::Key<'a> { self.cursor.key(storage) }
+    #[inline] fn val<'a>(&self, storage: &'a Self::Storage) -> Self::Val<'a> { (self.logic)(self.cursor.val(storage)).expect("filter_map: discarded value presented") }
+
+    #[inline]
+    fn map_times<L: FnMut(Self::TimeGat<'_>, Self::DiffGat<'_>)>(&mut self, storage: &Self::Storage, logic: L) {
+        self.cursor.map_times(storage, logic)
+    }
+
+    #[inline] fn step_key(&mut self, storage: &Self::Storage) { self.cursor.step_key(storage); self.skip_discarded(storage); }
+    #[inline] fn seek_key(&mut self, storage: &Self::Storage, key: Self::Key<'_>) { self.cursor.seek_key(storage, key); self.skip_discarded(storage); }
+
+    #[inline] fn step_val(&mut self, storage: &Self::Storage) { self.cursor.step_val(storage); self.skip_discarded(storage); }
+    #[inline]
+    fn seek_val(&mut self, storage: &Self::Storage, val: Self::Val<'_>) {
+        // Relies on the function being order-preserving, as the underlying values are not ordered by `A`.
+        while self.cursor.val_valid(storage) && (self.logic)(self.cursor.val(storage)).map(|a| a < val).unwrap_or(true) {
+            self.cursor.step_val(storage);
+        }
+    }
+
+    #[inline] fn rewind_keys(&mut self, storage: &Self::Storage) { self.cursor.rewind_keys(storage); self.skip_discarded(storage); }
+    #[inline] fn rewind_vals(&mut self, storage: &Self::Storage) { self.cursor.rewind_vals(storage); self.skip_discarded(storage); }
+}
+
+
+/// Wrapper to provide a batch cursor with filtered and projected values.
+pub struct BatchCursorFilterMap<C, F, A: ?Sized> {
+    cursor: C,
+    logic: F,
+    phantom: PhantomData<A>,
+}
+
+impl<C, F, A: ?Sized> BatchCursorFilterMap<C, F, A> {
+    fn new(cursor: C, logic: F) -> Self {
+        BatchCursorFilterMap {
+            cursor,
+            logic,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: Cursor, F, A> BatchCursorFilterMap<C, F, A>
+where
+    A: Ord+?Sized+'static,
+    F: for<'a> Fn(C::Val<'a>)->Option<&'a A>+'static,
+{
+    /// Steps past discarded values, so that any valid value is retained.
+    fn skip_discarded(&mut self, storage: &BatchFilterMap<C::Storage, F, A>) {
+        while self.cursor.val_valid(&storage.batch) && (self.logic)(self.cursor.val(&storage.batch)).is_none() {
+            self.cursor.step_val(&storage.batch);
+        }
+    }
+}
+
+impl<C: Cursor, F, A> Cursor for BatchCursorFilterMap<C, F, A>
+where
+    A: Ord+?Sized+'static,
+    F: for<'a> Fn(C::Val<'a>)->Option<&'a A>+'static,
+{
+    type Key<'a> = C::Key<'a>;
+    type Val<'a> = &'a A;
+    type Time = C::Time;
+    type TimeGat<'a> = C::TimeGat<'a>;
+    type Diff = C::Diff;
+    type DiffGat<'a> = C::DiffGat<'a>;
+
+    type Storage = BatchFilterMap<C::Storage, F, A>;
+
+    #[inline] fn key_valid(&self, storage: &Self::Storage) -> bool { self.cursor.key_valid(&storage.batch) }
+    #[inline] fn val_valid(&self, storage: &Self::Storage) -> bool { self.cursor.val_valid(&storage.batch) }
+
+    #[inline] fn key<'a>(&self, storage: &'a Self::Storage) -> Self::Key<'a> { self.cursor.key(&storage.batch) }
+    #[inline] fn val<'a>(&self, storage: &'a Self::Storage) -> Self::Val<'a> { (self.logic)(self.cursor.val(&storage.batch)).expect("filter_map: discarded value presented") }
+
+    #[inline]
+    fn map_times<L: FnMut(Self::TimeGat<'_>, Self::DiffGat<'_>)>(&mut self, storage: &Self::Storage, logic: L) {
+        self.cursor.map_times(&storage.batch, logic)
+    }
+
+    #[inline] fn step_key(&mut self, storage: &Self::Storage) { self.cursor.step_key(&storage.batch); self.skip_discarded(storage); }
+    #[inline] fn seek_key(&mut self, storage: &Self::Storage, key: Self::Key<'_>) { self.cursor.seek_key(&storage.batch, key); self.skip_discarded(storage); }
+
+    #[inline] fn step_val(&mut self, storage: &Self::Storage) { self.cursor.step_val(&storage.batch); self.skip_discarded(storage); }
+    #[inline]
+    fn seek_val(&mut self, storage: &Self::Storage, val: Self::Val<'_>) {
+        // Relies on the function being order-preserving, as the underlying values are not ordered by `A`.
+        while self.cursor.val_valid(&storage.batch) && (self.logic)(self.cursor.val(&storage.batch)).map(|a| a < val).unwrap_or(true) {
+            self.cursor.step_val(&storage.batch);
+        }
+    }
+
+    #[inline] fn rewind_keys(&mut self, storage: &Self::Storage) { self.cursor.rewind_keys(&storage.batch); self.skip_discarded(storage); }
+    #[inline] fn rewind_vals(&mut self, storage: &Self::Storage) { self.cursor.rewind_vals(&storage.batch); self.skip_discarded(storage); }
+}
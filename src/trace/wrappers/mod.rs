@@ -6,4 +6,6 @@ pub mod frontier;
 pub mod rc;
 
 pub mod filter;
+pub mod val_project;
+pub mod filter_map;
 pub mod freeze;
@@ -0,0 +1,262 @@
+//! Wrapper for a trace whose values are projected to a component.
+//!
+//! The wrapper presents each value through a projection that borrows from the value, which allows
+//! an arrangement of `(K, (A, B))` to be read as an arrangement of `(K, A)` without copying data.
+//!
+//! The underlying trace is ordered and consolidated by its full values, and the projection must be
+//! monotone with respect to this order (for example, the projection onto the first component of a
+//! tuple). Distinct values may share a projection, and so the wrapped cursors may present the same
+//! value at several consecutive positions. Consumers that accumulate updates by value (for example
+//! `join_core`) are unaffected, but consumers that expect distinct values should consolidate first.
+
+use std::marker::PhantomData;
+
+use timely::progress::frontier::AntichainRef;
+
+use crate::trace::{TraceReader, BatchReader, Description};
+use crate::trace::cursor::Cursor;
+
+/// Wrapper to provide a trace with projected values.
+pub struct TraceValProject<Tr, F, A: ?Sized> {
+    trace: Tr,
+    logic: F,
+    phantom: PhantomData<A>,
+}
+
+impl<Tr, F, A> Clone for TraceValProject<Tr, F, A>
+where
+    Tr: TraceReader+Clone,
+    F: Clone,
+    A: ?Sized,
+{
+    fn clone(&self) -> Self {
+        TraceValProject {
+            trace: self.trace.clone(),
+            logic: self.logic.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<Tr, F, A> TraceReader for TraceValProject<Tr, F, A>
+where
+    Tr: TraceReader,
+    Tr::Batch: Clone,
+    A: Ord+?Sized+'static,
+    F: for<'a> Fn(Tr::Val<'a>)->&'a A+Clone+'static,
+{
+    type Key<'a> = Tr::Key<'a>;
+    type Val<'a> = &'a A;
+    type Time = Tr::Time;
+    type TimeGat<'a> = Tr::TimeGat<'a>;
+    type Diff = Tr::Diff;
+    type DiffGat<'a> = Tr::DiffGat<'a>;
+
+    type Batch = BatchValProject<Tr::Batch, F, A>;
+    type Storage = Tr::Storage;
+    type Cursor = CursorValProject<Tr::Cursor, F, A>;
+
+    fn map_batches<F2: FnMut(&Self::Batch)>(&self, mut f: F2) {
+        let logic = self.logic.clone();
+        self.trace
+            .map_batches(|batch| f(&Self::Batch::make_from(batch.clone(), logic.clone())))
+    }
+
+    fn set_logical_compaction(&mut self, frontier: AntichainRef<Tr::Time>) { self.trace.set_logical_compaction(frontier) }
+    fn get_logical_compaction(&mut self) -> AntichainRef<Tr::Time> { self.trace.get_logical_compaction() }
+
+    fn set_physical_compaction(&mut self, frontier: AntichainRef<Tr::Time>) { self.trace.set_physical_compaction(frontier) }
+    fn get_physical_compaction(&mut self) -> AntichainRef<Tr::Time> { self.trace.get_physical_compaction() }
+
+    fn cursor_through(&mut self, upper: AntichainRef<Tr::Time>) -> Option<(Self::Cursor, Self::Storage)> {
+        self.trace.cursor_through(upper).map(|(x,y)| (CursorValProject::new(x, self.logic.clone()), y))
+    }
+}
+
+impl<Tr, F, A> TraceValProject<Tr, F, A>
+where
+    Tr: TraceReader,
+    A: ?Sized,
+{
+    /// Makes a new trace wrapper
+    pub fn make_from(trace: Tr, logic: F) -> Self {
+        TraceValProject {
+            trace,
+            logic,
+            phantom: PhantomData,
+        }
+    }
+}
+
+
+/// Wrapper to provide a batch with projected values.
+pub struct BatchValProject<B, F, A: ?Sized> {
+    batch: B,
+    logic: F,
+    phantom: PhantomData<A>,
+}
+
+impl<B: Clone, F: Clone, A: ?Sized> Clone for BatchValProject<B, F, A> {
+    fn clone(&self) -> Self {
+        BatchValProject {
+            batch: self.batch.clone(),
+            logic: self.logic.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<B, F, A> BatchReader for BatchValProject<B, F, A>
+where
+    B: BatchReader,
+    A: Ord+?Sized+'static,
+    F: for<'a> Fn(B::Val<'a>)->&'a A+Clone+'static,
+{
+    type Key<'a> = B::Key<'a>;
+    type Val<'a> = &'a A;
+    type Time = B::Time;
+    type TimeGat<'a> = B::TimeGat<'a>;
+    type Diff = B::Diff;
+    type DiffGat<'a> = B::DiffGat<'a>;
+
+    type Cursor = BatchCursorValProject<B::Cursor, F, A>;
+
+    fn cursor(&self) -> Self::Cursor {
+        BatchCursorValProject::new(self.batch.cursor(), self.logic.clone())
+    }
+    fn len(&self) -> usize { self.batch.len() }
+    fn description(&self) -> &Description<B::Time> { self.batch.description() }
+}
+
+impl<B, F, A> BatchValProject<B, F, A>
+where
+    B: BatchReader,
+    A: ?Sized,
+{
+    /// Makes a new batch wrapper
+    pub fn make_from(batch: B, logic: F) -> Self {
+        BatchValProject {
+            batch,
+            logic,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Wrapper to provide a cursor with projected values.
+pub struct CursorValProject<C, F, A: ?Sized> {
+    cursor: C,
+    logic: F,
+    phantom: PhantomData<A>,
+}
+
+impl<C, F, A: ?Sized> CursorValProject<C, F, A> {
+    fn new(cursor: C, logic: F) -> Self {
+        CursorValProject {
+            cursor,
+            logic,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<C, F, A> Cursor for CursorValProject<C, F, A>
+where
+    C: Cursor,
+    A: Ord+?Sized+'static,
+    F: for<'a> Fn(C::Val<'a>)->&'a A+'static,
+{
+    type Key<'a> = C::Key<'a>;
+    type Val<'a> = &'a A;
+    type Time = C::Time;
+    type TimeGat<'a> = C::TimeGat<'a>;
+    type Diff = C::Diff;
+    type DiffGat<'a> = C::DiffGat<'a>;
+
+    type Storage = C::Storage;
+
+    #[inline] fn key_valid(&self, storage: &Self::Storage) -> bool { self.cursor.key_valid(storage) }
+    #[inline] fn val_valid(&self, storage: &Self::Storage) -> bool { self.cursor.val_valid(storage) }
+
+    #[inline] fn key<'a>(&self, storage: &'a Self::Storage) -> Self::Key<'a> { self.cursor.key(storage) }
+    #[inline] fn val<'a>(&self, storage: &'a Self::Storage) -> Self::Val<'a> { (self.logic)(self.cursor.val(storage)) }
+
+    #[inline]
+    fn map_times<L: FnMut(Self::TimeGat<'_>, Self::DiffGat<'_>)>(&mut self, storage: &Self::Storage, logic: L) {
+        self.cursor.map_times(storage, logic)
+    }
+
+    #[inline] fn step_key(&mut self, storage: &Self::Storage) { self.cursor.step_key(storage) }
+    #[inline] fn seek_key(&mut self, storage: &Self::Storage, key: Self::Key<'_>) { self.cursor.seek_key(storage, key) }
+
+    #[inline] fn step_val(&mut self, storage: &Self::Storage) { self.cursor.step_val(storage) }
+    #[inline]
+    fn seek_val(&mut self, storage: &Self::Storage, val: Self::Val<'_>) {
+        // Relies on the projection being monotone, as the underlying values are not ordered by `A`.
+        while self.cursor.val_valid(storage) && (self.logic)(self.cursor.val(storage)) < val {
+            self.cursor.step_val(storage);
+        }
+    }
+
+    #[inline] fn rewind_keys(&mut self, storage: &Self::Storage) { self.cursor.rewind_keys(storage) }
+    #[inline] fn rewind_vals(&mut self, storage: &Self::Storage) { self.cursor.rewind_vals(storage) }
+}
+
+
+/// Wrapper to provide a batch cursor with projected values.
+pub struct BatchCursorValProject<C, F, A: ?Sized> {
+    cursor: C,
+    logic: F,
+    phantom: PhantomData<A>,
+}
+
+impl<C, F, A: ?Sized> BatchCursorValProject<C, F, A> {
+    fn new(cursor: C, logic: F) -> Self {
+        BatchCursorValProject {
+            cursor,
+            logic,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: Cursor, F, A> Cursor for BatchCursorValProject<C, F, A>
+where
+    A: Ord+?Sized+'static,
+    F: for<'a> Fn(C::Val<'a>)->&'a A+'static,
+{
+    type Key<'a> = C::Key<'a>;
+    type Val<'a> = &'a A;
+    type Time = C::Time;
+    type TimeGat<'a> = C::TimeGat<'a>;
+    type Diff = C::Diff;
+    type DiffGat<'a> = C::DiffGat<'a>;
+
+    type Storage = BatchValProject<C::Storage, F, A>;
+
+    #[inline] fn key_valid(&self, storage: &Self::Storage) -> bool { self.cursor.key_valid(&storage.batch) }
+    #[inline] fn val_valid(&self, storage: &Self::Storage) -> bool { self.cursor.val_valid(&storage.batch) }
+
+    #[inline] fn key<'a>(&self, storage: &'a Self::Storage) -> Self::Key<'a> { self.cursor.key(&storage.batch) }
+    #[inline] fn val<'a>(&self, storage: &'a Self::Storage) -> Self::Val<'a> { (self.logic)(self.cursor.val(&storage.batch)) }
+
+    #[inline]
+    fn map_times<L: FnMut(Self::TimeGat<'_>, Self::DiffGat<'_>)>(&mut self, storage: &Self::Storage, logic: L) {
+        self.cursor.map_times(&storage.batch, logic)
+    }
+
+    #[inline] fn step_key(&mut self, storage: &Self::Storage) { self.cursor.step_key(&storage.batch) }
+    #[inline] fn seek_key(&mut self, storage: &Self::Storage, key: Self::Key<'_>) { self.cursor.seek_key(&storage.batch, key) }
+
+    #[inline] fn step_val(&mut self, storage: &Self::Storage) { self.cursor.step_val(&storage.batch) }
+    #[inline]
+    fn seek_val(&mut self, storage: &Self::Storage, val: Self::Val<'_>) {
+        // Relies on the projection being monotone, as the underlying values are not ordered by `A`.
+        while self.cursor.val_valid(&storage.batch) && (self.logic)(self.cursor.val(&storage.batch)) < val {
+            self.cursor.step_val(&storage.batch);
+        }
+    }
+
+    #[inline] fn rewind_keys(&mut self, storage: &Self::Storage) { self.cursor.rewind_keys(&storage.batch) }
+    #[inline] fn rewind_vals(&mut self, storage: &Self::Storage) { self.cursor.rewind_vals(&storage.batch) }
+}
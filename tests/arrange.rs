@@ -0,0 +1,174 @@
+use std::sync::{Arc, Mutex};
+
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+use timely::logging::{TimelyEvent, TimelyEventBuilder};
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::arrange::{Arrange, ArrangementRegistry};
+use differential_dataflow::trace::implementations::{ValBatcher, ValBuilder, ValSpine};
+use differential_dataflow::consolidation::consolidate_updates;
+
+type Batcher = ValBatcher<u32, u32, u32, isize>;
+type Builder = ValBuilder<u32, u32, u32, isize>;
+type Spine = ValSpine<u32, u32, u32, isize>;
+
+#[test]
+fn arrange_or_reuse_shares_spine() {
+
+    let names = Arc::new(Mutex::new(Vec::new()));
+    let names2 = Arc::clone(&names);
+
+    let (first, second) = timely::execute_directly(move |worker| {
+
+        // record the names of constructed operators.
+        worker
+            .log_register()
+            .insert::<TimelyEventBuilder,_>("timely", move |_time, data| {
+                if let Some(data) = data {
+                    for (_, event) in data.iter() {
+                        if let TimelyEvent::Operates(operates) = event {
+                            names2.lock().unwrap().push(operates.name.clone());
+                        }
+                    }
+                }
+            });
+
+        let (mut input, captures) = worker.dataflow::<u32,_,_>(|scope| {
+            let registry = ArrangementRegistry::default();
+            let (input, data) = scope.new_collection::<(u32, u32), isize>();
+            let first = data.arrange_or_reuse::<Batcher, Builder, Spine, _>(&registry, "data", "ArrangeShared");
+            let second = data.arrange_or_reuse::<Batcher, Builder, Spine, _>(&registry, "data", "ArrangeShared");
+            assert_eq!(registry.borrow().len(), 1);
+            let captures = (
+                first.as_collection(|k, v| (*k, *v)).inner.capture(),
+                second.as_collection(|k, v| (*k, *v)).inner.capture(),
+            );
+            (input, captures)
+        });
+
+        input.insert((0, 1));
+        input.insert((1, 2));
+        input.advance_to(1);
+        input.remove((0, 1));
+
+        captures
+    });
+
+    let names = names.lock().unwrap();
+    assert_eq!(names.iter().filter(|name| *name == "ArrangeShared").count(), 1);
+
+    let mut first = first.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    let mut second = second.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    consolidate_updates(&mut first);
+    consolidate_updates(&mut second);
+    assert_eq!(first, vec![((0, 1), 0, 1), ((0, 1), 1, -1), ((1, 2), 0, 1)]);
+    assert_eq!(first, second);
+}
+
+/// Counts the values of each key with three workers, arranging keys distributed by `hash`.
+fn count_hashed(hash: fn(&u32) -> u64) -> Vec<((u32, usize), u32, isize)> {
+
+    use differential_dataflow::operators::Reduce;
+    use differential_dataflow::operators::arrange::ArrangeByKey;
+
+    let captured = timely::execute(timely::Config::process(3), move |worker| {
+
+        let index = worker.index() as u32;
+        let peers = worker.peers() as u32;
+        let (mut input, captured) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<(u32, u32), isize>();
+            let captured =
+            data.arrange_by_key_hashed(hash)
+                .reduce(|_key, input, output| output.push((input.len(), 1)))
+                .inner
+                .capture();
+            (input, captured)
+        });
+
+        // key zero is hot, and each worker introduces its share of the updates.
+        for round in 0 .. 3u32 {
+            input.advance_to(round);
+            for x in (0 .. 100u32).filter(|x| x % peers == index) {
+                let key = if x % 2 == 0 { 0 } else { x % 7 };
+                input.update((key, x + round), if round == 2 && x % 4 == 1 { -1 } else { 1 });
+            }
+        }
+
+        captured
+    }).unwrap();
+
+    let mut updates = captured
+        .join()
+        .into_iter()
+        .flat_map(|captured| captured.unwrap().extract())
+        .flat_map(|(_, list)| list)
+        .collect::<Vec<_>>();
+    consolidate_updates(&mut updates);
+    updates
+}
+
+#[test]
+fn arrange_by_key_hashed_placement_only() {
+    let natural = count_hashed(|key| *key as u64);
+    let salted = count_hashed(|key| (*key as u64 ^ 0xDEAD_BEEF).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 7);
+    assert!(!natural.is_empty());
+    assert_eq!(natural, salted);
+}
+
+/// Arranges 1000 individually sent records, reporting the number of batches formed and the arranged updates.
+fn arrange_singletons(coalesce: Option<(usize, usize)>) -> (usize, Vec<((u32, u32), u32, isize)>) {
+
+    use std::rc::Rc;
+    use std::cell::Cell;
+    use timely::dataflow::operators::Inspect;
+    use differential_dataflow::operators::arrange::ArrangeByKey;
+
+    timely::execute_directly(move |worker| {
+
+        let batches = Rc::new(Cell::new(0));
+        let batches2 = Rc::clone(&batches);
+
+        let (mut input, captured) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<(u32, u32), isize>();
+            let arranged = match coalesce {
+                Some((min_batch, max_deferred)) => data.arrange_coalesced(min_batch, max_deferred),
+                None => data.arrange_by_key(),
+            };
+            arranged.stream.inspect(move |_batch| batches2.set(batches2.get() + 1));
+            (input, arranged.as_collection(|k, v| (*k, *v)).inner.capture())
+        });
+
+        for round in 0 .. 1000u32 {
+            input.insert((round % 10, round));
+            input.advance_to(round + 1);
+            input.flush();
+            worker.step();
+        }
+        drop(input);
+        while worker.step() { }
+
+        let mut updates = captured.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+        consolidate_updates(&mut updates);
+        (batches.get(), updates)
+    })
+}
+
+#[test]
+fn arrange_coalesced_forms_fewer_batches() {
+    let (batches, updates) = arrange_singletons(None);
+    let (coalesced_batches, coalesced_updates) = arrange_singletons(Some((100, usize::MAX)));
+    assert_eq!(updates.len(), 1000);
+    assert_eq!(updates, coalesced_updates);
+    assert!(batches >= 1000);
+    assert!(coalesced_batches <= 20, "formed {} batches", coalesced_batches);
+}
+
+#[test]
+fn arrange_coalesced_bounds_deferral() {
+    let (batches, updates) = arrange_singletons(None);
+    let (coalesced_batches, coalesced_updates) = arrange_singletons(Some((usize::MAX, 10)));
+    assert_eq!(updates, coalesced_updates);
+    assert!(coalesced_batches < batches);
+    assert!(coalesced_batches >= 50, "formed {} batches", coalesced_batches);
+}
@@ -0,0 +1,62 @@
+use differential_dataflow::input::Input;
+
+#[test]
+fn assert_empty_with_transient() {
+    timely::execute_directly(|worker| {
+        let mut input = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection();
+            data.assert_empty_with("transient updates");
+            input
+        });
+
+        // the record is inserted and retracted before its time completes.
+        input.insert(1u32);
+        input.flush();
+        worker.step();
+        input.remove(1u32);
+        input.advance_to(1);
+        input.flush();
+        worker.step();
+    });
+}
+
+#[test]
+#[should_panic(expected = "residual updates: non-empty collection: 2 at time 1 with difference -1")]
+fn assert_empty_with_residual() {
+    timely::execute_directly(|worker| {
+        let mut input = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection();
+            data.assert_empty_with("residual updates");
+            input
+        });
+
+        input.advance_to(1);
+        input.update(2u32, -1);
+    });
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "un-consolidated updates for 1 at time 0")]
+fn debug_assert_consolidated_duplicate() {
+    use timely::dataflow::operators::ToStream;
+    use differential_dataflow::AsCollection;
+    timely::example(|scope| {
+        vec![(1u32, 0u64, 1isize), (1, 0, 1)]
+            .to_stream(scope)
+            .as_collection()
+            .debug_assert_consolidated();
+    });
+}
+
+#[test]
+fn debug_assert_consolidated_distinct_times() {
+    use timely::dataflow::operators::ToStream;
+    use differential_dataflow::AsCollection;
+    timely::example(|scope| {
+        vec![(1u32, 0u64, 1isize), (1, 1, 1), (2, 0, 1)]
+            .to_stream(scope)
+            .as_collection()
+            .debug_assert_consolidated();
+    });
+}
@@ -0,0 +1,196 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use timely::progress::frontier::{Antichain, AntichainRef};
+
+use differential_dataflow::trace::{Batcher, BatchReader};
+use differential_dataflow::trace::implementations::ValBuilder;
+use differential_dataflow::trace::implementations::chunker::VecChunker;
+use differential_dataflow::trace::implementations::merge_batcher::{MergeBatcher, Merger, VecMerger};
+
+type Update = ((u64, u64), u64, isize);
+
+/// The number of updates presented to `extract` by `CountingMerger`.
+static EXTRACTED: AtomicUsize = AtomicUsize::new(0);
+
+/// A `VecMerger` that counts the updates it inspects when extracting.
+#[derive(Default)]
+struct CountingMerger {
+    inner: VecMerger<(u64, u64), u64, isize>,
+}
+
+impl Merger for CountingMerger {
+    type Chunk = Vec<Update>;
+    type Time = u64;
+    fn merge(&mut self, list1: Vec<Self::Chunk>, list2: Vec<Self::Chunk>, output: &mut Vec<Self::Chunk>, stash: &mut Vec<Self::Chunk>) {
+        self.inner.merge(list1, list2, output, stash)
+    }
+    fn extract(
+        &mut self,
+        merged: Vec<Self::Chunk>,
+        upper: AntichainRef<Self::Time>,
+        frontier: &mut Antichain<Self::Time>,
+        readied: &mut Vec<Self::Chunk>,
+        kept: &mut Vec<Self::Chunk>,
+        stash: &mut Vec<Self::Chunk>,
+    ) {
+        EXTRACTED.fetch_add(merged.iter().map(|chunk| chunk.len()).sum(), Ordering::SeqCst);
+        self.inner.extract(merged, upper, frontier, readied, kept, stash)
+    }
+    fn account(chunk: &Self::Chunk) -> (usize, usize, usize, usize) {
+        VecMerger::<(u64, u64), u64, isize>::account(chunk)
+    }
+}
+
+type CountingBatcher = MergeBatcher<Vec<Update>, VecChunker<Update>, CountingMerger>;
+type Builder = ValBuilder<u64, u64, u64, isize>;
+
+#[test]
+fn seal_skips_future_updates() {
+
+    let bulk = 100_000u64;
+    let mut batcher = CountingBatcher::new(None, 0);
+
+    // load updates far in the future, which the first seal must inspect once.
+    let mut updates = (0 .. bulk).map(|i| ((i, i), 1_000_000, 1)).collect::<Vec<_>>();
+    batcher.push_container(&mut updates);
+    assert_eq!(batcher.seal::<Builder>(Antichain::from_elem(1)).len(), 0);
+    assert_eq!(batcher.frontier().to_vec(), vec![1_000_000]);
+
+    // advancing through early rounds should not inspect the future updates again.
+    for round in 1 .. 100u64 {
+        let mut updates = (0 .. 10).map(|i| ((i, round), round, 1)).collect::<Vec<_>>();
+        batcher.push_container(&mut updates);
+        let before = EXTRACTED.load(Ordering::SeqCst);
+        assert_eq!(batcher.seal::<Builder>(Antichain::from_elem(round + 1)).len(), 10);
+        let work = EXTRACTED.load(Ordering::SeqCst) - before;
+        assert!(work <= 10, "round {} inspected {} updates", round, work);
+        assert_eq!(batcher.frontier().to_vec(), vec![1_000_000]);
+    }
+
+    // updates that cancel in the future are consolidated once they are sealed.
+    let mut updates = (0 .. 10).map(|i| ((i, i), 1_000_000, -1)).collect::<Vec<_>>();
+    batcher.push_container(&mut updates);
+    assert_eq!(batcher.seal::<Builder>(Antichain::from_elem(100)).len(), 0);
+    assert_eq!(batcher.seal::<Builder>(Antichain::new()).len(), bulk as usize - 10);
+    assert!(batcher.frontier().is_empty());
+}
+
+/// The number of chunks presented to `merge` by `MergeCountingMerger`.
+static MERGED: AtomicUsize = AtomicUsize::new(0);
+
+/// A `VecMerger` that counts the chunks it merges.
+#[derive(Default)]
+struct MergeCountingMerger {
+    inner: VecMerger<(u64, u64), u64, isize>,
+}
+
+impl Merger for MergeCountingMerger {
+    type Chunk = Vec<Update>;
+    type Time = u64;
+    fn merge(&mut self, list1: Vec<Self::Chunk>, list2: Vec<Self::Chunk>, output: &mut Vec<Self::Chunk>, stash: &mut Vec<Self::Chunk>) {
+        MERGED.fetch_add(list1.len() + list2.len(), Ordering::SeqCst);
+        self.inner.merge(list1, list2, output, stash)
+    }
+    fn extract(
+        &mut self,
+        merged: Vec<Self::Chunk>,
+        upper: AntichainRef<Self::Time>,
+        frontier: &mut Antichain<Self::Time>,
+        readied: &mut Vec<Self::Chunk>,
+        kept: &mut Vec<Self::Chunk>,
+        stash: &mut Vec<Self::Chunk>,
+    ) {
+        self.inner.extract(merged, upper, frontier, readied, kept, stash)
+    }
+    fn account(chunk: &Self::Chunk) -> (usize, usize, usize, usize) {
+        VecMerger::<(u64, u64), u64, isize>::account(chunk)
+    }
+}
+
+type MergeCountingBatcher = MergeBatcher<Vec<Update>, VecChunker<Update>, MergeCountingMerger>;
+
+#[test]
+fn fueled_push_bounds_merging() {
+
+    use differential_dataflow::trace::cursor::Cursor;
+
+    let fuel = 100;
+    let mut fueled = MergeCountingBatcher::new(None, 0);
+    fueled.set_fuel(Some(fuel));
+    let mut unfueled = MergeCountingBatcher::new(None, 0);
+
+    // a skewed load, in which all updates concern a few keys, with many values each.
+    let mut max_fueled = 0;
+    let mut max_unfueled = 0;
+    for round in 0 .. 1_000u64 {
+        let updates = (0 .. 1_000u64).map(|i| ((i % 7, i * 1_000 + round), i % 3, if i % 5 == 0 { -1 } else { 1 })).collect::<Vec<_>>();
+        for (batcher, max) in [(&mut fueled, &mut max_fueled), (&mut unfueled, &mut max_unfueled)] {
+            let before = MERGED.load(Ordering::SeqCst);
+            batcher.push_container(&mut updates.clone());
+            *max = std::cmp::max(*max, MERGED.load(Ordering::SeqCst) - before);
+        }
+    }
+
+    assert!(max_fueled <= fuel, "a push merged {} chunks", max_fueled);
+    assert!(max_unfueled > fuel, "no unfueled push merged more than {} chunks", max_unfueled);
+
+    // sealing produces identical, consolidated batches.
+    let fueled = fueled.seal::<Builder>(Antichain::from_elem(2));
+    let unfueled = unfueled.seal::<Builder>(Antichain::from_elem(2));
+    assert_eq!(fueled.len(), unfueled.len());
+    assert_eq!(fueled.cursor().to_vec::<u64, u64>(&fueled), unfueled.cursor().to_vec::<u64, u64>(&unfueled));
+}
+
+#[test]
+fn holding_pen_logs_far_future_updates() {
+
+    use std::sync::{Arc, Mutex};
+    use timely::dataflow::ProbeHandle;
+    use timely::dataflow::operators::Probe;
+    use differential_dataflow::input::Input;
+    use differential_dataflow::operators::arrange::ArrangeByKey;
+    use differential_dataflow::logging::{DifferentialEvent, DifferentialEventBuilder};
+
+    let held_back = Arc::new(Mutex::new(Vec::new()));
+    let held_back2 = Arc::clone(&held_back);
+
+    timely::execute_directly(move |worker| {
+
+        // record the number of held back updates reported by each batcher event.
+        worker
+            .log_register()
+            .insert::<DifferentialEventBuilder,_>("differential/arrange", move |_time, data| {
+                if let Some(data) = data {
+                    for (_, event) in data.iter() {
+                        if let DifferentialEvent::HoldingPen(event) = event {
+                            held_back2.lock().unwrap().push((event.records, event.held_back));
+                        }
+                    }
+                }
+            });
+
+        let mut probe = ProbeHandle::new();
+        let mut input = worker.dataflow::<u64,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<(u64, u64), isize>();
+            data.arrange_by_key().stream.probe_with(&mut probe);
+            input
+        });
+
+        // each round introduces one update for now, and one for the far future.
+        for round in 0 .. 5u64 {
+            input.insert((round, round));
+            input.update_at((round, round), 1_000, 1);
+            input.advance_to(round + 1);
+            input.flush();
+            worker.step_while(|| probe.less_than(input.time()));
+        }
+    });
+
+    // the far future updates accumulate in the holding pen, until the input closes.
+    let held_back = held_back.lock().unwrap();
+    let counts = held_back.iter().map(|(_, held)| *held).collect::<Vec<_>>();
+    let peak = counts.iter().position(|held| *held == 5).expect("five updates never held back");
+    assert!(counts[.. peak].windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(counts[.. peak].contains(&1));
+    assert_eq!(held_back.last(), Some(&(0, 0)));
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "checkpoint")]
+
+use timely::dataflow::operators::generic::OperatorInfo;
+use timely::progress::{Antichain, frontier::AntichainRef};
+
+use differential_dataflow::operators::arrange::{Checkpoint, TraceAgent, TraceWriter};
+use differential_dataflow::trace::implementations::{ValBatcher, ValBuilder, ValSpine};
+use differential_dataflow::trace::{Trace, TraceReader, Batcher};
+use differential_dataflow::trace::cursor::Cursor;
+
+type IntegerTrace = ValSpine<u64, u64, usize, i64>;
+type IntegerBuilder = ValBuilder<u64, u64, usize, i64>;
+type IntegerCheckpoint = Checkpoint<<IntegerTrace as TraceReader>::Batch, usize>;
+
+fn info() -> OperatorInfo { OperatorInfo::new(0, 0, [].into()) }
+
+/// An agent for a trace containing updates at times `0 .. 3`, with batches up to `3`, and its writer.
+fn get_agent() -> (TraceAgent<IntegerTrace>, TraceWriter<IntegerTrace>) {
+    let (agent, mut writer) = TraceAgent::new(IntegerTrace::new(info(), None, None), info(), None);
+    let mut batcher = ValBatcher::<u64,u64,usize,i64>::new(None, 0);
+    batcher.push_container(&mut vec![
+        ((1, 2), 0, 1),
+        ((2, 3), 1, 1),
+        ((2, 3), 2, -1),
+        ((4, 5), 2, 2),
+    ]);
+    for time in 1 .. 4 {
+        writer.insert(batcher.seal::<IntegerBuilder>(Antichain::from_elem(time)), None);
+    }
+    (agent, writer)
+}
+
+/// The contents of the trace, with times advanced to `since` and consolidated, as merging may do.
+fn contents(agent: &mut TraceAgent<IntegerTrace>, since: usize) -> Vec<((u64, u64), Vec<(usize, i64)>)> {
+    let (mut cursor, storage) = agent.cursor();
+    let mut contents = cursor.to_vec(&storage);
+    for (_, times) in contents.iter_mut() {
+        for (time, _) in times.iter_mut() {
+            *time = std::cmp::max(*time, since);
+        }
+        differential_dataflow::consolidation::consolidate(times);
+    }
+    contents
+}
+
+/// Serializes and deserializes `checkpoint`, as if written to and read from disk.
+fn round_trip(checkpoint: &IntegerCheckpoint) -> IntegerCheckpoint {
+    let bytes = bincode::serialize(checkpoint).expect("bincode: serialization failed");
+    bincode::deserialize(&bytes[..]).expect("bincode: deserialization failed")
+}
+
+#[test]
+fn checkpoint_restore_contents() {
+
+    let (mut agent, _writer) = get_agent();
+    agent.set_logical_compaction(AntichainRef::new(&[1]));
+    agent.set_physical_compaction(AntichainRef::new(&[1]));
+
+    let checkpoint = round_trip(&agent.checkpoint());
+    assert_eq!(checkpoint.upper(), Antichain::from_elem(3));
+
+    let (mut restored, mut writer) = TraceAgent::restore(IntegerTrace::new(info(), None, None), checkpoint, AntichainRef::new(&[3]), info(), None).unwrap();
+    assert_eq!(contents(&mut restored, 1), contents(&mut agent, 1));
+    assert_eq!(restored.get_logical_compaction().to_owned(), Antichain::from_elem(1));
+    assert_eq!(restored.get_physical_compaction().to_owned(), Antichain::from_elem(1));
+
+    // the restored trace accepts new batches contiguously from the upper frontier of the checkpoint.
+    let mut batcher = ValBatcher::<u64,u64,usize,i64>::new(None, 0);
+    batcher.push_container(&mut vec![((1, 2), 3, -1)]);
+    writer.insert(batcher.seal::<IntegerBuilder>(Antichain::from_elem(4)), None);
+
+    let mut expected = contents(&mut agent, 1);
+    expected[0].1.push((3, -1));
+    assert_eq!(contents(&mut restored, 1), expected);
+}
+
+#[test]
+fn checkpoint_empty_trace() {
+    let (agent, _writer) = TraceAgent::new(IntegerTrace::new(info(), None, None), info(), None);
+    let checkpoint = round_trip(&agent.checkpoint());
+    assert!(checkpoint.batches().is_empty());
+    assert_eq!(checkpoint.upper(), Antichain::from_elem(0));
+    assert!(TraceAgent::restore(IntegerTrace::new(info(), None, None), checkpoint, AntichainRef::new(&[0]), info(), None).is_ok());
+}
+
+#[test]
+fn checkpoint_rejects_misaligned_upper() {
+    let (agent, _writer) = get_agent();
+    let checkpoint = round_trip(&agent.checkpoint());
+    let result = TraceAgent::restore(IntegerTrace::new(info(), None, None), checkpoint, AntichainRef::new(&[5]), info(), None);
+    assert!(result.is_err());
+}
+
+#[test]
+fn checkpoint_rejects_missing_batches() {
+    let (agent, _writer) = get_agent();
+    let checkpoint = agent.checkpoint();
+    let (mut batches, logical, physical) = checkpoint.into_parts();
+    batches.remove(0);
+    let checkpoint = Checkpoint::new(batches, logical, physical);
+    assert!(checkpoint.validate(AntichainRef::new(&[3])).is_err());
+}
+
+#[test]
+fn checkpoint_shares_batches() {
+    let (agent, _writer) = get_agent();
+    let checkpoint = agent.checkpoint();
+    let (batches, logical, physical) = checkpoint.into_parts();
+
+    // a checkpoint holding each batch twice serializes each batch once, and shares them again.
+    let doubled = batches.iter().flat_map(|batch| vec![batch.clone(), batch.clone()]).collect::<Vec<_>>();
+    let single = Checkpoint::new(batches, logical.clone(), physical.clone());
+    let doubled = Checkpoint::new(doubled, logical, physical);
+
+    let single_bytes = bincode::serialize(&single).expect("bincode: serialization failed");
+    let doubled_bytes = bincode::serialize(&doubled).expect("bincode: serialization failed");
+    assert!(doubled_bytes.len() < single_bytes.len() + 100);
+
+    let restored = round_trip(&doubled);
+    assert_eq!(restored.batches().len(), 6);
+    for pair in restored.batches().chunks(2) {
+        assert!(std::rc::Rc::ptr_eq(&pair[0], &pair[1]));
+    }
+    assert!(!std::rc::Rc::ptr_eq(&restored.batches()[0], &restored.batches()[2]));
+}
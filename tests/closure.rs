@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::algorithms::graphs::closure::transitive_closure;
+
+type Edge = (usize, usize);
+
+/// Runs `transitive_closure` over edge updates, and returns the accumulated closure after each round.
+fn closure_rounds(rounds: Vec<Vec<(Edge, isize)>>) -> Vec<Vec<Edge>> {
+
+    let count = rounds.len();
+
+    let captured = timely::execute_directly(move |worker| {
+
+        let (mut input, captured) = worker.dataflow(|scope| {
+            let (input, edges) = scope.new_collection();
+            let captured = transitive_closure(&edges).inner.capture();
+            (input, captured)
+        });
+
+        for (round, updates) in rounds.into_iter().enumerate() {
+            input.advance_to(round);
+            for (edge, diff) in updates {
+                input.update(edge, diff);
+            }
+        }
+
+        captured
+    });
+
+    let mut updates = captured
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .collect::<Vec<_>>();
+    updates.sort_by_key(|x| x.1);
+
+    let mut results = Vec::new();
+    let mut accum = BTreeMap::new();
+    let mut updates = updates.into_iter().peekable();
+    for round in 0 .. count {
+        while let Some((edge, _time, diff)) = updates.next_if(|x| x.1 <= round) {
+            *accum.entry(edge).or_insert(0) += diff;
+        }
+        accum.retain(|_, diff| *diff != 0);
+        assert!(accum.values().all(|diff| *diff == 1));
+        results.push(accum.keys().cloned().collect());
+    }
+    results
+}
+
+#[test]
+fn closure_path() {
+    let results = closure_rounds(vec![
+        vec![((0, 1), 1), ((1, 2), 1), ((2, 3), 1)],
+    ]);
+    assert_eq!(results[0], vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+}
+
+#[test]
+fn closure_self_loop() {
+    let results = closure_rounds(vec![
+        vec![((0, 0), 1), ((0, 1), 1)],
+        vec![((0, 0), -1)],
+    ]);
+    assert_eq!(results[0], vec![(0, 0), (0, 1)]);
+    assert_eq!(results[1], vec![(0, 1)]);
+}
+
+#[test]
+fn closure_cycle() {
+    let results = closure_rounds(vec![
+        vec![((0, 1), 1), ((1, 2), 1), ((2, 0), 1), ((2, 3), 1)],
+        vec![((2, 0), -1)],
+    ]);
+    assert_eq!(results[0], vec![
+        (0, 0), (0, 1), (0, 2), (0, 3),
+        (1, 0), (1, 1), (1, 2), (1, 3),
+        (2, 0), (2, 1), (2, 2), (2, 3),
+    ]);
+    // removing an edge of the cycle retracts the pairs that depended on it.
+    assert_eq!(results[1], vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+}
+
+#[test]
+fn closure_duplicate_edges() {
+    let results = closure_rounds(vec![
+        vec![((0, 1), 1), ((0, 1), 1), ((1, 2), 1)],
+        vec![((0, 1), -1)],
+        vec![((0, 1), -1)],
+    ]);
+    assert_eq!(results[0], vec![(0, 1), (0, 2), (1, 2)]);
+    assert_eq!(results[1], vec![(0, 1), (0, 2), (1, 2)]);
+    assert_eq!(results[2], vec![(1, 2)]);
+}
@@ -0,0 +1,63 @@
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+use timely::worker::AsWorker;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::collection::concatenate;
+use differential_dataflow::consolidation::consolidate_updates;
+
+type Updates = Vec<(u64, u32, isize)>;
+
+fn extract(captured: std::sync::mpsc::Receiver<timely::dataflow::operators::capture::Event<u32, Updates>>) -> Updates {
+    let mut updates = captured.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    consolidate_updates(&mut updates);
+    updates
+}
+
+#[test]
+fn concatenate_matches_nested_concat() {
+
+    let (flat, nested, operators) = timely::execute_directly(|worker| {
+
+        let (mut input, captures) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<u64, isize>();
+            let parts = (0 .. 5u64).map(|i| data.filter(move |x| x % 5 == i).map(move |x| x + i)).collect::<Vec<_>>();
+            let nested = parts[1 .. ].iter().fold(parts[0].clone(), |acc, part| acc.concat(part));
+            let flat = concatenate(scope, parts);
+            (input, (flat.inner.capture(), nested.inner.capture()))
+        });
+
+        let operators = worker.dataflow::<u32,_,_>(|scope| {
+            let (_input, data) = scope.new_collection::<u64, isize>();
+            let before = scope.peek_identifier();
+            concatenate(scope, (0 .. 5).map(|_| data.clone()));
+            scope.peek_identifier() - before
+        });
+
+        for round in 0 .. 3u32 {
+            input.advance_to(round);
+            for x in 0 .. 20u64 {
+                input.update(x * (round as u64 + 1), if x % 3 == 0 { -1 } else { 1 });
+            }
+        }
+
+        let (flat, nested) = captures;
+        (flat, nested, operators)
+    });
+
+    let flat = extract(flat);
+    assert!(!flat.is_empty());
+    assert_eq!(flat, extract(nested));
+    // five collections are concatenated by a single operator.
+    assert_eq!(operators, 1);
+}
+
+#[test]
+fn concatenate_empty() {
+    let captured = timely::execute_directly(|worker| {
+        worker.dataflow::<u32,_,_>(|scope| {
+            concatenate::<_, u64, isize, Vec<_>, _>(scope, Vec::new()).inner.capture()
+        })
+    });
+    assert!(extract(captured).is_empty());
+}
@@ -0,0 +1,41 @@
+use timely::dataflow::operators::{Capture, ToStream};
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::AsCollection;
+
+#[test]
+fn consolidate_stream_keyed_by_time() {
+
+    let captured = timely::example(|scope| {
+        // all updates arrive in a single batch, at a variety of times.
+        vec![
+            (0u32, 0u64, 1isize),
+            (0, 1, 1),
+            (0, 0, 1),
+            (1, 1, -1),
+            (1, 1, 1),
+            (2, 0, 1),
+            (2, 1, -1),
+        ]
+        .to_stream(scope)
+        .as_collection()
+        .consolidate_stream()
+        .inner
+        .capture()
+    });
+
+    let mut results = captured
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .collect::<Vec<_>>();
+    results.sort();
+
+    // equal `(data, time)` pairs merge and cancellations vanish, but distinct times remain distinct.
+    assert_eq!(results, vec![
+        (0, 0, 2),
+        (0, 1, 1),
+        (2, 0, 1),
+        (2, 1, -1),
+    ]);
+}
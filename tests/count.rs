@@ -0,0 +1,139 @@
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::{CountTotal, SumTotal};
+use differential_dataflow::consolidation::consolidate_updates;
+
+/// Runs `count_total` over updates introduced at their times, and returns the consolidated output updates.
+fn run_count_total(updates: Vec<(u32, usize, isize)>) -> Vec<((u32, isize), usize, isize)> {
+
+    let captured = timely::execute_directly(move |worker| {
+
+        let (mut input, captured) = worker.dataflow(|scope| {
+            let (input, data) = scope.new_collection();
+            let captured = data.count_total().inner.capture();
+            (input, captured)
+        });
+
+        let mut updates = updates;
+        updates.sort_by_key(|x| x.1);
+        for (key, time, diff) in updates {
+            input.advance_to(time);
+            input.update(key, diff);
+        }
+
+        captured
+    });
+
+    let mut updates = captured
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .collect::<Vec<_>>();
+    consolidate_updates(&mut updates);
+    updates
+}
+
+#[test]
+fn count_total_trajectory() {
+    let results = run_count_total(vec![
+        (0, 0, 2),
+        (0, 1, 1),
+        (0, 3, -1),
+        (0, 3, 1),
+        (0, 4, -2),
+    ]);
+    assert_eq!(results, vec![
+        ((0, 1), 4, 1),
+        ((0, 2), 0, 1),
+        ((0, 2), 1, -1),
+        ((0, 3), 1, 1),
+        ((0, 3), 4, -1),
+    ]);
+}
+
+#[test]
+fn count_total_negative() {
+    let results = run_count_total(vec![
+        (0, 0, -1),
+        (0, 1, -1),
+        (0, 2, 3),
+    ]);
+    assert_eq!(results, vec![
+        ((0, -2), 1, 1),
+        ((0, -2), 2, -1),
+        ((0, -1), 0, 1),
+        ((0, -1), 1, -1),
+        ((0, 1), 2, 1),
+    ]);
+}
+
+#[test]
+fn count_total_retraction() {
+    let results = run_count_total(vec![
+        (0, 0, 1),
+        (1, 0, 2),
+        (0, 1, 1),
+        (0, 2, -2),
+        (1, 3, -2),
+    ]);
+    assert_eq!(results, vec![
+        ((0, 1), 0, 1),
+        ((0, 1), 1, -1),
+        ((0, 2), 1, 1),
+        ((0, 2), 2, -1),
+        ((1, 2), 0, 1),
+        ((1, 2), 3, -1),
+    ]);
+
+    // the output consolidates to empty with the input.
+    let mut total = results.into_iter().map(|(data, _time, diff)| (data, diff)).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate(&mut total);
+    assert!(total.is_empty());
+}
+
+#[test]
+fn sum_total_retraction() {
+
+    let captured = timely::execute_directly(move |worker| {
+
+        let (mut input, captured) = worker.dataflow::<usize,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<(u32, u64), isize>();
+            let captured = data.sum_total(|val| *val as i128).inner.capture();
+            (input, captured)
+        });
+
+        input.insert((0, 5));
+        input.update((1, 3), 2);
+        input.advance_to(1);
+        input.insert((0, 7));
+        input.remove((1, 3));
+        input.advance_to(2);
+        input.remove((0, 5));
+        input.advance_to(3);
+        input.remove((0, 7));
+
+        captured
+    });
+
+    let mut results = captured
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .collect::<Vec<_>>();
+    consolidate_updates(&mut results);
+
+    // retractions subtract their contributions, and keys without records are not reported.
+    assert_eq!(results, vec![
+        ((0, 5), 0, 1),
+        ((0, 5), 1, -1),
+        ((0, 7), 2, 1),
+        ((0, 7), 3, -1),
+        ((0, 12), 1, 1),
+        ((0, 12), 2, -1),
+        ((1, 3), 1, 1),
+        ((1, 6), 0, 1),
+        ((1, 6), 1, -1),
+    ]);
+}
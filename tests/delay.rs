@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+
+/// Rounds `time` up to a whole number of seconds.
+fn round_up(time: &Duration) -> Duration {
+    let seconds = time.as_secs() + if time.subsec_nanos() > 0 { 1 } else { 0 };
+    Duration::from_secs(seconds)
+}
+
+#[test]
+fn delay_rounds_and_consolidates() {
+
+    let captured = timely::execute_directly(|worker| {
+
+        let (mut input, captured) = worker.dataflow::<Duration,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<&'static str, isize>();
+            let captured = data.delay(round_up).consolidate().inner.capture();
+            (input, captured)
+        });
+
+        // "a" is inserted and removed within the second ending at one, and should vanish.
+        input.advance_to(Duration::from_millis(100));
+        input.insert("a");
+        input.insert("b");
+        input.advance_to(Duration::from_millis(600));
+        input.remove("a");
+        input.insert("b");
+        // "c" changes in two different seconds, and should be presented at both.
+        input.advance_to(Duration::from_millis(1000));
+        input.insert("c");
+        input.advance_to(Duration::from_millis(1500));
+        input.remove("c");
+
+        captured
+    });
+
+    let mut updates = captured.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    updates.sort();
+
+    // each update appears once, consolidated with others at its whole second.
+    assert_eq!(updates, vec![
+        ("b", Duration::from_secs(1), 2),
+        ("c", Duration::from_secs(1), 1),
+        ("c", Duration::from_secs(2), -1),
+    ]);
+}
+
+#[test]
+#[should_panic]
+fn delay_rejects_earlier_times() {
+    timely::execute_directly(|worker| {
+        let mut input = worker.dataflow::<Duration,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<&'static str, isize>();
+            data.delay(|time: &Duration| time.saturating_sub(Duration::from_secs(1)));
+            input
+        });
+        input.advance_to(Duration::from_secs(2));
+        input.insert("a");
+    });
+}
@@ -0,0 +1,62 @@
+use timely::dataflow::Scope;
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+use timely::order::Product;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::arrange::ArrangeByKey;
+use differential_dataflow::consolidation::consolidate_updates;
+
+/// Enters arranged `(key, val)` pairs into an iterative scope, with `logic` determining each inner time.
+fn enter_with<L>(logic: L) -> Vec<((u64, u64), Product<u64, u64>, isize)>
+where
+    L: Fn(&u64, &u64, &u64) -> Product<u64, u64> + Clone + Send + Sync + 'static,
+{
+    let captured = timely::execute_directly(move |worker| {
+
+        let logic = logic.clone();
+        let (mut input, captured) = worker.dataflow::<u64,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<(u64, u64), isize>();
+            let arranged = data.arrange_by_key();
+            let captured = scope.iterative::<u64,_,_>(|inner| {
+                arranged
+                    .enter_at(inner, move |key: &u64, val: &u64, time: &u64| logic(key, val, time), |time: &Product<u64, u64>| time.outer)
+                    .as_collection(|key, val| (*key, *val))
+                    .inner
+                    .capture()
+            });
+            (input, captured)
+        });
+
+        input.insert((0, 10));
+        input.insert((2, 20));
+        input.advance_to(1);
+        input.insert((1, 30));
+        input.remove((0, 10));
+
+        captured
+    });
+
+    let mut results = captured.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    consolidate_updates(&mut results);
+    results
+}
+
+#[test]
+fn enter_at_per_key_delay() {
+    // each key is delayed in the inner coordinate by its own value.
+    let results = enter_with(|key, _val, time| Product::new(*time, *key));
+    assert_eq!(results, vec![
+        ((0, 10), Product::new(0, 0), 1),
+        ((0, 10), Product::new(1, 0), -1),
+        ((1, 30), Product::new(1, 1), 1),
+        ((2, 20), Product::new(0, 2), 1),
+    ]);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "not in advance of entered time")]
+fn enter_at_rejects_earlier_times() {
+    enter_with(|_key, _val, time| Product::new(time.saturating_sub(1), 0));
+}
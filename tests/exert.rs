@@ -0,0 +1,50 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use timely::dataflow::operators::Probe;
+use timely::dataflow::operators::probe::Handle;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::arrange::ArrangeByKey;
+
+#[test]
+fn arrange_exert_when_idle() {
+
+    let invocations = Arc::new(AtomicUsize::new(0));
+    let invocations2 = Arc::clone(&invocations);
+
+    timely::execute_directly(move |worker| {
+
+        // requests effort for a bounded number of invocations, which should keep the operator scheduled.
+        let invocations3 = Arc::clone(&invocations2);
+        let logic = Arc::new(move |_layers: &[(usize, usize, usize)]| {
+            let count = invocations3.fetch_add(1, Ordering::SeqCst);
+            if count < 1000 { Some(1) } else { None }
+        });
+
+        let mut probe = Handle::new();
+        let mut input = worker.dataflow(|scope| {
+            let (input, data) = scope.new_collection();
+            data.arrange_by_key_named_exert("Exerted", logic)
+                .stream
+                .probe_with(&mut probe);
+            input
+        });
+
+        for round in 0 .. 4usize {
+            input.advance_to(round);
+            input.insert((round, round));
+        }
+        input.advance_to(4);
+        input.flush();
+        worker.step_while(|| probe.less_than(input.time()));
+
+        // the input is now idle, and further steps should still invoke the exertion logic.
+        let before = invocations2.load(Ordering::SeqCst);
+        for _ in 0 .. 10 {
+            worker.step();
+        }
+        let after = invocations2.load(Ordering::SeqCst);
+        assert!(after > before, "exertion logic not invoked while idle: {} invocations before, {} after", before, after);
+    });
+}
@@ -0,0 +1,59 @@
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::arrange::ArrangeByKey;
+use differential_dataflow::consolidation::consolidate_updates;
+
+#[test]
+fn filter_map_imported() {
+
+    let (captured, expected) = timely::execute_directly(|worker| {
+
+        // values are `(kind, name)` pairs, of which we retain the names of kind 1.
+        let (mut input, mut trace, expected) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<(u32, (u32, String)), isize>();
+            let expected =
+            data.filter(|(_key, (kind, _name))| *kind == 1)
+                .map(|(key, (_kind, name))| (key, name))
+                .inner
+                .capture();
+            (input, data.arrange_by_key().trace, expected)
+        });
+
+        let captured = worker.dataflow(move |scope| {
+            let imported = trace.import(scope);
+            drop(trace);
+            imported
+                .filter_map(|(kind, name)| if *kind == 1 { Some(name) } else { None })
+                .as_collection(|key, name: &String| (*key, name.clone()))
+                .inner
+                .capture()
+        });
+
+        // key 0 interleaves retained and discarded values; key 2 has only discarded values.
+        input.insert((0, (0, "a".to_string())));
+        input.insert((0, (1, "b".to_string())));
+        input.insert((0, (2, "c".to_string())));
+        input.insert((0, (1, "d".to_string())));
+        input.insert((1, (1, "e".to_string())));
+        input.insert((2, (0, "f".to_string())));
+        input.advance_to(1);
+        input.remove((0, (1, "b".to_string())));
+        input.insert((1, (1, "g".to_string())));
+        input.insert((2, (2, "h".to_string())));
+        input.advance_to(2);
+        input.remove((1, (1, "e".to_string())));
+        input.remove((2, (0, "f".to_string())));
+
+        (captured, expected)
+    });
+
+    let mut captured = captured.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    let mut expected = expected.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    consolidate_updates(&mut captured);
+    consolidate_updates(&mut expected);
+
+    assert_eq!(expected.len(), 6);
+    assert_eq!(captured, expected);
+}
@@ -0,0 +1,91 @@
+use timely::dataflow::operators::generic::OperatorInfo;
+use timely::progress::Antichain;
+
+use differential_dataflow::trace::implementations::BatchContainer;
+use differential_dataflow::trace::implementations::huffman_container::HuffmanContainer;
+use differential_dataflow::trace::implementations::ord_neu::{HuffmanBatcher, HuffmanBuilder, HuffmanSpine};
+use differential_dataflow::trace::{Trace, TraceReader, Batcher};
+use differential_dataflow::trace::cursor::{Cursor, IntoOwned};
+
+/// Byte strings drawn mostly from a few symbols, with rarer symbols offset by `shift`.
+fn skewed(count: usize, shift: u8) -> Vec<Vec<u8>> {
+    (0 .. count)
+        .map(|i| {
+            let mut bytes = vec![b'a'; 1 + i % 7];
+            bytes.extend(std::iter::repeat(b'b').take(i % 3));
+            if i % 5 == 0 { bytes.push(b'c' + shift); }
+            bytes
+        })
+        .collect()
+}
+
+fn contents(container: &HuffmanContainer<u8>) -> Vec<Vec<u8>> {
+    (0 .. container.len()).map(|i| container.index(i).into_owned()).collect()
+}
+
+#[test]
+fn huffman_container_escapes() {
+
+    let mut raw1 = HuffmanContainer::<u8>::with_capacity(0);
+    let mut raw2 = HuffmanContainer::<u8>::with_capacity(0);
+    for bytes in skewed(100, 0) { raw1.push(bytes); }
+    for bytes in skewed(50, 0) { raw2.push(bytes); }
+    assert!(!raw1.is_encoded());
+
+    // merging raw containers learns a code, which covers all of their symbols.
+    let mut merged = HuffmanContainer::merge_capacity(&raw1, &raw2);
+    for i in 0 .. raw1.len() { merged.push(raw1.index(i)); }
+    for i in 0 .. raw2.len() { merged.push(raw2.index(i)); }
+    assert!(merged.is_encoded());
+    assert_eq!(merged.escaped(), 0);
+    let mut expected = contents(&raw1);
+    expected.extend(contents(&raw2));
+    assert_eq!(contents(&merged), expected);
+
+    // merging with symbols absent from the code reuses the code, and escapes the new symbols.
+    let mut raw3 = HuffmanContainer::<u8>::with_capacity(0);
+    for bytes in skewed(50, 10) { raw3.push(bytes); }
+    let mut merged2 = HuffmanContainer::merge_capacity(&merged, &raw3);
+    for i in 0 .. merged.len() { merged2.push(merged.index(i)); }
+    for i in 0 .. raw3.len() { merged2.push(raw3.index(i)); }
+    assert!(merged2.is_encoded());
+    assert_eq!(merged2.escaped(), 10);
+    expected.extend(contents(&raw3));
+    assert_eq!(contents(&merged2), expected);
+}
+
+#[test]
+fn huffman_spine_round_trip() {
+
+    let op_info = OperatorInfo::new(0, 0, [].into());
+    let mut trace = HuffmanSpine::<(), usize, i64>::new(op_info, None, None);
+    let mut batcher = HuffmanBatcher::<(), usize, i64>::new(None, 0);
+
+    // each round introduces strings with symbols unseen in earlier rounds.
+    let mut expected = Vec::new();
+    for round in 0 .. 8usize {
+        let mut updates = skewed(100, round as u8)
+            .into_iter()
+            .map(|bytes| ((bytes, ()), round, 1))
+            .collect::<Vec<_>>();
+        for ((bytes, ()), time, diff) in updates.iter() {
+            expected.push(((bytes.clone(), ()), *time, *diff));
+        }
+        batcher.push_container(&mut updates);
+        trace.insert(batcher.seal::<HuffmanBuilder<(), usize, i64>>(Antichain::from_elem(round + 1)));
+    }
+    for _ in 0 .. 10 { trace.exert(); }
+
+    differential_dataflow::consolidation::consolidate_updates(&mut expected);
+
+    let (mut cursor, storage) = trace.cursor();
+    let mut results = Vec::new();
+    for ((key, val), list) in cursor.to_vec::<Vec<u8>, ()>(&storage) {
+        for (time, diff) in list {
+            results.push(((key.clone(), val), time, diff));
+        }
+    }
+    differential_dataflow::consolidation::consolidate_updates(&mut results);
+
+    assert_eq!(results, expected);
+}
@@ -1,5 +1,6 @@
 use timely::dataflow::operators::*;
 use timely::dataflow::operators::capture::Extract;
+use timely::progress::Antichain;
 use timely::progress::frontier::AntichainRef;
 
 use differential_dataflow::input::InputSession;
@@ -243,3 +244,62 @@ fn import_skewed() {
         (4, vec![((0, 1), 1)]),
     ]);
 }
+
+#[test]
+fn import_frontier_since_two_frontiers() {
+
+    use differential_dataflow::input::Input;
+
+    let (coarse, fine) = timely::execute_directly(|worker| {
+
+        let (mut input, mut trace) = worker.dataflow::<usize,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<(u64, u64), i64>();
+            (input, data.arrange_by_key().trace)
+        });
+
+        // import the same trace at two logical compaction frontiers.
+        let (coarse, fine) = worker.dataflow(|scope| {
+            let (mut coarse, _) = trace.import_frontier_since(scope, "Coarse", Antichain::from_elem(2));
+            let (fine, _) = trace.import_frontier_since(scope, "Fine", Antichain::from_elem(0));
+            assert_eq!(coarse.trace.get_logical_compaction().to_owned(), Antichain::from_elem(2));
+            (
+                coarse.as_collection(|k: &u64, v: &u64| (*k, *v)).inner.capture(),
+                fine.as_collection(|k: &u64, v: &u64| (*k, *v)).inner.capture(),
+            )
+        });
+
+        input.insert((0, 0));
+        input.advance_to(1);
+        input.insert((1, 1));
+        input.advance_to(2);
+        input.remove((0, 0));
+        input.insert((2, 2));
+
+        (coarse, fine)
+    });
+
+    let mut coarse = coarse.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    let mut fine = fine.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut coarse);
+    differential_dataflow::consolidation::consolidate_updates(&mut fine);
+
+    // the coarse import presents the history of the trace as of time two; the fine import its full history.
+    assert_eq!(coarse, vec![((1, 1), 2, 1), ((2, 2), 2, 1)]);
+    assert_eq!(fine, vec![((0, 0), 0, 1), ((0, 0), 2, -1), ((1, 1), 1, 1), ((2, 2), 2, 1)]);
+}
+
+#[test]
+#[should_panic(expected = "not beyond the logical compaction frontier")]
+fn import_frontier_since_rejects_compacted() {
+    use differential_dataflow::input::Input;
+    timely::execute_directly(|worker| {
+        let mut trace = worker.dataflow::<usize,_,_>(|scope| {
+            let (_input, data) = scope.new_collection::<(u64, u64), i64>();
+            data.arrange_by_key().trace
+        });
+        trace.set_logical_compaction(AntichainRef::new(&[2]));
+        worker.dataflow(|scope| {
+            trace.import_frontier_since(scope, "Import", Antichain::from_elem(1));
+        });
+    });
+}
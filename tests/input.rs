@@ -0,0 +1,48 @@
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::arrange::ArrangeBySelf;
+use differential_dataflow::consolidation::consolidate_updates;
+
+#[test]
+fn insert_batch_matches_inserts() {
+
+    let (single, batched) = timely::execute_directly(|worker| {
+
+        let (mut single, mut batched, captures) = worker.dataflow::<u32,_,_>(|scope| {
+            let (single, data1) = scope.new_collection::<u32, isize>();
+            let (batched, data2) = scope.new_collection::<u32, isize>();
+            let capture1 = data1.arrange_by_self().as_collection(|k, _| *k).inner.capture();
+            let capture2 = data2.arrange_by_self().as_collection(|k, _| *k).inner.capture();
+            (single, batched, (capture1, capture2))
+        });
+
+        // unsorted elements with repetitions, followed by updates at a later time.
+        let elements = (0 .. 1000u32).map(|x| (x * 7919) % 500).collect::<Vec<_>>();
+        single.insert(1000);
+        for element in elements.iter() {
+            single.insert(*element);
+        }
+        // an update buffered by the session, which should be sent along with the batch.
+        batched.insert(1000);
+        batched.insert_batch(elements.clone());
+
+        single.advance_to(1);
+        batched.advance_to(1);
+        for element in elements.iter().take(100) {
+            single.remove(*element);
+            single.update(*element + 1, 2);
+        }
+        batched.update_batch(elements.iter().take(100).flat_map(|x| vec![(*x, -1), (*x + 1, 2)]).collect());
+
+        captures
+    });
+
+    let mut single = single.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    let mut batched = batched.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    consolidate_updates(&mut single);
+    consolidate_updates(&mut batched);
+    assert!(!single.is_empty());
+    assert_eq!(single, batched);
+}
@@ -0,0 +1,122 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use timely::container::columnation::TimelyStack;
+use timely::dataflow::channels::pact::Pipeline;
+use timely::dataflow::operators::{ToStream, Operator};
+
+use differential_dataflow::{AsCollection, Collection};
+
+type Update = (u64, u64, isize);
+
+/// Accepts only columnar containers, so that the inspection must present them as such.
+fn record(stack: &TimelyStack<Update>, observed: &RefCell<Vec<Update>>) {
+    observed.borrow_mut().extend(stack.iter().cloned());
+}
+
+#[test]
+fn inspect_container_columnar() {
+
+    let before = Rc::new(RefCell::new(Vec::new()));
+    let after = Rc::new(RefCell::new(Vec::new()));
+    let before2 = Rc::clone(&before);
+    let after2 = Rc::clone(&after);
+
+    timely::example(move |scope| {
+
+        // a collection whose updates are held in columnar containers.
+        let columnar: Collection<_, u64, isize, TimelyStack<Update>> =
+        vec![(0u64, 0u64, 1isize), (1, 0, 2), (2, 1, -1)]
+            .into_iter()
+            .to_stream(scope)
+            .unary(Pipeline, "Columnate", |_cap, _info| |input, output| {
+                input.for_each(|time, data| {
+                    let mut stack = TimelyStack::default();
+                    for update in data.iter() {
+                        stack.copy(update);
+                    }
+                    output.session(&time).give_container(&mut stack);
+                });
+            })
+            .as_collection();
+
+        // observe the containers twice, to confirm that the first inspection does not disturb them.
+        columnar
+            .inspect_container(move |event| if let Ok((_time, stack)) = event { record(stack, &before2); })
+            .inspect_container(move |event| if let Ok((_time, stack)) = event { record(stack, &after2); });
+    });
+
+    let mut before = before.borrow().clone();
+    let mut after = after.borrow().clone();
+    before.sort();
+    after.sort();
+    assert_eq!(before, vec![(0, 0, 1), (1, 0, 2), (2, 1, -1)]);
+    assert_eq!(after, before);
+}
+
+#[test]
+fn inspect_frontier_reports_advances() {
+
+    use timely::dataflow::ProbeHandle;
+    use differential_dataflow::input::Input;
+
+    let frontiers = Rc::new(RefCell::new(Vec::new()));
+    let frontiers2 = Rc::clone(&frontiers);
+
+    timely::execute_directly(move |worker| {
+
+        let mut probe = ProbeHandle::new();
+        let mut input = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<u32, isize>();
+            data.inspect_frontier(move |frontier| frontiers2.borrow_mut().push(frontier.to_vec()))
+                .probe_with(&mut probe);
+            input
+        });
+
+        for round in 1 .. 4 {
+            input.insert(round);
+            input.advance_to(round);
+            input.flush();
+            worker.step_while(|| probe.less_than(input.time()));
+        }
+    });
+
+    // the empty frontier is reported once the input is dropped and the dataflow completes.
+    assert_eq!(*frontiers.borrow(), vec![vec![1], vec![2], vec![3], vec![]]);
+}
+
+#[test]
+fn observe_updates_outlives_input() {
+
+    use differential_dataflow::input::Input;
+
+    timely::execute_directly(|worker| {
+
+        let (mut input, mut observer) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<u32, isize>();
+            (input, data.observe_updates(2))
+        });
+
+        for round in 0 .. 5u32 {
+            input.advance_to(round);
+            input.insert(round);
+        }
+        input.close();
+
+        // step until the input is complete, without draining the full observer.
+        for _ in 0 .. 10 { worker.step(); }
+        assert_eq!(observer.len(), 2);
+        assert!(!observer.is_complete());
+
+        let mut seen = Vec::new();
+        let mut steps = 0;
+        while !observer.is_complete() || !observer.is_empty() {
+            seen.extend(observer.drain());
+            worker.step();
+            steps += 1;
+            assert!(steps < 100, "observer failed to complete; saw {:?}", seen);
+        }
+
+        assert_eq!(seen, (0 .. 5u32).map(|x| (x, x, 1)).collect::<Vec<_>>());
+    });
+}
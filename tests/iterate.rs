@@ -0,0 +1,54 @@
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+
+/// Increments `start` until `limit` is reached, returning the result and the last round at which the loop changed.
+fn increment_until(start: u32, limit: u32) -> (Vec<u32>, u64) {
+
+    let (result, rounds) = timely::example(move |scope| {
+
+        let data = scope.new_collection_from(vec![start]).1;
+
+        let mut rounds = None;
+        let result = data.iterate_until(
+            |values| {
+                rounds = Some(values.inner.capture());
+                values.map(|x| x + 1)
+            },
+            move |values| values.map(move |x| x >= limit),
+        );
+
+        (result.inner.capture(), rounds.unwrap())
+    });
+
+    let mut result = result
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .map(|(x, _time, diff)| (x, diff))
+        .collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate(&mut result);
+    assert!(result.iter().all(|(_, diff)| *diff == 1));
+
+    let round = rounds
+        .extract()
+        .into_iter()
+        .map(|(time, _)| time.inner)
+        .max()
+        .unwrap();
+
+    (result.into_iter().map(|(x, _)| x).collect(), round)
+}
+
+#[test]
+fn iterate_until_stops_at_round() {
+    // the result of round `i` is `start + i + 1`, and the loop's input last changes at the round that produced it.
+    assert_eq!(increment_until(0, 5), (vec![5], 4));
+}
+
+#[test]
+fn iterate_until_stops_immediately() {
+    // the first round's result satisfies the condition, and so the loop's input never changes.
+    assert_eq!(increment_until(7, 5), (vec![8], 0));
+}
@@ -73,6 +73,102 @@ fn antijoin() {
     assert_eq!(extracted[0].1, vec![((1,2), Default::default(),1)]);
 }
 
+#[test]
+fn join_map_opt() {
+    let data = timely::example(|scope| {
+        let col1 = vec![((0,0), Default::default(),1),((0,1), Default::default(),1),((1,2), Default::default(),1)].into_iter().to_stream(scope).as_collection();
+        let col2 = vec![((0,'a'), Default::default(),1),((1,'B'), Default::default(),1)].into_iter().to_stream(scope).as_collection();
+
+        // should produce only the record `(1,'a')`.
+        col1.join_map_opt(&col2, |_k,v1,v2| if *v1 == 1 { Some((*v1, *v2)) } else { None }).inner.capture()
+    });
+
+    let extracted = data.extract();
+    assert_eq!(extracted.len(), 1);
+    assert_eq!(extracted[0].1, vec![((1,'a'), Default::default(),1)]);
+}
+
+#[test]
+fn join_map_opt_volume() {
+
+    // Keys match broadly, but only one in fifty pairs of values is retained.
+    let keys = 4u64;
+    let vals = 50u64;
+
+    let (filtered, optional) = timely::example(move |scope| {
+
+        let data = (0 .. 1).to_stream(scope)
+                           .flat_map(move |_| (0 .. keys * vals).map(move |i| ((i % keys, i / keys), Default::default(), 1)))
+                           .as_collection();
+
+        let filtered = data.join_map(&data, |k,v1,v2| (*k, *v1, *v2))
+                           .inner
+                           .capture();
+        let optional = data.join_map_opt(&data, |k,v1,v2| if v1 == v2 { Some((*k, *v1, *v2)) } else { None })
+                           .inner
+                           .capture();
+
+        (filtered, optional)
+    });
+
+    let filtered = filtered.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    let optional = optional.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+
+    // `join_map` produces every matching pair, where `join_map_opt` produces only those retained.
+    assert_eq!(filtered.len() as u64, keys * vals * vals);
+    assert_eq!(optional.len() as u64, keys * vals);
+
+    let mut expected = filtered.into_iter().filter(|((_, v1, v2), _, _)| v1 == v2).collect::<Vec<_>>();
+    expected.sort();
+    let mut optional = optional;
+    optional.sort();
+    assert_eq!(optional, expected);
+}
+
+#[test]
+fn antijoin_core_toggle() {
+
+    use differential_dataflow::input::Input;
+    use differential_dataflow::operators::arrange::ArrangeBySelf;
+
+    let captured = timely::execute_directly(|worker| {
+
+        let (mut data, mut keys, captured) = worker.dataflow::<u32,_,_>(|scope| {
+            let (data_input, data) = scope.new_collection();
+            let (keys_input, keys) = scope.new_collection();
+            let captured = data.antijoin_core(&keys.arrange_by_self()).inner.capture();
+            (data_input, keys_input, captured)
+        });
+
+        data.insert((0u32, 'a'));
+        data.insert((1u32, 'b'));
+
+        // key zero enters the anti-set at time one, leaves at time three, and returns at time four.
+        for (time, change) in [(1, 1), (3, -1), (4, 1)] {
+            data.advance_to(time);
+            keys.advance_to(time);
+            keys.update(0u32, change);
+        }
+
+        captured
+    });
+
+    let mut results = captured
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut results);
+
+    assert_eq!(results, vec![
+        ((0, 'a'), 0, 1),
+        ((0, 'a'), 1, -1),
+        ((0, 'a'), 3, 1),
+        ((0, 'a'), 4, -1),
+        ((1, 'b'), 0, 1),
+    ]);
+}
+
 #[test] fn join_scale_1() { join_scaling(1); }
 #[test] fn join_scale_10() { join_scaling(10); }
 #[test] fn join_scale_100() { join_scaling(100); }
@@ -98,4 +194,118 @@ fn join_scaling(scale: u64) {
 
     let extracted = data.extract();
     assert_eq!(extracted.len(), 0);
-}
\ No newline at end of file
+}
+
+#[test]
+fn join_core_named_operates() {
+
+    use std::sync::{Arc, Mutex};
+    use timely::logging::{TimelyEvent, TimelyEventBuilder};
+    use differential_dataflow::input::Input;
+    use differential_dataflow::operators::arrange::ArrangeByKey;
+    use differential_dataflow::operators::join::JoinCore;
+
+    let names = Arc::new(Mutex::new(Vec::new()));
+    let names2 = Arc::clone(&names);
+
+    timely::execute_directly(move |worker| {
+
+        // record the names of constructed operators.
+        worker
+            .log_register()
+            .insert::<TimelyEventBuilder,_>("timely", move |_time, data| {
+                if let Some(data) = data {
+                    for (_, event) in data.iter() {
+                        if let TimelyEvent::Operates(operates) = event {
+                            names2.lock().unwrap().push(operates.name.clone());
+                        }
+                    }
+                }
+            });
+
+        worker.dataflow::<u32,_,_>(|scope| {
+            let x = scope.new_collection_from(vec![(0u32, 1u32), (1, 3)]).1;
+            let y = scope.new_collection_from(vec![(0u32, 'a'), (1, 'b')]).1.arrange_by_key();
+            x.join_core_named("JoinFirst", &y, |_key, &a, &b| Some((a, b)));
+            x.arrange_by_key().join_core_named("JoinSecond", &y, |_key, &a, &b| Some((b, a)));
+        });
+    });
+
+    let names = names.lock().unwrap();
+    assert_eq!(names.iter().filter(|name| *name == "JoinFirst").count(), 1);
+    assert_eq!(names.iter().filter(|name| *name == "JoinSecond").count(), 1);
+    assert_eq!(names.iter().filter(|name| *name == "Arrange: JoinFirst").count(), 1);
+}
+
+#[test]
+fn join_broadcast_matches_join() {
+
+    use differential_dataflow::input::Input;
+    use differential_dataflow::consolidation::consolidate_updates;
+
+    let captured = timely::execute(timely::Config::process(3), |worker| {
+
+        let index = worker.index() as u64;
+        let peers = worker.peers() as u64;
+        let (mut large, mut small, captured) = worker.dataflow::<u64,_,_>(|scope| {
+            let (large_input, large) = scope.new_collection::<(u64, u64), isize>();
+            let (small_input, small) = scope.new_collection::<(u64, char), isize>();
+            let broadcast = large.join_broadcast(&small, |key, &val, &name| (*key, val, name)).inner.capture();
+            let joined = large.join_map(&small, |key, &val, &name| (*key, val, name)).inner.capture();
+            (large_input, small_input, (broadcast, joined))
+        });
+
+        // each worker introduces its share of the large side; the small side changes over time.
+        for round in 0 .. 4u64 {
+            large.advance_to(round);
+            small.advance_to(round);
+            for x in (0 .. 60u64).filter(|x| x % peers == index) {
+                large.update((x % 5, x + round), if round == 3 && x % 2 == 0 { -1 } else { 1 });
+            }
+            if index == 0 {
+                match round {
+                    0 => { small.insert((0, 'a')); small.insert((1, 'b')); },
+                    1 => { small.insert((2, 'c')); small.remove((0, 'a')); },
+                    2 => { small.insert((1, 'b')); small.insert((1, 'd')); },
+                    _ => { small.remove((1, 'b')); },
+                }
+            }
+        }
+
+        captured
+    }).unwrap();
+
+    let (mut broadcast, mut joined) = (Vec::new(), Vec::new());
+    for result in captured.join() {
+        let (b, j) = result.unwrap();
+        broadcast.extend(b.extract().into_iter().flat_map(|(_, list)| list));
+        joined.extend(j.extract().into_iter().flat_map(|(_, list)| list));
+    }
+    consolidate_updates(&mut broadcast);
+    consolidate_updates(&mut joined);
+
+    assert!(!joined.is_empty());
+    assert_eq!(broadcast, joined);
+}
+
+#[test]
+fn join_sparse_keys() {
+
+    let data = timely::example(|scope| {
+
+        // the large collection has every key below 1000, and the sparse collection has a few keys, half of which
+        // are beyond the keys of the large collection.
+        let large = (0 .. 1000u64).map(|x| ((x, x * 10), 0u64, 1isize)).to_stream(scope).as_collection();
+        let sparse = (0 .. 12u64).map(|x| ((x * 100 + if x % 2 == 0 { 0 } else { 1000 }, x), 0u64, 1isize)).to_stream(scope).as_collection();
+
+        large.join(&sparse).inner.capture()
+    });
+
+    let mut results = data.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut results);
+    let expected = (0 .. 12u64)
+        .filter(|x| x % 2 == 0 && x * 100 < 1000)
+        .map(|x| ((x * 100, (x * 1000, x)), 0, 1))
+        .collect::<Vec<_>>();
+    assert_eq!(results, expected);
+}
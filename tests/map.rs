@@ -0,0 +1,330 @@
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::consolidation::consolidate_updates;
+
+fn append(text: &mut String) {
+    text.push_str("-suffix");
+}
+
+#[test]
+fn map_in_place_matches_map() {
+
+    let (in_place, mapped) = timely::execute_directly(|worker| {
+
+        let (mut input, in_place, mapped) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<String, isize>();
+            let in_place = data.map_in_place(append).inner.capture();
+            let mapped = data.map(|x| { let mut x = x; append(&mut x); x }).inner.capture();
+            (input, in_place, mapped)
+        });
+
+        for round in 0 .. 5u32 {
+            input.advance_to(round);
+            input.insert(format!("record-{}", round));
+            input.insert(format!("record-{}", round % 2));
+            if round > 0 {
+                input.remove(format!("record-{}", round - 1));
+            }
+        }
+
+        (in_place, mapped)
+    });
+
+    let mut in_place = in_place.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    let mut mapped = mapped.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    consolidate_updates(&mut in_place);
+    consolidate_updates(&mut mapped);
+
+    assert!(!mapped.is_empty());
+    assert_eq!(in_place, mapped);
+}
+
+#[test]
+fn reweight_matches_explode() {
+
+    let (reweighted, exploded) = timely::execute_directly(|worker| {
+
+        let (mut input, reweighted, exploded) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<(u32, isize), isize>();
+            // a zero magnitude produces zero differences, which should consolidate away.
+            let reweighted = data.reweight(|(_, magnitude)| *magnitude).map(|(key, _)| key).inner.capture();
+            let exploded = data.explode(|(key, magnitude)| Some((key, magnitude))).inner.capture();
+            (input, reweighted, exploded)
+        });
+
+        for round in 0 .. 5u32 {
+            input.advance_to(round);
+            input.insert((round % 3, round as isize - 2));
+            if round > 0 {
+                input.remove(((round - 1) % 3, round as isize - 3));
+            }
+        }
+
+        (reweighted, exploded)
+    });
+
+    let mut reweighted = reweighted.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    let mut exploded = exploded.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    consolidate_updates(&mut reweighted);
+    consolidate_updates(&mut exploded);
+
+    assert!(!exploded.is_empty());
+    assert!(!reweighted.iter().any(|(key, time, _)| *key == 2 && *time == 2));
+    assert_eq!(reweighted, exploded);
+}
+
+/// An amount of money in cents, used as a difference type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Cents(i64);
+
+impl differential_dataflow::difference::IsZero for Cents {
+    fn is_zero(&self) -> bool { self.0 == 0 }
+}
+impl differential_dataflow::difference::Semigroup for Cents {
+    fn plus_equals(&mut self, rhs: &Self) { self.0 += rhs.0; }
+}
+impl differential_dataflow::difference::Multiply<isize> for Cents {
+    type Output = Cents;
+    fn multiply(self, rhs: &isize) -> Cents { Cents(self.0 * (*rhs as i64)) }
+}
+impl differential_dataflow::difference::Multiply<u32> for Cents {
+    type Output = Cents;
+    fn multiply(self, rhs: &u32) -> Cents { Cents(self.0 * (*rhs as i64)) }
+}
+
+#[test]
+fn reweight_custom_difference() {
+
+    let totals = timely::execute_directly(|worker| {
+
+        let (mut input, totals) = worker.dataflow::<u32,_,_>(|scope| {
+            // each record is an account and a price in cents; its difference is a quantity.
+            let (input, data) = scope.new_collection::<(&'static str, u32), isize>();
+            let totals =
+            data.explode(|(account, price)| Some(((account, price), Cents(1))))
+                .reweight(|(_account, price)| *price)
+                .map(|(account, _price)| account)
+                .inner
+                .capture();
+            (input, totals)
+        });
+
+        input.insert(("alice", 250));
+        input.insert(("alice", 100));
+        input.insert(("bob", 0));
+        input.update(("bob", 75), 2);
+
+        totals
+    });
+
+    let mut totals = totals.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    consolidate_updates(&mut totals);
+    assert_eq!(totals, vec![("alice", 0, Cents(350)), ("bob", 0, Cents(150))]);
+}
+
+#[test]
+fn split_matches_filters() {
+
+    let (accepted, rejected, filtered, complement, original, recombined) = timely::execute_directly(|worker| {
+
+        let (mut input, captures) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<u64, isize>();
+            let (accepted, rejected) = data.split(|x| x % 3 == 0);
+            let captures = (
+                accepted.inner.capture(),
+                rejected.inner.capture(),
+                data.filter(|x| x % 3 == 0).inner.capture(),
+                data.filter(|x| x % 3 != 0).inner.capture(),
+                data.inner.capture(),
+                accepted.concat(&rejected).inner.capture(),
+            );
+            (input, captures)
+        });
+
+        for round in 0 .. 5u32 {
+            input.advance_to(round);
+            for x in 0 .. 10u64 {
+                input.update(x * (round as u64 + 1), (x as isize) - 4);
+            }
+        }
+
+        captures
+    });
+
+    type Updates = Vec<(u64, u32, isize)>;
+    fn extract(captured: std::sync::mpsc::Receiver<timely::dataflow::operators::capture::Event<u32, Updates>>) -> Updates {
+        let mut updates = captured.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+        consolidate_updates(&mut updates);
+        updates
+    }
+
+    let accepted = extract(accepted);
+    let rejected = extract(rejected);
+    assert!(!accepted.is_empty());
+    assert!(!rejected.is_empty());
+    assert_eq!(accepted, extract(filtered));
+    assert_eq!(rejected, extract(complement));
+    assert_eq!(extract(recombined), extract(original));
+}
+
+#[test]
+fn partition_results_recombines() {
+
+    let (original, recombined) = timely::execute_directly(|worker| {
+
+        let (mut input, captures) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<Result<u64, String>, isize>();
+            let (oks, errs) = data.partition_results();
+            let captures = (
+                data.inner.capture(),
+                oks.map(Ok).concat(&errs.map(Err)).inner.capture(),
+            );
+            (input, captures)
+        });
+
+        // records are added and later retracted, in both outputs.
+        input.insert(Ok(1));
+        input.insert(Err("one".to_string()));
+        input.advance_to(1);
+        input.insert(Ok(2));
+        input.remove(Ok(1));
+        input.update(Err("two".to_string()), 3);
+        input.advance_to(2);
+        input.remove(Err("one".to_string()));
+        input.update(Err("two".to_string()), -1);
+
+        captures
+    });
+
+    type Updates = Vec<(Result<u64, String>, u32, isize)>;
+    fn extract(captured: std::sync::mpsc::Receiver<timely::dataflow::operators::capture::Event<u32, Updates>>) -> Updates {
+        let mut updates = captured.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+        consolidate_updates(&mut updates);
+        updates
+    }
+
+    let original = extract(original);
+    assert_eq!(original.len(), 7);
+    assert_eq!(extract(recombined), original);
+}
+
+#[test]
+fn sample_treats_retractions_alike() {
+
+    let (none, half, all) = timely::execute_directly(|worker| {
+
+        let (mut input, captures) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<u64, isize>();
+            let captures = (
+                data.sample(0.0, 1).inner.capture(),
+                data.sample(0.5, 1).inner.capture(),
+                data.sample(1.0, 1).inner.capture(),
+            );
+            (input, captures)
+        });
+
+        // each record is inserted, and later retracted.
+        for x in 0 .. 1_000 {
+            input.insert(x);
+        }
+        input.advance_to(1);
+        for x in 0 .. 1_000 {
+            input.remove(x);
+        }
+
+        captures
+    });
+
+    type Updates = Vec<(u64, u32, isize)>;
+    fn extract(captured: std::sync::mpsc::Receiver<timely::dataflow::operators::capture::Event<u32, Updates>>) -> Updates {
+        let mut updates = captured.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+        consolidate_updates(&mut updates);
+        updates
+    }
+
+    assert!(extract(none).is_empty());
+    assert_eq!(extract(all).len(), 2_000);
+
+    // each sampled record is present with both its insertion and its retraction.
+    let half = extract(half);
+    let inserted = half.iter().filter(|(_, time, diff)| *time == 0 && *diff == 1).map(|(x, _, _)| *x).collect::<Vec<_>>();
+    let removed = half.iter().filter(|(_, time, diff)| *time == 1 && *diff == -1).map(|(x, _, _)| *x).collect::<Vec<_>>();
+    assert_eq!(inserted.len() + removed.len(), half.len());
+    assert_eq!(inserted, removed);
+    assert!(inserted.len() > 400 && inserted.len() < 600, "sampled {} of 1000 records", inserted.len());
+}
+
+#[test]
+fn times_pairs_records_with_times() {
+
+    let captured = timely::execute_directly(|worker| {
+
+        let (mut input, captured) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<char, isize>();
+            (input, data.times().inner.capture())
+        });
+
+        // 'c' is inserted and removed at time 2, and so has no time there.
+        input.insert('a');
+        input.insert('b');
+        input.advance_to(2);
+        input.insert('c');
+        input.remove('c');
+        input.remove('a');
+        input.advance_to(5);
+        input.insert('a');
+        input.insert('a');
+
+        captured
+    });
+
+    let mut results = captured.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    consolidate_updates(&mut results);
+    assert_eq!(results, vec![
+        (('a', 0), 0, 1),
+        (('a', 2), 2, -1),
+        (('a', 5), 5, 2),
+        (('b', 0), 0, 1),
+    ]);
+}
+
+#[test]
+fn rebatch_closes_partial_batches() {
+
+    let captured = timely::execute_directly(|worker| {
+
+        let (mut input, probe, captured) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<char, isize>();
+            let batched = data.rebatch(2);
+            (input, batched.probe(), batched.inner.capture())
+        });
+
+        input.insert('c');
+        input.insert('a');
+        input.insert('b');
+        input.advance_to(1);
+        input.flush();
+
+        // the partial batch holding 'c' must not hold back the output frontier.
+        for _ in 0 .. 100 {
+            if !probe.less_than(&1) { break; }
+            worker.step();
+        }
+        assert!(!probe.less_than(&1));
+
+        input.remove('a');
+
+        captured
+    });
+
+    let mut results = captured.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    consolidate_updates(&mut results);
+    assert_eq!(results, vec![
+        ((0, 'a'), 0, 1),
+        ((0, 'b'), 0, 1),
+        ((1, 'c'), 0, 1),
+        ((2, 'a'), 1, -1),
+    ]);
+}
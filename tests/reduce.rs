@@ -39,4 +39,364 @@ fn reduce_scaling() {
 
     let extracted = data.extract();
     assert_eq!(extracted.len(), 1);
-}
\ No newline at end of file
+}
+
+#[test]
+fn reduce_with_limit_overflow_transitions() {
+
+    use std::sync::{Arc, Mutex};
+
+    let overflows = Arc::new(Mutex::new(Vec::new()));
+    let overflows2 = Arc::clone(&overflows);
+
+    let data = timely::example(move |scope| {
+
+        // key 0 exceeds the limit at time 1, stays over it at time 2, returns within it at time 3,
+        // and exceeds it again at time 4.
+        let col1 = vec![
+            ((0,1),0,1), ((0,2),0,1),
+            ((0,3),1,1),
+            ((0,4),2,1),
+            ((0,3),3,-1), ((0,4),3,-1),
+            ((0,5),4,1),
+        ]
+            .into_iter()
+            .to_stream(scope)
+            .as_collection();
+
+        col1.reduce_with_limit(
+                2,
+                move |key, count| overflows2.lock().unwrap().push((*key, count)),
+                |_,s,t| t.push((*s[0].0, 1isize)),
+            )
+            .inner
+            .capture()
+    });
+
+    let mut extracted = data.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut extracted);
+    assert_eq!(extracted, vec![((0,1),0,1), ((0,1),1,-1), ((0,1),3,1), ((0,1),4,-1)]);
+
+    // the overflow is reported once when it starts, and once more when it starts again, with three values each time.
+    assert_eq!(*overflows.lock().unwrap(), vec![(0, 3), (0, 3)]);
+}
+
+#[test]
+fn reduce_limited_backfill() {
+
+    let data = timely::example(|scope| {
+
+        // key 0 has values 1, 2, 3, and value 1 is retracted at time 1.
+        let col1 = vec![((0,1),0,1),((0,2),0,1),((0,3),0,1),((0,1),1,-1)]
+                        .into_iter()
+                        .to_stream(scope)
+                        .as_collection();
+
+        col1.reduce_limited(2, |_,v,_,t| t.push((*v, 1isize))).inner.capture()
+    });
+
+    let mut extracted = data.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    extracted.sort_by_key(|x| (x.1, x.0));
+    assert_eq!(extracted, vec![((0,1),0,1), ((0,2),0,1), ((0,1),1,-1), ((0,3),1,1)]);
+}
+
+#[test]
+fn reduce_limited_ties() {
+
+    let data = timely::example(|scope| {
+
+        // values 10, 11, 12 all produce the output 1; value 20 produces 2.
+        let col1 = vec![((0,10),0,1),((0,11),0,1),((0,12),0,1),((0,20),0,1)]
+                        .into_iter()
+                        .to_stream(scope)
+                        .as_collection();
+
+        col1.reduce_limited(2, |_,v,_,t| t.push((*v / 10, 1isize))).inner.capture()
+    });
+
+    // the first two outputs, from values 10 and 11, are retained.
+    let extracted = data.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    assert_eq!(extracted, vec![((0,1),0,2)]);
+}
+
+#[test]
+fn reduce_limited_large_group() {
+
+    use timely::dataflow::operators::probe::Handle;
+    use differential_dataflow::input::Input;
+
+    let data = timely::execute_directly(|worker| {
+
+        let mut probe = Handle::new();
+        let (mut input, data) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<(u32, u32), isize>();
+            let limited = data.reduce_limited(2, |_,v,_,t| t.push((*v, 1isize)));
+            limited.probe_with(&mut probe);
+            (input, limited.inner.capture())
+        });
+
+        // key 0 has many values, whose least values are retracted, and to which later values come and go.
+        let rounds = vec![
+            (0 .. 1000).map(|v| (v, 1)).collect::<Vec<_>>(),
+            vec![(0, -1)],
+            vec![(1, -1), (2, -1)],
+            vec![(1000, 1)],
+            vec![(500, -1), (1000, -1)],
+        ];
+        for updates in rounds {
+            for (val, diff) in updates {
+                input.update((0, val), diff);
+            }
+            input.advance_to(input.time() + 1);
+            input.flush();
+            worker.step_while(|| probe.less_than(input.time()));
+        }
+
+        data
+    });
+
+    let mut extracted = data.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut extracted);
+    extracted.sort_by_key(|x| (x.1, x.0));
+    assert_eq!(extracted, vec![
+        ((0,0),0,1), ((0,1),0,1),
+        ((0,0),1,-1), ((0,2),1,1),
+        ((0,1),2,-1), ((0,2),2,-1), ((0,3),2,1), ((0,4),2,1),
+    ]);
+}
+
+#[test]
+fn reduce_fallible_oscillation() {
+
+    let (oks, errs) = timely::example(|scope| {
+
+        // key 0 has a single value at times 0, 2, and 4, and two values at times 1 and 3.
+        let col1 = vec![((0,5),0,1),((0,6),1,1),((0,6),2,-1),((0,7),3,1),((0,5),4,-1)]
+                        .into_iter()
+                        .to_stream(scope)
+                        .as_collection();
+
+        let (oks, errs) = col1.reduce_fallible(|_,s,t,e| {
+            if s.len() == 1 { t.push((*s[0].0, 1isize)); }
+            else { e.push((s.len(), 1isize)); }
+        });
+
+        (oks.inner.capture(), errs.inner.capture())
+    });
+
+    let mut oks = oks.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut oks);
+    assert_eq!(oks, vec![((0,5),0,1), ((0,5),1,-1), ((0,5),2,1), ((0,5),3,-1), ((0,7),4,1)]);
+
+    let mut errs = errs.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut errs);
+    assert_eq!(errs, vec![((0,2),1,1), ((0,2),2,-1), ((0,2),3,1), ((0,2),4,-1)]);
+}
+
+#[test]
+fn count_distinct_toggles() {
+
+    let data = timely::example(|scope| {
+
+        // key 0 gains and loses values 5 (with multiplicity two) and 6; key 1 swaps value 7 for 8.
+        let col1 = vec![
+            ((0,5),0,2), ((0,6),1,1), ((0,5),2,-2), ((0,6),3,-1),
+            ((1,7),0,1), ((1,8),2,1), ((1,7),3,-1),
+        ]
+            .into_iter()
+            .to_stream(scope)
+            .as_collection();
+
+        col1.count_distinct().inner.capture()
+    });
+
+    let mut counts = data.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut counts);
+    assert_eq!(counts, vec![
+        ((0,1),0,1), ((0,1),1,-1), ((0,1),2,1), ((0,1),3,-1),
+        ((0,2),1,1), ((0,2),2,-1),
+        ((1,1),0,1), ((1,1),2,-1), ((1,1),3,1),
+        ((1,2),2,1), ((1,2),3,-1),
+    ]);
+}
+
+#[test]
+fn count_distinct_input_difference() {
+
+    let data = timely::example(|scope| {
+
+        // the counts take the difference type of their input, here `i32`.
+        let col1 = vec![((0,5),0,3i32), ((0,6),0,1), ((1,7),0,2), ((0,6),1,-1)]
+            .into_iter()
+            .to_stream(scope)
+            .as_collection();
+
+        col1.count_distinct().inner.capture()
+    });
+
+    let mut counts = data.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut counts);
+    assert_eq!(counts, vec![((0,1),1,1i32), ((0,2),0,1), ((0,2),1,-1), ((1,1),0,1)]);
+}
+
+#[test]
+fn reduce_observes_negative() {
+
+    let data = timely::example(|scope| {
+
+        // value 5 accumulates to -1 at time 1, and value 6 accumulates to zero at time 2.
+        let col1 = vec![((0,5),0,1), ((0,6),0,1), ((0,5),1,-2), ((0,6),2,-1)]
+            .into_iter()
+            .to_stream(scope)
+            .as_collection();
+
+        col1.reduce(|_key, input, output| {
+                for (val, diff) in input.iter() {
+                    output.push(((**val, *diff), 1));
+                }
+            })
+            .inner
+            .capture()
+    });
+
+    let mut observed = data.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut observed);
+    assert_eq!(observed, vec![
+        ((0,(5,-1)),1,1),
+        ((0,(5,1)),0,1), ((0,(5,1)),1,-1),
+        ((0,(6,1)),0,1), ((0,(6,1)),2,-1),
+    ]);
+}
+
+#[test]
+fn reduce_deltas_presents_prior_output() {
+
+    use std::sync::{Arc, Mutex};
+
+    let priors = Arc::new(Mutex::new(Vec::new()));
+    let priors2 = Arc::clone(&priors);
+
+    let data = timely::example(move |scope| {
+
+        // key 0 changes its sum at each time; key 1 appears at time 1 and vanishes at time 2.
+        let col1 = vec![((0,1),0,1), ((0,2),0,1), ((0,3),1,1), ((1,10),1,1), ((0,1),2,-1), ((1,10),2,-1)]
+            .into_iter()
+            .to_stream(scope)
+            .as_collection();
+
+        col1.reduce_deltas(move |key, input, prior, deltas| {
+                priors2.lock().unwrap().push((*key, prior.to_vec()));
+                let sum = input.iter().map(|(val, diff)| **val * *diff).sum::<isize>();
+                deltas.extend(prior.iter().map(|(val, diff)| (*val, -diff)));
+                deltas.push((sum, 1));
+            })
+            .inner
+            .capture()
+    });
+
+    let mut observed = data.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut observed);
+    assert_eq!(observed, vec![
+        ((0,3),0,1), ((0,3),1,-1),
+        ((0,5),2,1),
+        ((0,6),1,1), ((0,6),2,-1),
+        ((1,10),1,1), ((1,10),2,-1),
+    ]);
+
+    // key 0 is evaluated once at each time, in order, and key 1 only while it has input.
+    let priors = priors.lock().unwrap();
+    let mut sorted = priors.clone();
+    sorted.sort();
+    assert_eq!(sorted, vec![
+        (0, vec![]),
+        (0, vec![(3,1)]),
+        (0, vec![(6,1)]),
+        (1, vec![]),
+    ]);
+
+    // each prior output for key 0 is what the operator emitted for it before that time.
+    let priors0 = priors.iter().filter(|(key, _)| *key == 0).map(|(_, prior)| prior.clone()).collect::<Vec<_>>();
+    for (time, prior) in priors0.into_iter().enumerate() {
+        let mut emitted = observed
+            .iter()
+            .filter(|((key, _), t, _)| *key == 0 && (*t as usize) < time)
+            .map(|((_, val), _, diff)| (*val, *diff))
+            .collect::<Vec<_>>();
+        differential_dataflow::consolidation::consolidate(&mut emitted);
+        assert_eq!(prior, emitted);
+    }
+}
+
+/// Computes the `p`-th percentile of each key of a small weighted collection, at a single time.
+fn percentiles(p: f64) -> Vec<(u32, u32)> {
+
+    let data = timely::example(move |scope| {
+
+        // key 0 has values 10, 20, 30, and 40 with counts 1, 2, 3, and 4; key 1 has a negative count before
+        // a positive count; key 2 has only a negative count; key 3 has a single value.
+        vec![
+            ((0u32, 10u32), 0u64, 1isize), ((0, 20), 0, 2), ((0, 30), 0, 3), ((0, 40), 0, 4),
+            ((1, 5), 0, -1), ((1, 7), 0, 3),
+            ((2, 3), 0, -2),
+            ((3, 8), 0, 1),
+        ]
+            .into_iter()
+            .to_stream(scope)
+            .as_collection()
+            .percentile(p)
+            .inner
+            .capture()
+    });
+
+    let mut results = data.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut results);
+    assert!(results.iter().all(|(_, time, diff)| *time == 0 && *diff == 1));
+    results.into_iter().map(|(pair, _, _)| pair).collect()
+}
+
+#[test]
+fn percentile_weighted_ranks() {
+    assert_eq!(percentiles(0.5), vec![(0, 30), (1, 7), (3, 8)]);
+    assert_eq!(percentiles(0.9), vec![(0, 40), (1, 7), (3, 8)]);
+    assert_eq!(percentiles(0.0), vec![(0, 10), (1, 7), (3, 8)]);
+    assert_eq!(percentiles(1.0), vec![(0, 40), (1, 7), (3, 8)]);
+    assert_eq!(percentiles(0.2), vec![(0, 20), (1, 7), (3, 8)]);
+}
+
+#[test]
+#[should_panic(expected = "percentile requires 0.0 <= p <= 1.0")]
+fn percentile_out_of_range() {
+    percentiles(1.5);
+}
+
+#[test]
+fn reduce_with_side_retriggers() {
+
+    use differential_dataflow::operators::arrange::ArrangeByKey;
+
+    let data = timely::example(|scope| {
+
+        // key 0 has values 1, 2, and 3; its side limit is 2 at time 0, 1 at time 1, and absent from time 2.
+        let col1 = vec![((0,1),0,1), ((0,2),0,1), ((0,3),0,1)]
+            .into_iter()
+            .to_stream(scope)
+            .as_collection();
+
+        let side = vec![((0,2),0,1), ((0,2),1,-1), ((0,1),1,1), ((0,1),2,-1)]
+            .into_iter()
+            .to_stream(scope)
+            .as_collection()
+            .arrange_by_key();
+
+        col1.reduce_with_side(&side, |_key, limit, input, output| {
+                let count = input.iter().filter(|(val, _)| limit.map(|l| **val <= *l).unwrap_or(true)).count();
+                output.push((count, 1isize));
+            })
+            .inner
+            .capture()
+    });
+
+    let mut extracted = data.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut extracted);
+    assert_eq!(extracted, vec![((0,1),1,1), ((0,1),2,-1), ((0,2),0,1), ((0,2),1,-1), ((0,3),2,1)]);
+}
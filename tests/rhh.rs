@@ -0,0 +1,104 @@
+use std::hash::{Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+
+use timely::dataflow::operators::Probe;
+use timely::dataflow::operators::probe::Handle;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::arrange::Arrange;
+use differential_dataflow::trace::{Cursor, TraceReader};
+use differential_dataflow::trace::implementations::rhh::{HashWrapper, RhhValBatcher, RhhValBuilder, RhhValSpine, SLOTS_PER_KEY};
+
+/// A string key whose hash depends only on its length, so that keys of equal length collide.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+struct Colliding(String);
+
+impl Hash for Colliding {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.len().hash(state);
+    }
+}
+
+fn key(text: &str) -> HashWrapper<Colliding> {
+    HashWrapper { inner: Colliding(text.to_string()) }
+}
+
+#[test]
+fn rhh_colliding_keys() {
+    colliding_keys::<SLOTS_PER_KEY>();
+}
+
+#[test]
+fn rhh_colliding_keys_load_factor() {
+    colliding_keys::<1>();
+    colliding_keys::<4>();
+}
+
+/// Arranges colliding keys with builders reserving `SLOTS` key slots per key, and checks they are all found.
+fn colliding_keys<const SLOTS: usize>() {
+
+    timely::execute_directly(|worker| {
+
+        let mut probe = Handle::new();
+        let (mut input, mut trace) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<(Colliding, usize), isize>();
+            let arranged =
+            data.map(|(key, val)| (HashWrapper { inner: key }, val))
+                .arrange::<RhhValBatcher<_,_,_,_>, RhhValBuilder<_,_,_,_,SLOTS>, RhhValSpine<_,_,_,_>>();
+            arranged.stream.probe_with(&mut probe);
+            (input, arranged.trace)
+        });
+
+        // many keys of each of a few lengths, each with its own value.
+        let present = (0 .. 100).map(|i| format!("{:0width$}", i, width = 1 + i % 3)).collect::<Vec<_>>();
+        // inserted over two rounds, so that the trace holds or merges several batches.
+        for (index, text) in present.iter().enumerate() {
+            input.insert((Colliding(text.clone()), index));
+            if index == present.len() / 2 {
+                input.advance_to(1);
+                input.flush();
+                worker.step_while(|| probe.less_than(input.time()));
+            }
+        }
+        input.advance_to(2);
+        input.flush();
+        worker.step_while(|| probe.less_than(input.time()));
+
+        let (mut cursor, storage) = trace.cursor();
+
+        // all keys are present once, in hash order and then key order.
+        let mut keys = Vec::new();
+        while let Some(key) = cursor.get_key(&storage) {
+            keys.push(key.clone());
+            cursor.step_key(&storage);
+        }
+        let mut expected = present.iter().map(|text| key(text)).collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(keys, expected);
+
+        // each present key is found with its value, by a fresh cursor.
+        for (index, text) in present.iter().enumerate() {
+            let sought = key(text);
+            cursor.rewind_keys(&storage);
+            cursor.seek_key(&storage, &sought);
+            assert_eq!(cursor.get_key(&storage), Some(&sought));
+            assert_eq!(cursor.get_val(&storage), Some(&index));
+        }
+
+        // absent keys that collide with present keys are not found.
+        for text in ["x", "xx", "xxx", "0x", "00x"] {
+            let sought = key(text);
+            cursor.rewind_keys(&storage);
+            cursor.seek_key(&storage, &sought);
+            assert_ne!(cursor.get_key(&storage), Some(&sought));
+        }
+
+        // seeking keys in order with one cursor finds each of them.
+        cursor.rewind_keys(&storage);
+        for sought in expected.iter() {
+            cursor.seek_key(&storage, sought);
+            assert_eq!(cursor.get_key(&storage), Some(sought));
+        }
+    });
+}
@@ -0,0 +1,54 @@
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::consolidation::consolidate_updates;
+
+#[test]
+fn scan_running_sums() {
+
+    let captured = timely::execute_directly(|worker| {
+
+        let (mut input, captured) = worker.dataflow::<usize,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<(&'static str, i64), isize>();
+            let captured =
+            data.scan(0i64, |sum, val, diff| *sum += *val * (*diff as i64))
+                .inner
+                .capture();
+            (input, captured)
+        });
+
+        input.insert(("a", 5));
+        input.insert(("b", 1));
+        input.advance_to(1);
+        input.insert(("a", 3));
+        // a correction: the value five for "a" should have been four.
+        input.advance_to(2);
+        input.remove(("a", 5));
+        input.insert(("a", 4));
+        // changes that leave the sum for "b" unchanged produce no output.
+        input.advance_to(3);
+        input.remove(("b", 1));
+        input.insert(("b", 3));
+        input.insert(("b", -2));
+        input.insert(("c", 2));
+        // "c" has an empty input, and no state.
+        input.advance_to(4);
+        input.remove(("c", 2));
+
+        captured
+    });
+
+    let mut updates = captured.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    consolidate_updates(&mut updates);
+    assert_eq!(updates, vec![
+        (("a", 5), 0, 1),
+        (("a", 5), 1, -1),
+        (("a", 7), 2, 1),
+        (("a", 8), 1, 1),
+        (("a", 8), 2, -1),
+        (("b", 1), 0, 1),
+        (("c", 2), 3, 1),
+        (("c", 2), 4, -1),
+    ]);
+}
@@ -0,0 +1,167 @@
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::{Threshold, ThresholdTotal};
+
+/// Introduces `count` changes to key zero in each round, and returns the accumulated output of `bucket` after each round.
+fn bucket_rounds(rounds: Vec<isize>, total: bool) -> Vec<Vec<(u32, i64)>> {
+
+    let count = rounds.len();
+
+    let captured = timely::execute_directly(move |worker| {
+
+        let (mut input, captured) = worker.dataflow(|scope| {
+            let (input, data) = scope.new_collection();
+            // one copy of each key per ten occurrences, rounding up, and one more for odd keys.
+            let bucket = |key: &u32, count: &isize| ((*count + 9) / 10) as i64 + if *count > 0 { (*key % 2) as i64 } else { 0 };
+            let output = if total { data.threshold_total(move |k, c| bucket(k, c)) } else { data.threshold_named("Buckets", move |k, c| bucket(k, c)) };
+            (input, output.inner.capture())
+        });
+
+        for (round, changes) in rounds.into_iter().enumerate() {
+            input.advance_to(round);
+            input.update(0u32, changes);
+            input.update(1u32, changes);
+        }
+
+        captured
+    });
+
+    let updates = captured
+        .extract()
+        .into_iter()
+        .flat_map(|(_, list)| list)
+        .collect::<Vec<_>>();
+
+    (0 .. count)
+        .map(|round| {
+            let mut accum = updates.iter().filter(|x| x.1 <= round).map(|(k, _, r)| (*k, *r)).collect::<Vec<_>>();
+            differential_dataflow::consolidation::consolidate(&mut accum);
+            accum
+        })
+        .collect()
+}
+
+#[test]
+fn threshold_buckets() {
+    for total in [false, true] {
+        let results = bucket_rounds(vec![5, 10, 10, -20, -5], total);
+        assert_eq!(results[0], vec![(0, 1), (1, 2)]);
+        assert_eq!(results[1], vec![(0, 2), (1, 3)]);
+        assert_eq!(results[2], vec![(0, 3), (1, 4)]);
+        assert_eq!(results[3], vec![(0, 1), (1, 2)]);
+        assert_eq!(results[4], vec![]);
+    }
+}
+
+/// Applies `rounds` of `(key, change)` updates, and returns the consolidated outputs of `distinct` and `distinct_total`.
+fn distinct_outputs(rounds: Vec<Vec<(u32, isize)>>) -> (Vec<(u32, usize, isize)>, Vec<(u32, usize, isize)>) {
+
+    let (distinct, total) = timely::execute_directly(move |worker| {
+
+        let (mut input, distinct, total) = worker.dataflow(|scope| {
+            let (input, data) = scope.new_collection::<u32, isize>();
+            (input, data.distinct().inner.capture(), data.distinct_total().inner.capture())
+        });
+
+        for (round, changes) in rounds.into_iter().enumerate() {
+            input.advance_to(round);
+            for (key, change) in changes {
+                input.update(key, change);
+            }
+        }
+
+        (distinct, total)
+    });
+
+    let mut distinct = distinct.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    let mut total = total.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut distinct);
+    differential_dataflow::consolidation::consolidate_updates(&mut total);
+    (distinct, total)
+}
+
+#[test]
+fn distinct_total_matches_distinct() {
+    let inputs = vec![
+        // insertions with multiplicities, in one round and across rounds.
+        vec![vec![(0, 1), (1, 3)], vec![(0, 2), (2, 1)]],
+        // removal to zero, then reinsertion.
+        vec![vec![(0, 1)], vec![(0, -1)], vec![(0, 1)], vec![(0, -1)]],
+        // changes that cancel within a round.
+        vec![vec![(0, 1), (0, -1), (1, 2)], vec![(1, -1), (1, -1), (2, 1)]],
+        // negative accumulations, which are reported as distinct records.
+        vec![vec![(0, -1)], vec![(0, 2)], vec![(0, -1), (1, -2)]],
+        // no changes in some rounds.
+        vec![vec![(3, 1)], vec![], vec![(3, 1)], vec![], vec![(3, -2)]],
+    ];
+
+    for rounds in inputs {
+        let (distinct, total) = distinct_outputs(rounds.clone());
+        assert_eq!(distinct, total, "distinct_total differs from distinct on {:?}", rounds);
+    }
+}
+
+#[test]
+fn threshold_hysteresis_toggles_at_thresholds() {
+
+    let captured = timely::execute_directly(|worker| {
+
+        let (mut input, captured) = worker.dataflow::<u32,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<u32, isize>();
+            (input, data.threshold_hysteresis(5, 2).inner.capture())
+        });
+
+        // the count of key zero rises above five, wobbles between the thresholds, and falls below two.
+        let counts = vec![3, 6, 4, 6, 2, 5, 1, 4];
+        let mut previous = 0;
+        for (round, count) in counts.into_iter().enumerate() {
+            input.advance_to(round as u32);
+            input.update(0, count - previous);
+            previous = count;
+        }
+
+        captured
+    });
+
+    let mut updates = captured.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    updates.sort();
+    assert_eq!(updates, vec![(0, 1, 1), (0, 6, -1)]);
+}
+
+#[test]
+fn distinct_within_reports_after_window() {
+
+    let captured = timely::execute_directly(|worker| {
+
+        let (mut input, captured) = worker.dataflow::<usize,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<&'static str, isize>();
+            (input, data.distinct_within(10).inner.capture())
+        });
+
+        // "a" occurs at 0, 5, and 20; "b" occurs at 0 and 12, and its changes at 8 cancel.
+        input.insert("a");
+        input.insert("b");
+        input.advance_to(5);
+        input.insert("a");
+        input.advance_to(8);
+        input.insert("b");
+        input.remove("b");
+        input.advance_to(12);
+        input.insert("b");
+        input.advance_to(20);
+        input.insert("a");
+
+        captured
+    });
+
+    let mut results = captured.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut results);
+    assert_eq!(results, vec![
+        (("a", 0), 0, 1),
+        (("a", 20), 20, 1),
+        (("b", 0), 0, 1),
+        (("b", 12), 12, 1),
+    ]);
+}
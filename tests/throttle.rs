@@ -0,0 +1,54 @@
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::consolidation::consolidate;
+
+#[test]
+fn throttle_caps_and_preserves() {
+
+    let (throttled, expected) = timely::execute_directly(|worker| {
+
+        let (mut input, captured) = worker.dataflow::<u64,_,_>(|scope| {
+            let (input, data) = scope.new_collection::<u64, isize>();
+            let throttled = data.throttle(3, |t| *t + 1).inner.capture();
+            let expected = data.inner.capture();
+            (input, (throttled, expected))
+        });
+
+        for x in 0 .. 7 {
+            input.insert(x);
+        }
+        input.advance_to(1);
+        input.insert(7);
+        input.insert(8);
+        input.remove(0);
+        input.advance_to(2);
+
+        captured
+    });
+
+    let throttled = throttled.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    let expected = expected.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+
+    // no time has more than three updates.
+    let mut times = throttled.iter().map(|(_, time, _)| *time).collect::<Vec<_>>();
+    times.sort();
+    times.dedup();
+    for time in times.iter() {
+        assert!(throttled.iter().filter(|(_, t, _)| t == time).count() <= 3);
+    }
+
+    // the least records are emitted first.
+    let mut first = throttled.iter().filter(|(_, time, _)| *time == 0).map(|(data, _, diff)| (*data, *diff)).collect::<Vec<_>>();
+    first.sort();
+    assert_eq!(first, vec![(0, 1), (1, 1), (2, 1)]);
+
+    // once all times are complete, the contents are those of the input.
+    let mut throttled = throttled.into_iter().map(|(data, _, diff)| (data, diff)).collect::<Vec<_>>();
+    let mut expected = expected.into_iter().map(|(data, _, diff)| (data, diff)).collect::<Vec<_>>();
+    consolidate(&mut throttled);
+    consolidate(&mut expected);
+    assert_eq!(throttled, expected);
+    assert!(times.len() > 2);
+}
@@ -1,9 +1,14 @@
+use std::rc::Rc;
+use std::cell::Cell;
+
 use timely::dataflow::operators::generic::OperatorInfo;
 use timely::progress::{Antichain, frontier::AntichainRef};
 
-use differential_dataflow::trace::implementations::{ValBatcher, ValBuilder, ValSpine};
-use differential_dataflow::trace::{Trace, TraceReader, Batcher};
-use differential_dataflow::trace::cursor::Cursor;
+use differential_dataflow::trace::implementations::{KeyBatcher, KeyBuilder, ValBatcher, ValBuilder, ValSpine, Vector};
+use differential_dataflow::trace::implementations::ord_neu::OrdValBuilder;
+use differential_dataflow::trace::implementations::spine_fueled::MergePolicy;
+use differential_dataflow::trace::{Trace, TraceReader, Batcher, BatchReader, Description};
+use differential_dataflow::trace::cursor::{Cursor, CursorList};
 
 type IntegerTrace = ValSpine<u64, u64, usize, i64>;
 type IntegerBuilder = ValBuilder<u64, u64, usize, i64>;
@@ -56,3 +61,366 @@ fn test_trace() {
     let vec_4 = cursor4.to_vec(&storage4);
     assert_eq!(vec_4, vec_3);
 }
+
+/// Accumulates the updates for each `(key, val)` presented by `cursor_at(frontier)`, omitting zero accumulations.
+fn accumulate_at(trace: &mut IntegerTrace, frontier: &[usize]) -> Option<Vec<((u64, u64), i64)>> {
+    let (mut cursor, storage) = trace.cursor_at(AntichainRef::new(frontier))?;
+    let mut result = Vec::new();
+    for ((key, val), list) in cursor.to_vec(&storage) {
+        let sum = list.iter().map(|(_, diff)| diff).sum::<i64>();
+        if sum != 0 {
+            result.push(((key, val), sum));
+        }
+    }
+    Some(result)
+}
+
+#[test]
+fn test_cursor_at() {
+    let mut trace = get_trace();
+
+    assert_eq!(accumulate_at(&mut trace, &[0]), Some(vec![((1, 2), 1)]));
+    assert_eq!(accumulate_at(&mut trace, &[1]), Some(vec![((1, 2), 1), ((2, 3), 1)]));
+    assert_eq!(accumulate_at(&mut trace, &[2]), Some(vec![((1, 2), 1)]));
+
+    // after compaction, queries not beyond the compaction frontier are refused.
+    trace.set_logical_compaction(AntichainRef::new(&[2]));
+    trace.set_physical_compaction(AntichainRef::new(&[2]));
+    trace.exert();
+    assert_eq!(accumulate_at(&mut trace, &[1]), None);
+    assert_eq!(accumulate_at(&mut trace, &[2]), Some(vec![((1, 2), 1)]));
+    assert_eq!(accumulate_at(&mut trace, &[5]), Some(vec![((1, 2), 1)]));
+}
+
+type SortedBuilder = OrdValBuilder<Vector<((u64, u64), usize, i64)>, Vec<((u64, u64), usize, i64)>>;
+
+#[test]
+fn test_from_sorted() {
+    let description = Description::new(Antichain::from_elem(0), Antichain::from_elem(3), Antichain::from_elem(0));
+    let batch = SortedBuilder::from_sorted(vec![
+        ((1, 2), 0, 1),
+        ((2, 3), 1, 1),
+        ((2, 3), 2, -1),
+        ((2, 4), 2, 2),
+    ], description);
+
+    assert_eq!(batch.len(), 4);
+    assert_eq!(batch.upper(), &Antichain::from_elem(3));
+    let mut cursor = batch.cursor();
+    assert_eq!(cursor.to_vec(&batch), vec![
+        ((1, 2), vec![(0, 1)]),
+        ((2, 3), vec![(1, 1), (2, -1)]),
+        ((2, 4), vec![(2, 2)]),
+    ]);
+}
+
+#[test]
+fn test_from_sorted_empty() {
+    let description = Description::new(Antichain::from_elem(1), Antichain::from_elem(2), Antichain::from_elem(0));
+    let batch = SortedBuilder::from_sorted(Vec::new(), description);
+
+    assert!(batch.is_empty());
+    assert_eq!(batch.lower(), &Antichain::from_elem(1));
+    assert_eq!(batch.upper(), &Antichain::from_elem(2));
+    let mut cursor = batch.cursor();
+    assert!(cursor.to_vec::<u64, u64>(&batch).is_empty());
+}
+
+#[test]
+fn test_count_vals() {
+    let description = Description::new(Antichain::from_elem(0), Antichain::from_elem(3), Antichain::from_elem(0));
+    let batch = SortedBuilder::from_sorted(vec![
+        ((1, 2), 0, 1),
+        ((2, 3), 1, 1),
+        ((2, 3), 2, -1),
+        ((2, 4), 2, 2),
+        ((2, 5), 0, 1),
+        ((4, 0), 1, 1),
+    ], description);
+
+    // compares the offset-based count against stepping through the values of each key.
+    let mut cursor = batch.cursor();
+    let mut counts = Vec::new();
+    while cursor.key_valid(&batch) {
+        let first = *cursor.val(&batch);
+        let mut stepped = 0;
+        while cursor.val_valid(&batch) {
+            stepped += 1;
+            cursor.step_val(&batch);
+        }
+        assert_eq!(cursor.count_vals(&batch), stepped);
+        assert_eq!(*cursor.val(&batch), first);
+        counts.push(stepped);
+        cursor.step_key(&batch);
+    }
+    assert_eq!(counts, vec![1, 3, 1]);
+    assert_eq!(cursor.count_vals(&batch), 0);
+
+    // a list of cursors sums the counts of its cursors, counting values they share once for each.
+    let other = SortedBuilder::from_sorted(vec![
+        ((2, 3), 1, 1),
+        ((2, 6), 1, 1),
+    ], Description::new(Antichain::from_elem(0), Antichain::from_elem(3), Antichain::from_elem(0)));
+    let batches = vec![batch, other];
+    let mut list = CursorList::new(batches.iter().map(|batch| batch.cursor()).collect(), &batches);
+    list.seek_key(&batches, &2);
+    assert_eq!(list.count_vals(&batches), 5);
+    assert_eq!(*list.val(&batches), 3);
+    list.seek_key(&batches, &4);
+    assert_eq!(list.count_vals(&batches), 1);
+    list.step_key(&batches);
+    assert_eq!(list.count_vals(&batches), 0);
+
+    // each key of a key batch has the single value `()`.
+    let mut batcher = KeyBatcher::<u64,usize,i64>::new(None, 0);
+    batcher.push_container(&mut vec![((1, ()), 0, 1), ((2, ()), 0, 1)]);
+    let keys = batcher.seal::<KeyBuilder<u64,usize,i64>>(Antichain::from_elem(1));
+    let mut cursor = keys.cursor();
+    cursor.step_val(&keys);
+    assert!(!cursor.val_valid(&keys));
+    assert_eq!(cursor.count_vals(&keys), 1);
+    assert!(cursor.val_valid(&keys));
+    cursor.step_key(&keys);
+    cursor.step_key(&keys);
+    assert_eq!(cursor.count_vals(&keys), 0);
+}
+
+/// The largest number of batches held by a trace while `count` single-update batches are inserted.
+fn max_batches(policy: MergePolicy, count: usize) -> usize {
+    let mut trace = IntegerTrace::new(OperatorInfo::new(0, 0, [].into()), None, None);
+    trace.set_merge_policy(policy);
+    let mut batcher = ValBatcher::<u64,u64,usize,i64>::new(None, 0);
+    let mut max = 0;
+    for time in 0 .. count {
+        batcher.push_container(&mut vec![((time as u64, 0), time, 1)]);
+        trace.insert(batcher.seal::<IntegerBuilder>(Antichain::from_elem(time + 1)));
+        trace.set_physical_compaction(AntichainRef::new(&[time + 1]));
+        let mut batches = 0;
+        trace.map_batches(|_| batches += 1);
+        max = std::cmp::max(max, batches);
+    }
+    max
+}
+
+#[test]
+fn test_merge_policy_bounds_batches() {
+    let count = 10_000;
+    let policy = MergePolicy { min_batch_size: count, effort: 2 };
+    let max = max_batches(policy, count);
+    // merges of the small batches complete promptly, leaving about one batch in each layer.
+    let layers = (count.next_power_of_two().trailing_zeros() + 1) as usize;
+    assert!(max <= layers + 2, "{} batches for {} layers", max, layers);
+}
+
+#[test]
+fn test_estimate_size() {
+    let mut trace = IntegerTrace::new(OperatorInfo::new(0, 0, [].into()), None, None);
+    assert_eq!(trace.estimate_size(), (0, 0, 0));
+
+    // the batches have no keys in common, so the counts do not depend on whether they are merged.
+    trace.insert(Rc::new(SortedBuilder::from_sorted(vec![
+        ((1, 2), 0, 1),
+        ((2, 3), 0, 1),
+        ((2, 3), 1, -1),
+        ((2, 4), 1, 1),
+    ], Description::new(Antichain::from_elem(0), Antichain::from_elem(2), Antichain::from_elem(0)))));
+    trace.insert(Rc::new(SortedBuilder::from_sorted(vec![
+        ((3, 5), 2, 1),
+        ((4, 6), 2, 1),
+        ((4, 6), 3, 2),
+    ], Description::new(Antichain::from_elem(2), Antichain::from_elem(4), Antichain::from_elem(0)))));
+
+    assert_eq!(trace.estimate_size(), (4, 5, 7));
+}
+
+#[test]
+fn test_cursor_join() {
+
+    use differential_dataflow::trace::cursor::CursorJoin;
+
+    type NamedBuilder = OrdValBuilder<Vector<((u64, String), usize, i64)>, Vec<((u64, String), usize, i64)>>;
+
+    // keys 1 and 4 are only in the first batch, keys 3 and 5 only in the second.
+    let batch1 = SortedBuilder::from_sorted(vec![
+        ((1, 10), 0, 1),
+        ((2, 20), 0, 1),
+        ((2, 21), 1, 1),
+        ((4, 40), 0, 1),
+        ((6, 60), 1, 1),
+    ], Description::new(Antichain::from_elem(0), Antichain::from_elem(2), Antichain::from_elem(0)));
+    let batch2 = NamedBuilder::from_sorted(vec![
+        ((2, "a".to_string()), 0, 1),
+        ((2, "b".to_string()), 0, -1),
+        ((3, "c".to_string()), 1, 1),
+        ((5, "d".to_string()), 0, 1),
+        ((6, "e".to_string()), 1, 2),
+    ], Description::new(Antichain::from_elem(0), Antichain::from_elem(2), Antichain::from_elem(0)));
+
+    let mut cursor = CursorJoin::new(batch1.cursor(), batch2.cursor(), &batch1, &batch2);
+    let mut pairs = Vec::new();
+    while cursor.key_valid(&batch1, &batch2) {
+        let key = *cursor.key(&batch1);
+        cursor.map_val_pairs(&batch1, &batch2, |val1, val2| pairs.push((key, *val1, val2.clone())));
+        cursor.step_key(&batch1, &batch2);
+    }
+    assert_eq!(pairs, vec![
+        (2, 20, "a".to_string()),
+        (2, 20, "b".to_string()),
+        (2, 21, "a".to_string()),
+        (2, 21, "b".to_string()),
+        (6, 60, "e".to_string()),
+    ]);
+
+    // seeking a key absent from one side lands on the next common key, and the times are available.
+    cursor.rewind_keys(&batch1, &batch2);
+    assert_eq!(cursor.get_key(&batch1, &batch2), Some(&2));
+    cursor.seek_key(&batch1, &batch2, &3);
+    assert_eq!(cursor.get_key(&batch1, &batch2), Some(&6));
+    let (cursor1, cursor2) = cursor.cursors();
+    let mut diffs = Vec::new();
+    cursor1.map_times(&batch1, |time, diff| diffs.push((*time, *diff)));
+    cursor2.map_times(&batch2, |time, diff| diffs.push((*time, *diff)));
+    assert_eq!(diffs, vec![(1, 1), (1, 2)]);
+
+    cursor.seek_key(&batch1, &batch2, &7);
+    assert!(!cursor.key_valid(&batch1, &batch2));
+    cursor.step_key(&batch1, &batch2);
+    assert!(!cursor.key_valid(&batch1, &batch2));
+}
+
+/// Wraps a cursor, counting the calls that move it between keys.
+struct CountingCursor<C> {
+    cursor: C,
+    moves: Rc<Cell<usize>>,
+}
+
+impl<C: Cursor> Cursor for CountingCursor<C> {
+    type Key<'a> = C::Key<'a>;
+    type Val<'a> = C::Val<'a>;
+    type Time = C::Time;
+    type TimeGat<'a> = C::TimeGat<'a>;
+    type Diff = C::Diff;
+    type DiffGat<'a> = C::DiffGat<'a>;
+    type Storage = C::Storage;
+
+    fn key_valid(&self, storage: &Self::Storage) -> bool { self.cursor.key_valid(storage) }
+    fn val_valid(&self, storage: &Self::Storage) -> bool { self.cursor.val_valid(storage) }
+    fn key<'a>(&self, storage: &'a Self::Storage) -> Self::Key<'a> { self.cursor.key(storage) }
+    fn val<'a>(&self, storage: &'a Self::Storage) -> Self::Val<'a> { self.cursor.val(storage) }
+    fn map_times<L: FnMut(Self::TimeGat<'_>, Self::DiffGat<'_>)>(&mut self, storage: &Self::Storage, logic: L) {
+        self.cursor.map_times(storage, logic)
+    }
+    fn step_key(&mut self, storage: &Self::Storage) { self.moves.set(self.moves.get() + 1); self.cursor.step_key(storage) }
+    fn seek_key(&mut self, storage: &Self::Storage, key: Self::Key<'_>) { self.moves.set(self.moves.get() + 1); self.cursor.seek_key(storage, key) }
+    fn step_val(&mut self, storage: &Self::Storage) { self.cursor.step_val(storage) }
+    fn seek_val(&mut self, storage: &Self::Storage, val: Self::Val<'_>) { self.cursor.seek_val(storage, val) }
+    fn rewind_keys(&mut self, storage: &Self::Storage) { self.cursor.rewind_keys(storage) }
+    fn rewind_vals(&mut self, storage: &Self::Storage) { self.cursor.rewind_vals(storage) }
+}
+
+/// Wraps a trace, counting the calls that move its cursors between keys.
+#[derive(Clone)]
+struct CountingTrace<Tr> {
+    trace: Tr,
+    moves: Rc<Cell<usize>>,
+}
+
+impl<Tr: TraceReader> TraceReader for CountingTrace<Tr> {
+    type Key<'a> = Tr::Key<'a>;
+    type Val<'a> = Tr::Val<'a>;
+    type Time = Tr::Time;
+    type TimeGat<'a> = Tr::TimeGat<'a>;
+    type Diff = Tr::Diff;
+    type DiffGat<'a> = Tr::DiffGat<'a>;
+    type Batch = Tr::Batch;
+    type Storage = Tr::Storage;
+    type Cursor = CountingCursor<Tr::Cursor>;
+
+    fn cursor_through(&mut self, upper: AntichainRef<Self::Time>) -> Option<(Self::Cursor, Self::Storage)> {
+        let moves = Rc::clone(&self.moves);
+        self.trace.cursor_through(upper).map(|(cursor, storage)| (CountingCursor { cursor, moves }, storage))
+    }
+    fn set_logical_compaction(&mut self, frontier: AntichainRef<Self::Time>) { self.trace.set_logical_compaction(frontier) }
+    fn get_logical_compaction(&mut self) -> AntichainRef<Self::Time> { self.trace.get_logical_compaction() }
+    fn set_physical_compaction(&mut self, frontier: AntichainRef<Self::Time>) { self.trace.set_physical_compaction(frontier) }
+    fn get_physical_compaction(&mut self) -> AntichainRef<Self::Time> { self.trace.get_physical_compaction() }
+    fn map_batches<F: FnMut(&Self::Batch)>(&self, f: F) { self.trace.map_batches(f) }
+}
+
+#[test]
+fn test_sparse_join_seeks() {
+
+    use differential_dataflow::trace::cursor::CursorJoin;
+
+    // a large batch with a thousand keys, and a small batch with four keys of which three are shared.
+    let large = SortedBuilder::from_sorted(
+        (0 .. 1000).map(|key| ((key, key * 10), 0, 1)).collect(),
+        Description::new(Antichain::from_elem(0), Antichain::from_elem(1), Antichain::from_elem(0)),
+    );
+    let small = SortedBuilder::from_sorted(
+        vec![((100, 1), 0, 1), ((500, 5), 0, 1), ((999, 9), 0, 1), ((1001, 0), 0, 1)],
+        Description::new(Antichain::from_elem(0), Antichain::from_elem(1), Antichain::from_elem(0)),
+    );
+
+    // the keys of the large cursor are visited by seeking past those absent from the small cursor, as in `join_core`.
+    let moves = Rc::new(Cell::new(0));
+    let large_cursor = CountingCursor { cursor: large.cursor(), moves: Rc::clone(&moves) };
+    let mut cursor = CursorJoin::new(large_cursor, small.cursor(), &large, &small);
+    let mut pairs = Vec::new();
+    while cursor.key_valid(&large, &small) {
+        let key = *cursor.key(&large);
+        cursor.map_val_pairs(&large, &small, |val1, val2| pairs.push((key, *val1, *val2)));
+        cursor.step_key(&large, &small);
+    }
+    assert_eq!(pairs, vec![(100, 1000, 1), (500, 5000, 5), (999, 9990, 9)]);
+
+    assert!(moves.get() <= 2 * 4, "moved {} times", moves.get());
+}
+
+#[test]
+fn test_sparse_join_core_seeks() {
+
+    use timely::dataflow::operators::{Capture, capture::Extract};
+    use differential_dataflow::input::Input;
+    use differential_dataflow::operators::arrange::{Arranged, ArrangeByKey};
+
+    let (moves, results) = timely::execute_directly(|worker| {
+
+        let moves = Rc::new(Cell::new(0));
+
+        let (mut large, mut sparse, probe, captured) = worker.dataflow::<u64,_,_>(|scope| {
+            let (large_input, large) = scope.new_collection::<(u64, u64), isize>();
+            let (sparse_input, sparse) = scope.new_collection::<(u64, u64), isize>();
+            // the join reads the trace of the large arrangement through a counting wrapper.
+            let large = large.arrange_by_key();
+            let large = Arranged { stream: large.stream, trace: CountingTrace { trace: large.trace, moves: Rc::clone(&moves) } };
+            let joined = sparse.arrange_by_key().join_core(&large, |key, val1, val2| Some((*key, *val1, *val2)));
+            (large_input, sparse_input, joined.probe(), joined.inner.capture())
+        });
+
+        // a large collection with a thousand keys, and later a sparse collection with four keys of which three are shared.
+        for key in 0 .. 1000 { large.insert((key, key * 10)); }
+        large.advance_to(1);
+        sparse.advance_to(1);
+        large.flush();
+        sparse.flush();
+        worker.step_while(|| probe.less_than(&1));
+
+        moves.set(0);
+        for (key, val) in [(100, 1), (500, 5), (999, 9), (1001, 0)] { sparse.insert((key, val)); }
+        large.advance_to(2);
+        sparse.advance_to(2);
+        large.flush();
+        sparse.flush();
+        worker.step_while(|| probe.less_than(&2));
+
+        (moves.get(), captured)
+    });
+
+    let mut results = results.extract().into_iter().flat_map(|(_, list)| list).collect::<Vec<_>>();
+    differential_dataflow::consolidation::consolidate_updates(&mut results);
+    assert_eq!(results, vec![((100, 1, 1000), 1, 1), ((500, 5, 5000), 1, 1), ((999, 9, 9990), 1, 1)]);
+
+    // the keys of the large trace absent from the sparse batch are sought past, rather than stepped through.
+    assert!(moves <= 2 * 4, "moved {} times", moves);
+}
@@ -16,7 +16,15 @@ use tpchlike::{Collections, types::*, queries};
 
 fn main() {
 
-    timely::execute_from_args(std::env::args().skip(4), |worker| {
+    // `--stop-at <round>` ends the measurement once the probe passes `round`, leaving remaining input unsent.
+    let mut args = std::env::args().collect::<Vec<_>>();
+    let stop_at = args.iter().position(|x| x == "--stop-at").map(|position| {
+        let round = args.get(position + 1).expect("--stop-at requires a round").parse::<usize>().expect("--stop-at requires an integer round");
+        args.drain(position .. position + 2);
+        round
+    });
+
+    timely::execute_from_args(args.clone().into_iter().skip(4), move |worker| {
 
         let index = worker.index();
         let peers = worker.peers();
@@ -24,11 +32,11 @@ fn main() {
         let core_ids = core_affinity::get_core_ids().unwrap();
         core_affinity::set_for_current(core_ids[index]);
 
-        let prefix = ::std::env::args().nth(1).unwrap();;
-        let logical_batch = ::std::env::args().nth(2).unwrap().parse::<usize>().unwrap();
-        let physical_batch = ::std::env::args().nth(3).unwrap().parse::<usize>().unwrap();
-        let query: usize = ::std::env::args().nth(4).unwrap().parse().unwrap();
-        let seal: bool = ::std::env::args().any(|x| x == "seal-inputs");
+        let prefix = args[1].clone();
+        let logical_batch = args[2].parse::<usize>().unwrap();
+        let physical_batch = args[3].parse::<usize>().unwrap();
+        let query: usize = args[4].parse().unwrap();
+        let seal: bool = args.iter().any(|x| x == "seal-inputs");
 
         let (mut inputs, probe, used) = worker.dataflow::<usize,_,_>(move |scope| {
 
@@ -95,15 +103,8 @@ fn main() {
         let mut regions = if used[6] { load::<Region>(prefix.as_str(), "region.tbl", index, peers, logical_batch, physical_batch, 6) } else { Vec::new() };
         let mut suppliers = if used[7] { load::<Supplier>(prefix.as_str(), "supplier.tbl", index, peers, logical_batch, physical_batch, 7) } else { Vec::new() };
 
+        // Counts tuples as they are sent, as an early stop may leave some unsent.
         let mut tuples = 0usize;
-        tuples += customers.iter().map(|x| x.len()).sum::<usize>();
-        tuples += lineitems.iter().map(|x| x.len()).sum::<usize>();
-        tuples += nations.iter().map(|x| x.len()).sum::<usize>();
-        tuples += orders.iter().map(|x| x.len()).sum::<usize>();
-        tuples += parts.iter().map(|x| x.len()).sum::<usize>();
-        tuples += partsupps.iter().map(|x| x.len()).sum::<usize>();
-        tuples += regions.iter().map(|x| x.len()).sum::<usize>();
-        tuples += suppliers.iter().map(|x| x.len()).sum::<usize>();
 
         // Synchronize before starting the timer.
         let next_round = 1;
@@ -124,14 +125,14 @@ fn main() {
         while customers.len() > 0 || lineitems.len() > 0 || nations.len() > 0 || orders.len() > 0 || parts.len() > 0 || partsupps.len() > 0 || regions.len() > 0 || suppliers.len() > 0 {
 
             // introduce physical batch of data for each input with remaining data.
-            if let Some(mut data) = customers.pop() { inputs.0.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.0 = None; } }
-            if let Some(mut data) = lineitems.pop() { inputs.1.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.1 = None; } }
-            if let Some(mut data) = nations.pop() { inputs.2.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.2 = None; } }
-            if let Some(mut data) = orders.pop() { inputs.3.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.3 = None; } }
-            if let Some(mut data) = parts.pop() { inputs.4.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.4 = None; } }
-            if let Some(mut data) = partsupps.pop() { inputs.5.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.5 = None; } }
-            if let Some(mut data) = regions.pop() { inputs.6.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.6 = None; } }
-            if let Some(mut data) = suppliers.pop() { inputs.7.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.7 = None; } }
+            if let Some(mut data) = customers.pop() { tuples += data.len(); inputs.0.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.0 = None; } }
+            if let Some(mut data) = lineitems.pop() { tuples += data.len(); inputs.1.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.1 = None; } }
+            if let Some(mut data) = nations.pop() { tuples += data.len(); inputs.2.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.2 = None; } }
+            if let Some(mut data) = orders.pop() { tuples += data.len(); inputs.3.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.3 = None; } }
+            if let Some(mut data) = parts.pop() { tuples += data.len(); inputs.4.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.4 = None; } }
+            if let Some(mut data) = partsupps.pop() { tuples += data.len(); inputs.5.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.5 = None; } }
+            if let Some(mut data) = regions.pop() { tuples += data.len(); inputs.6.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.6 = None; } }
+            if let Some(mut data) = suppliers.pop() { tuples += data.len(); inputs.7.as_mut().map(|x| x.send_batch(&mut data)); } else { if seal { inputs.7 = None; } }
 
             // catch all inputs up to the same (next) round.
             let next_round = 1 + 8 * (round + 1) * physical_batch;
@@ -147,29 +148,47 @@ fn main() {
             let time = next_round;
             worker.step_while(|| probe.less_than(&time));
             round += 1;
+
+            // stop once the probe has passed the target round.
+            if stop_at.map(|stop| !probe.less_than(&stop)).unwrap_or(false) {
+                break;
+            }
         }
 
-        // Finish outstanding work before stopping the timer.
-        let next_round = usize::max_value();
-        inputs.0.as_mut().map(|x| x.advance_to(next_round));
-        inputs.1.as_mut().map(|x| x.advance_to(next_round));
-        inputs.2.as_mut().map(|x| x.advance_to(next_round));
-        inputs.3.as_mut().map(|x| x.advance_to(next_round));
-        inputs.4.as_mut().map(|x| x.advance_to(next_round));
-        inputs.5.as_mut().map(|x| x.advance_to(next_round));
-        inputs.6.as_mut().map(|x| x.advance_to(next_round));
-        inputs.7.as_mut().map(|x| x.advance_to(next_round));
+        let elapsed = if stop_at.is_some() {
+            // Measure up to the stop, and close the inputs without sending the remaining data.
+            // Closing the inputs releases their capabilities, so that the dataflow can complete.
+            let elapsed = timer.elapsed();
+            inputs = (None, None, None, None, None, None, None, None);
+            worker.step_while(|| !probe.done());
+            elapsed
+        }
+        else {
+            // Finish outstanding work before stopping the timer.
+            let next_round = usize::max_value();
+            inputs.0.as_mut().map(|x| x.advance_to(next_round));
+            inputs.1.as_mut().map(|x| x.advance_to(next_round));
+            inputs.2.as_mut().map(|x| x.advance_to(next_round));
+            inputs.3.as_mut().map(|x| x.advance_to(next_round));
+            inputs.4.as_mut().map(|x| x.advance_to(next_round));
+            inputs.5.as_mut().map(|x| x.advance_to(next_round));
+            inputs.6.as_mut().map(|x| x.advance_to(next_round));
+            inputs.7.as_mut().map(|x| x.advance_to(next_round));
 
-        let time = next_round;
-        worker.step_while(|| probe.less_than(&time));
+            let time = next_round;
+            worker.step_while(|| probe.less_than(&time));
+            timer.elapsed()
+        };
 
         let query_name = if query < 10 { format!("q0{}", query) } else { format!("q{}", query) };
-        let elapsed = timer.elapsed();
         let nanos = elapsed.as_secs() * 1000000000 + elapsed.subsec_nanos() as u64;
         if index == 0 {
             let rate = ((peers * tuples) as f64) / (nanos as f64 / 1000000000.0);
             // Query, Logical, Physical, Workers, Rate, Time
             println!("{}\t{}\t{}\t{}\t{}\t{}", query_name, logical_batch, physical_batch, peers, rate, nanos);
+            if let Some(stop) = stop_at {
+                println!("stopped at round {} after {} rounds, with {} tuples sent by worker {}", stop, round, tuples, index);
+            }
             // println!("query: {}, elapsed: {:?}, tuples: {:?}, rate: {:?}", query_name, timer.elapsed(), peers * tuples, ((peers * tuples) as f64) / (nanos as f64 / 1000000000.0));
         }
 